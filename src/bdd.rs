@@ -0,0 +1,488 @@
+//! Reduced ordered binary decision diagrams.
+
+use std::collections::HashMap;
+
+/// A node of a diagram; terminals are [`Bdd::FALSE`] and [`Bdd::TRUE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeRef(u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Node {
+    var: usize,
+    low: NodeRef,
+    high: NodeRef,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
+
+impl Op {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Op::And => a & b,
+            Op::Or => a | b,
+            Op::Xor => a ^ b,
+        }
+    }
+}
+
+/// A collection of diagrams over the same variables, sharing nodes.
+///
+/// Variable `0` is the topmost one.
+#[derive(Debug, Clone)]
+pub struct Bdd {
+    num_vars: usize,
+    nodes: Vec<Node>,
+    unique: HashMap<Node, NodeRef>,
+    cache: HashMap<(Op, NodeRef, NodeRef), NodeRef>,
+}
+
+impl Bdd {
+    pub const FALSE: NodeRef = NodeRef(0);
+    pub const TRUE: NodeRef = NodeRef(1);
+
+    pub fn new(num_vars: usize) -> Self {
+        let terminal = Node { var: num_vars, low: Self::FALSE, high: Self::FALSE };
+        Self {
+            num_vars,
+            nodes: vec![terminal, terminal],
+            unique: HashMap::new(),
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Variable tested by the node; equals to [`Bdd::num_vars`] for terminals.
+    pub fn level(&self, node: NodeRef) -> usize {
+        self.nodes[node.0 as usize].var
+    }
+
+    /// Children of the node: where the tested variable is false and where it's true.
+    pub fn children(&self, node: NodeRef) -> (NodeRef, NodeRef) {
+        let Node { low, high, .. } = self.nodes[node.0 as usize];
+        (low, high)
+    }
+
+    fn make(&mut self, var: usize, low: NodeRef, high: NodeRef) -> NodeRef {
+        if low == high {
+            return low;
+        }
+        let node = Node { var, low, high };
+        if let Some(&existing) = self.unique.get(&node) {
+            return existing;
+        }
+        let id = NodeRef(self.nodes.len() as u32);
+        self.nodes.push(node);
+        self.unique.insert(node, id);
+        id
+    }
+
+    /// Diagram that is true iff variable is true.
+    pub fn var(&mut self, var: usize) -> NodeRef {
+        assert!(var < self.num_vars, "variable should be in range");
+        self.make(var, Self::FALSE, Self::TRUE)
+    }
+
+    pub fn constant(&self, value: bool) -> NodeRef {
+        if value { Self::TRUE } else { Self::FALSE }
+    }
+
+    pub fn not(&mut self, f: NodeRef) -> NodeRef {
+        self.apply(Op::Xor, f, Self::TRUE)
+    }
+
+    pub fn and(&mut self, f: NodeRef, g: NodeRef) -> NodeRef {
+        self.apply(Op::And, f, g)
+    }
+
+    pub fn or(&mut self, f: NodeRef, g: NodeRef) -> NodeRef {
+        self.apply(Op::Or, f, g)
+    }
+
+    pub fn xor(&mut self, f: NodeRef, g: NodeRef) -> NodeRef {
+        self.apply(Op::Xor, f, g)
+    }
+
+    fn apply(&mut self, op: Op, f: NodeRef, g: NodeRef) -> NodeRef {
+        let is_terminal = |node: NodeRef| node == Self::FALSE || node == Self::TRUE;
+        if is_terminal(f) && is_terminal(g) {
+            return self.constant(op.apply(f == Self::TRUE, g == Self::TRUE));
+        }
+        if let Some(&result) = self.cache.get(&(op, f, g)) {
+            return result;
+        }
+        let var = self.level(f).min(self.level(g));
+        let (f_low, f_high) = self.cofactors(f, var);
+        let (g_low, g_high) = self.cofactors(g, var);
+        let low = self.apply(op, f_low, g_low);
+        let high = self.apply(op, f_high, g_high);
+        let result = self.make(var, low, high);
+        self.cache.insert((op, f, g), result);
+        result
+    }
+
+    /// Children of the node with respect to provided variable, which shouldn't be below it.
+    fn cofactors(&self, node: NodeRef, var: usize) -> (NodeRef, NodeRef) {
+        match self.level(node) == var {
+            true => self.children(node),
+            false => (node, node),
+        }
+    }
+
+    /// Existentially quantifies every variable for which `quantified` is true.
+    pub fn exists(&mut self, f: NodeRef, quantified: &[bool]) -> NodeRef {
+        let mut memo = HashMap::new();
+        self.exists_memo(f, quantified, &mut memo)
+    }
+
+    fn exists_memo(
+        &mut self,
+        f: NodeRef,
+        quantified: &[bool],
+        memo: &mut HashMap<NodeRef, NodeRef>,
+    ) -> NodeRef {
+        let var = self.level(f);
+        if var == self.num_vars {
+            return f;
+        }
+        if let Some(&result) = memo.get(&f) {
+            return result;
+        }
+        let (low, high) = self.children(f);
+        let low = self.exists_memo(low, quantified, memo);
+        let high = self.exists_memo(high, quantified, memo);
+        let result = match quantified[var] {
+            true => self.or(low, high),
+            false => self.make(var, low, high),
+        };
+        memo.insert(f, result);
+        result
+    }
+
+    /// Number of assignments of `counted` variables that satisfy the diagram, or `u128::MAX` if there are more.
+    ///
+    /// Diagram should not depend on variables that aren't counted.
+    pub fn count(&self, f: NodeRef, counted: &[bool]) -> u128 {
+        Counter::new(self, counted).count_at(0, f)
+    }
+
+    /// Every path from the node to a terminal, in lexicographic order.
+    ///
+    /// A path is given by values of tested variables, or `None` for skipped ones, and the terminal.
+    pub fn paths(&self, f: NodeRef) -> Vec<(Vec<Option<bool>>, bool)> {
+        let mut paths = Vec::new();
+        let mut stack = vec![(f, vec![None; self.num_vars])];
+        while let Some((node, mut path)) = stack.pop() {
+            let level = self.level(node);
+            if level == self.num_vars {
+                paths.push((path, node == Self::TRUE));
+                continue;
+            }
+            let (low, high) = self.children(node);
+            let mut high_path = path.clone();
+            high_path[level] = Some(true);
+            path[level] = Some(false);
+            stack.push((high, high_path));
+            stack.push((low, path));
+        }
+        paths
+    }
+
+    /// Value of the diagram when variable `i` is `values[i]`.
+    pub fn eval(&self, f: NodeRef, values: &[bool]) -> bool {
+        let mut node = f;
+        while self.level(node) < self.num_vars {
+            let (low, high) = self.children(node);
+            node = if values[self.level(node)] { high } else { low };
+        }
+        node == Self::TRUE
+    }
+
+    /// Number of satisfying assignments of `counted` variables lexicographically before `values`.
+    ///
+    /// Diagram should not depend on variables that aren't counted.
+    pub fn rank(&self, f: NodeRef, counted: &[bool], values: &[bool]) -> u128 {
+        let mut counter = Counter::new(self, counted);
+        let mut node = f;
+        let mut rank = 0;
+        for level in (0..self.num_vars).filter(|&level| counted[level]) {
+            let (low, high) = self.cofactors(node, level);
+            if values[level] {
+                rank += counter.count_at(level + 1, low);
+                node = high;
+            } else {
+                node = low;
+            }
+        }
+        rank
+    }
+
+    /// Satisfying assignment of `counted` variables with provided rank, the inverse of [`Bdd::rank`].
+    ///
+    /// Variables that aren't counted are false, and the rank should be less than the number of solutions.
+    pub fn select(&self, f: NodeRef, counted: &[bool], rank: u128) -> Vec<bool> {
+        let mut counter = Counter::new(self, counted);
+        let mut node = f;
+        let mut rank = rank;
+        let mut values = vec![false; self.num_vars];
+        for level in (0..self.num_vars).filter(|&level| counted[level]) {
+            let (low, high) = self.cofactors(node, level);
+            let count = counter.count_at(level + 1, low);
+            if rank < count {
+                node = low;
+            } else {
+                rank -= count;
+                values[level] = true;
+                node = high;
+            }
+        }
+        values
+    }
+
+    /// Satisfying assignments of `counted` variables, in lexicographic order.
+    ///
+    /// Diagram should not depend on variables that aren't counted.
+    pub fn solutions<'a>(&'a self, f: NodeRef, counted: &'a [bool]) -> Solutions<'a> {
+        let mut stack = Vec::new();
+        if f != Self::FALSE {
+            stack.push((0, f, Vec::new()));
+        }
+        Solutions { counter: Counter::new(self, counted), stack }
+    }
+}
+
+/// Memoized counting of satisfying assignments.
+#[derive(Debug, Clone)]
+struct Counter<'a> {
+    bdd: &'a Bdd,
+    counted: &'a [bool],
+    /// Number of counted variables before each level.
+    prefix: Vec<u32>,
+    memo: HashMap<NodeRef, u128>,
+}
+
+impl<'a> Counter<'a> {
+    fn new(bdd: &'a Bdd, counted: &'a [bool]) -> Self {
+        let prefix = std::iter::once(0)
+            .chain(counted.iter().scan(0, |acc, &counted| {
+                *acc += counted as u32;
+                Some(*acc)
+            }))
+            .collect();
+        Self { bdd, counted, prefix, memo: HashMap::new() }
+    }
+
+    /// Number of solutions of the node over counted variables starting from its level.
+    fn count_node(&mut self, node: NodeRef) -> u128 {
+        if node == Bdd::FALSE {
+            return 0;
+        }
+        if node == Bdd::TRUE {
+            return 1;
+        }
+        if let Some(&count) = self.memo.get(&node) {
+            return count;
+        }
+        let var = self.bdd.level(node);
+        let (low, high) = self.bdd.children(node);
+        let count = self.count_at(var + 1, low).saturating_add(self.count_at(var + 1, high));
+        self.memo.insert(node, count);
+        count
+    }
+
+    /// Number of solutions of the node over counted variables starting from provided level,
+    /// saturating at `u128::MAX`.
+    fn count_at(&mut self, level: usize, node: NodeRef) -> u128 {
+        let skipped = self.prefix[self.bdd.level(node)] - self.prefix[level];
+        let count = self.count_node(node);
+        match count.checked_shl(skipped) {
+            Some(shifted) if shifted >> skipped == count => shifted,
+            _ => u128::MAX,
+        }
+    }
+}
+
+/// Lazy enumeration of satisfying assignments, created by [`Bdd::solutions`].
+///
+/// Skipping with [`Iterator::nth`] doesn't visit skipped solutions.
+#[derive(Debug, Clone)]
+pub struct Solutions<'a> {
+    counter: Counter<'a>,
+    /// Subdiagrams yet to be visited, with the level they start at and values of counted variables above.
+    stack: Vec<(usize, NodeRef, Vec<bool>)>,
+}
+
+impl<'a> Solutions<'a> {
+    /// Splits the topmost entry by the value of its first variable, pushing children in reverse order.
+    fn expand(&mut self, level: usize, node: NodeRef, prefix: Vec<bool>) {
+        let bdd = self.counter.bdd;
+        let (low, high) = match bdd.level(node) == level {
+            true => bdd.children(node),
+            false => (node, node),
+        };
+        if !self.counter.counted[level] {
+            // Diagram doesn't depend on it, so it's enough to follow one branch.
+            self.stack.push((level + 1, low, prefix));
+            return;
+        }
+        for (value, child) in [(true, high), (false, low)] {
+            if child != Bdd::FALSE {
+                let mut prefix = prefix.clone();
+                prefix.push(value);
+                self.stack.push((level + 1, child, prefix));
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Solutions<'a> {
+    type Item = Vec<bool>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((level, node, prefix)) = self.stack.pop() {
+            if level == self.counter.bdd.num_vars() {
+                return Some(prefix);
+            }
+            self.expand(level, node, prefix);
+        }
+        None
+    }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        while let Some((level, node, prefix)) = self.stack.pop() {
+            let count = self.counter.count_at(level, node);
+            if count <= n as u128 {
+                n -= count as usize;
+                continue;
+            }
+            if level == self.counter.bdd.num_vars() {
+                return Some(prefix);
+            }
+            self.expand(level, node, prefix);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod bdd_tests {
+    use super::Bdd;
+
+    #[test]
+    fn bdd_reduced() {
+        let mut bdd = Bdd::new(2);
+        let a = bdd.var(0);
+        let not_a = bdd.not(a);
+        assert_eq!(Bdd::TRUE, bdd.or(a, not_a));
+        assert_eq!(Bdd::FALSE, bdd.and(a, not_a));
+        let b = bdd.var(1);
+        let ab = bdd.and(a, b);
+        let ba = bdd.and(b, a);
+        assert_eq!(ab, ba);
+    }
+
+    #[test]
+    fn bdd_count() {
+        let mut bdd = Bdd::new(3);
+        let (a, c) = (bdd.var(0), bdd.var(2));
+        let f = bdd.or(a, c);
+        assert_eq!(6, bdd.count(f, &[true; 3]));
+        assert_eq!(8, bdd.count(Bdd::TRUE, &[true; 3]));
+        assert_eq!(0, bdd.count(Bdd::FALSE, &[true; 3]));
+
+        let projected = bdd.exists(f, &[false, false, true]);
+        assert_eq!(Bdd::TRUE, projected);
+        assert_eq!(2, bdd.count(projected, &[true, false, false]));
+
+        let mut bdd = Bdd::new(130);
+        let a = bdd.var(0);
+        assert_eq!(u128::MAX, bdd.count(Bdd::TRUE, &[true; 130]));
+        assert_eq!(u128::MAX, bdd.count(a, &[true; 130]));
+        let mut counted = [true; 130];
+        counted[127..].fill(false);
+        assert_eq!(1 << 127, bdd.count(Bdd::TRUE, &counted));
+    }
+
+    #[test]
+    fn bdd_solutions() {
+        let mut bdd = Bdd::new(3);
+        let (a, b, c) = (bdd.var(0), bdd.var(1), bdd.var(2));
+        let (not_a, not_b) = (bdd.not(a), bdd.not(b));
+        let (a_not_b, b_not_a) = (bdd.and(a, not_b), bdd.and(b, not_a));
+        let ab = bdd.or(a_not_b, b_not_a);
+        let f = bdd.or(ab, c);
+        let all = bdd.solutions(f, &[true; 3]).collect::<Vec<_>>();
+        let expected = (0..8u8)
+            .map(|n| vec![n & 4 != 0, n & 2 != 0, n & 1 != 0])
+            .filter(|v| v[0] ^ v[1] | v[2])
+            .collect::<Vec<_>>();
+        assert_eq!(expected, all);
+
+        for n in 0..expected.len() + 1 {
+            assert_eq!(expected.get(n).cloned(), bdd.solutions(f, &[true; 3]).nth(n));
+        }
+        let mut iter = bdd.solutions(f, &[true; 3]);
+        assert_eq!(expected.get(1).cloned(), iter.nth(1));
+        assert_eq!(expected.get(4).cloned(), iter.nth(2));
+        assert_eq!(expected.get(5).cloned(), iter.next());
+    }
+
+    #[test]
+    fn bdd_eval_rank_and_select() {
+        let mut bdd = Bdd::new(3);
+        let (a, c) = (bdd.var(0), bdd.var(2));
+        let not_c = bdd.not(c);
+        let f = bdd.and(a, not_c);
+        assert!(bdd.eval(f, &[true, true, false]));
+        assert!(!bdd.eval(f, &[true, true, true]));
+
+        // Solutions are `100` and `110`.
+        assert_eq!(0, bdd.rank(f, &[true; 3], &[false, true, true]));
+        assert_eq!(1, bdd.rank(f, &[true; 3], &[true, true, false]));
+        assert_eq!(2, bdd.rank(f, &[true; 3], &[true, true, true]));
+        assert_eq!(4, bdd.rank(not_c, &[true; 3], &[true, true, true]));
+        assert_eq!(vec![true, true, false], bdd.select(f, &[true; 3], 1));
+        assert_eq!(vec![true, false, false], bdd.select(not_c, &[true; 3], 2));
+        for rank in 0..4 {
+            let values = bdd.select(not_c, &[true; 3], rank);
+            assert_eq!(rank, bdd.rank(not_c, &[true; 3], &values));
+        }
+    }
+
+    #[test]
+    fn bdd_paths() {
+        let mut bdd = Bdd::new(3);
+        let (a, c) = (bdd.var(0), bdd.var(2));
+        let f = bdd.or(a, c);
+        let expected = vec![
+            (vec![Some(false), None, Some(false)], false),
+            (vec![Some(false), None, Some(true)], true),
+            (vec![Some(true), None, None], true),
+        ];
+        assert_eq!(expected, bdd.paths(f));
+        assert_eq!(vec![(vec![None; 3], true)], bdd.paths(Bdd::TRUE));
+    }
+
+    #[test]
+    fn bdd_projected_solutions() {
+        let mut bdd = Bdd::new(3);
+        let (a, b, c) = (bdd.var(0), bdd.var(1), bdd.var(2));
+        let ab = bdd.and(a, b);
+        let (not_ab, not_c) = (bdd.not(ab), bdd.not(c));
+        let (ab_not_c, c_not_ab) = (bdd.and(ab, not_c), bdd.and(c, not_ab));
+        let f = bdd.or(ab_not_c, c_not_ab);
+        let counted = [true, false, true];
+        let projected = bdd.exists(f, &[false, true, false]);
+        let solutions = bdd.solutions(projected, &counted).collect::<Vec<_>>();
+        // `a & b ^ c` with `b` free: `c = 1` is possible for any `a`, `c = 0` only if `a = 1`.
+        assert_eq!(vec![vec![false, true], vec![true, false], vec![true, true]], solutions);
+        assert_eq!(3, bdd.count(projected, &counted));
+    }
+}
@@ -1,38 +1,134 @@
-use bitvec::prelude::*;
+/// Maximal number of variables [`BoolIterator`] supports.
+pub const MAX_WIDTH: u8 = 64;
 
+/// Iterates over all assignments of `width` variables, counting in binary.
+///
+/// Variable `0` is the least significant bit of the row index.
+/// Supports random access with [`Iterator::nth`] and iteration from the back.
+///
+/// It doesn't implement [`ExactSizeIterator`], since 64 variables have more rows than `usize` can hold;
+/// [`BoolIterator::remaining`] gives the exact number of rows left instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BoolIterator {
     width: u8,
-    value: u32,
+    /// Index of the next row from the front.
+    front: u128,
+    /// Index after the next row from the back.
+    back: u128,
 }
 
 impl BoolIterator {
+    /// Creates an iterator over all rows; there are no rows if `width` is zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is more than [`MAX_WIDTH`].
     pub const fn new(width: u8) -> Self {
-        Self { width, value: 0 }
+        assert!(width <= MAX_WIDTH, "at most 64 variables are supported");
+        let back = if width == 0 { 0 } else { 1u128 << width };
+        Self { width, front: 0, back }
+    }
+
+    /// Creates an iterator over all rows of `width` variables, or `None` if there are more than [`MAX_WIDTH`].
+    pub fn with_width(width: usize) -> Option<Self> {
+        let width = u8::try_from(width).ok().filter(|&width| width <= MAX_WIDTH)?;
+        Some(Self::new(width))
+    }
+
+    /// Number of rows left, which may not fit into `usize`.
+    pub const fn remaining(&self) -> u128 {
+        self.back - self.front
+    }
+
+    fn row(&self, index: u128) -> Row {
+        Row { bits: index as u64, width: self.width }
     }
 }
 
 impl Iterator for BoolIterator {
-    type Item = Vec<bool>;
+    type Item = Row;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.width == 0 || self.value >= 2u32.pow(self.width as u32) {
+        if self.front >= self.back {
             return None;
         }
-        let item = self
-            .value
-            .view_bits::<Lsb0>()
-            .iter()
-            .by_vals()
-            .take(self.width as usize)
-            .collect();
-        self.value += 1;
-        Some(item)
+        let row = self.row(self.front);
+        self.front += 1;
+        Some(row)
+    }
+
+    /// Exact, unless there are more rows than `usize` can hold.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining()) {
+            Ok(len) => (len, Some(len)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n as u128).min(self.back);
+        self.next()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if there are more rows left than `usize` can hold.
+    fn count(self) -> usize {
+        usize::try_from(self.remaining()).expect("too many rows to count")
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for BoolIterator {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.row(self.back))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back = self.back.saturating_sub(n as u128).max(self.front);
+        self.next_back()
+    }
+}
+
+/// Values of variables in a single row, packed into a word with variable `i` being bit `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Row {
+    bits: u64,
+    width: u8,
+}
+
+impl Row {
+    /// Value of variable `i`.
+    pub const fn get(self, i: u8) -> bool {
+        self.bits >> i & 1 == 1
+    }
+
+    /// Values of variables, starting with variable `0`.
+    ///
+    /// Iterate it in reverse to get the order of truth table columns.
+    pub fn values(self) -> impl DoubleEndedIterator<Item = bool> + ExactSizeIterator {
+        (0..self.width).map(move |i| self.get(i))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::BoolIterator;
+    use super::{BoolIterator, Row};
+
+    fn to_vec(row: Row) -> Vec<bool> {
+        row.values().collect()
+    }
+
+    fn bits(row: Row) -> u64 {
+        row.bits
+    }
 
     #[test]
     fn bool_iterator_0() {
@@ -42,7 +138,7 @@ mod tests {
 
     #[test]
     fn bool_iterator_1() {
-        let mut iter = BoolIterator::new(1);
+        let mut iter = BoolIterator::new(1).map(to_vec);
         assert_eq!(Some(vec![false]), iter.next());
         assert_eq!(Some(vec![true]), iter.next());
         assert_eq!(None, iter.next());
@@ -50,11 +146,64 @@ mod tests {
 
     #[test]
     fn bool_iterator_2() {
-        let mut iter = BoolIterator::new(2);
+        let mut iter = BoolIterator::new(2).map(to_vec);
         assert_eq!(Some(vec![false, false]), iter.next());
         assert_eq!(Some(vec![true, false]), iter.next());
         assert_eq!(Some(vec![false, true]), iter.next());
         assert_eq!(Some(vec![true, true]), iter.next());
         assert_eq!(None, iter.next());
     }
+
+    #[test]
+    fn bool_iterator_len() {
+        let mut iter = BoolIterator::new(10);
+        assert_eq!((1024, Some(1024)), iter.size_hint());
+        iter.next();
+        iter.next_back();
+        assert_eq!(1022, iter.remaining());
+        assert_eq!(1022, iter.count());
+        assert_eq!(Some(BoolIterator::new(64)), BoolIterator::with_width(64));
+        assert_eq!(None, BoolIterator::with_width(65));
+        assert_eq!(None, BoolIterator::with_width(256));
+    }
+
+    #[test]
+    fn bool_iterator_back() {
+        let mut iter = BoolIterator::new(2).map(bits);
+        assert_eq!(Some(3), iter.next_back());
+        assert_eq!(Some(0), iter.next());
+        assert_eq!(Some(2), iter.next_back());
+        assert_eq!(Some(1), iter.next_back());
+        assert_eq!(None, iter.next_back());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn bool_iterator_nth() {
+        let mut iter = BoolIterator::new(4);
+        assert_eq!(Some(5), iter.nth(5).map(bits));
+        assert_eq!(Some(13), iter.nth_back(2).map(bits));
+        assert_eq!(Some(6), iter.next().map(bits));
+        assert_eq!(None, iter.nth(100));
+        assert_eq!(None, iter.next_back());
+    }
+
+    #[test]
+    fn bool_iterator_64() {
+        let mut iter = BoolIterator::new(64);
+        assert_eq!(1 << 64, iter.remaining());
+        assert_eq!((usize::MAX, None), iter.size_hint());
+        assert_eq!(Some(u64::MAX), iter.next_back().map(bits));
+        assert_eq!(Some(1 << 40), iter.nth(1 << 40).map(bits));
+        let row = iter.next().unwrap();
+        assert_eq!(64, row.values().len());
+        assert!(row.get(0) && row.get(40) && !row.get(63));
+    }
+
+    #[test]
+    fn row_values() {
+        let row = BoolIterator::new(3).nth(6).unwrap();
+        assert_eq!(vec![false, true, true], to_vec(row));
+        assert_eq!(vec![true, true, false], row.values().rev().collect::<Vec<_>>());
+    }
 }
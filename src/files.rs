@@ -0,0 +1,59 @@
+//! Exchanging files and text with the user.
+
+use js_sys::{Array, Function, Reflect, Uint8Array};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{Blob, BlobPropertyBag, File, FileReader, HtmlAnchorElement, Url};
+use yew::Callback;
+
+/// Offers the user to save provided contents as a file.
+pub fn download(filename: &str, mime: &str, contents: &[u8]) {
+    let parts = Array::of1(&Uint8Array::from(contents));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .expect("Blob should be constructible from bytes");
+    let url = Url::create_object_url_with_blob(&blob).expect("Blob URL should be creatable");
+
+    let anchor: HtmlAnchorElement = web_sys::window()
+        .and_then(|window| window.document())
+        .expect("Document should be available")
+        .create_element("a")
+        .expect("Anchor should be creatable")
+        .unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+    let _ = Url::revoke_object_url(&url);
+}
+
+/// Reads the whole file, emitting its contents to the callback once loaded.
+pub fn read(file: &File, callback: Callback<Vec<u8>>) {
+    let reader = FileReader::new().expect("FileReader should be constructible");
+    let onload = Closure::once_into_js({
+        let reader = reader.clone();
+        move || {
+            if let Ok(result) = reader.result() {
+                callback.emit(Uint8Array::new(&result).to_vec());
+            }
+        }
+    });
+    reader.set_onload(Some(onload.unchecked_ref()));
+    let _ = reader.read_as_array_buffer(file);
+}
+
+/// Copies text to the clipboard, if the browser allows it.
+pub fn copy(text: &str) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    // Clipboard API is unstable in `web-sys`, so it's called dynamically.
+    let clipboard = Reflect::get(&window.navigator(), &"clipboard".into());
+    let Ok(clipboard) = clipboard else {
+        return;
+    };
+    if let Ok(write) = Reflect::get(&clipboard, &"writeText".into()) {
+        if let Ok(write) = write.dyn_into::<Function>() {
+            let _ = write.call1(&clipboard, &text.into());
+        }
+    }
+}
@@ -1,40 +1,156 @@
+mod aiger;
+mod blif;
+mod circuit;
+mod compare;
+mod cube;
+mod definitions;
+mod derivation;
+mod dimacs;
+mod expr;
+mod hdl;
+mod minimize;
+mod minterms;
+mod models;
+mod multi;
+mod network;
 mod parse;
+mod pla;
+mod rewrite;
+mod trace;
+mod tree;
+mod tseitin;
+mod variable;
+
+use std::fmt::Display;
 
 use self::parse::PostfixToken;
 
+pub use aiger::{AigerError, AigerFormat};
+pub use blif::BlifError;
+pub use circuit::{Circuit, Gate, GateSet};
+pub use cube::Cube;
+pub use definitions::DefinitionError;
+pub use derivation::{check_derivation, DerivationError, Equality, Verdict};
+pub use dimacs::CnfEncoding;
+pub use hdl::{Hdl, HdlBody};
+pub use minimize::{Cover, MINIMIZATION_LIMIT};
+pub use multi::MultiFunction;
 pub use parse::ParseError;
+pub use pla::PlaError;
+pub use tree::ParseTree;
+pub use variable::Variable;
+
+/// Maximal number of variables for which functions are analysed by enumerating all assignments.
+///
+/// Larger functions are handled by SAT solver and aren't shown as a truth table.
+pub const ENUMERATION_LIMIT: usize = 16;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Function {
-    variables: Vec<char>,
+    variables: Vec<Variable>,
     postfix: Vec<PostfixToken>,
 }
 
 impl Function {
-    pub fn vars(&self) -> &[char] {
+    pub fn vars(&self) -> &[Variable] {
         self.variables.as_slice()
     }
 
-    fn var_index(&self, var: char) -> Option<usize> {
-        self.variables.iter().position(|ch| *ch == var)
+    fn var_index(&self, var: &Variable) -> Option<usize> {
+        self.variables.iter().position(|v| v == var)
     }
 
     pub fn eval(&self, vars: &[bool]) -> Option<bool> {
+        self.eval_by(|var| vars.get(self.var_index(var)?).copied())
+    }
+
+    /// Evaluates the function, looking up the value of each variable with provided closure.
+    fn eval_by(&self, value_of: impl Fn(&Variable) -> Option<bool>) -> Option<bool> {
+        self.eval_observed(value_of, |_, _| {})
+    }
+
+    /// Evaluates the function like [`Function::eval_by`],
+    /// calling `observe` with the index of every postfix token and the stack after it.
+    fn eval_observed(
+        &self,
+        value_of: impl Fn(&Variable) -> Option<bool>,
+        mut observe: impl FnMut(usize, &[bool]),
+    ) -> Option<bool> {
         let mut stack = Vec::<bool>::with_capacity(16);
-        for token in self.postfix.iter() {
+        for (index, token) in self.postfix.iter().enumerate() {
             let val = match token {
                 PostfixToken::And => stack.pop().unwrap() & stack.pop().unwrap(),
                 PostfixToken::Or => stack.pop().unwrap() | stack.pop().unwrap(),
+                PostfixToken::Xor => stack.pop().unwrap() ^ stack.pop().unwrap(),
                 PostfixToken::Not => !stack.pop().unwrap(),
                 PostfixToken::Const(val) => *val,
-                PostfixToken::Var(ch) => vars[self.var_index(*ch)?],
+                PostfixToken::Var(var) => value_of(var)?,
             };
             stack.push(val);
+            observe(index, &stack);
         }
         stack.pop()
     }
 }
 
+/// Writes the function in infix notation, using as few parentheses as possible.
+impl Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        /// Precedence of the topmost operator of an expression.
+        const OR: u8 = 0;
+        const XOR: u8 = 1;
+        const AND: u8 = 2;
+        const ATOM: u8 = 3;
+
+        let wrap = |(expr, precedence): (String, u8), min: u8| match precedence < min {
+            true => format!("({expr})"),
+            false => expr,
+        };
+        let mut stack = Vec::<(String, u8)>::with_capacity(16);
+        for token in self.postfix.iter() {
+            let entry = match token {
+                PostfixToken::And | PostfixToken::Or | PostfixToken::Xor => {
+                    let rhs = stack.pop().unwrap();
+                    let lhs = stack.pop().unwrap();
+                    let (op, precedence) = match token {
+                        PostfixToken::And => ('&', AND),
+                        PostfixToken::Xor => ('^', XOR),
+                        _ => ('|', OR),
+                    };
+                    let (lhs, rhs) = (wrap(lhs, precedence), wrap(rhs, precedence));
+                    (format!("{lhs} {op} {rhs}"), precedence)
+                }
+                PostfixToken::Not => (format!("!{}", wrap(stack.pop().unwrap(), ATOM)), ATOM),
+                PostfixToken::Var(var) => (var.to_string(), ATOM),
+                PostfixToken::Const(val) => ((*val as u8).to_string(), ATOM),
+            };
+            stack.push(entry);
+        }
+        let (expr, _) = stack.pop().unwrap_or_default();
+        f.write_str(&expr)
+    }
+}
+
+#[cfg(test)]
+mod display_tests {
+    use super::Function;
+
+    #[test]
+    fn display_roundtrip() {
+        let formulas = ["x", "!0", "x & y | z", "x & (y | z)", "!(x | y) & !!z", "a | b & !(c | 1)", "a & b ^ !c | d"];
+        for formula in formulas.into_iter().chain(["(a | b) ^ c & d", "!(a ^ b)"]) {
+            let func = Function::parse(formula).unwrap();
+            assert_eq!(formula, func.to_string());
+        }
+    }
+
+    #[test]
+    fn display_normalizes() {
+        let func = Function::parse("((x)) & ( y&z )").unwrap();
+        assert_eq!("x & y & z", func.to_string());
+    }
+}
+
 #[cfg(test)]
 mod eval_tests {
     use super::Function;
@@ -0,0 +1,413 @@
+use std::{collections::HashMap, fmt::Display, io::Write};
+
+use thiserror::Error;
+
+use super::{
+    circuit::Gate,
+    network::{network_function, variable_names, NetworkError, Node},
+    Function, GateSet, MultiFunction,
+};
+
+/// Largest variable index of a file that is read, which bounds memory allocated before reading the gates.
+const MAX_VARIABLES: u32 = 1 << 20;
+
+/// Encoding of the and-inverter graph in an AIGER file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AigerFormat {
+    /// `aag` files, with every gate on its own line.
+    Ascii,
+    /// `aig` files, with gates compressed into bytes.
+    Binary,
+}
+
+/// Builds an and-inverter graph from gates, with literal `2 * i + 1` being the negation of variable `i`.
+struct AigBuilder {
+    num_inputs: u32,
+    ands: Vec<(u32, u32)>,
+    indices: HashMap<(u32, u32), u32>,
+}
+
+impl AigBuilder {
+    /// Literal of the conjunction, reusing an equal gate.
+    fn and(&mut self, lhs: u32, rhs: u32) -> u32 {
+        // Binary files need the larger operand first.
+        let operands = (lhs.max(rhs), lhs.min(rhs));
+        match operands {
+            (_, 0) => return 0,
+            (larger, 1) => return larger,
+            (larger, smaller) if larger == smaller => return larger,
+            (larger, smaller) if larger == smaller ^ 1 => return 0,
+            _ => {}
+        }
+        if let Some(&literal) = self.indices.get(&operands) {
+            return literal;
+        }
+        self.ands.push(operands);
+        let literal = 2 * (self.num_inputs + self.ands.len() as u32);
+        self.indices.insert(operands, literal);
+        literal
+    }
+}
+
+impl Function {
+    /// Reads an and-inverter graph in ASCII or binary AIGER format, with a single output and no latches.
+    ///
+    /// Inputs are named by the symbol table, or `i0`, `i1`, and so on without it.
+    pub fn from_aiger(contents: &[u8]) -> Result<Function, AigerError> {
+        use AigerErrorKind::*;
+
+        let mut reader = Reader { contents, pos: 0, line: 0 };
+        let header = reader.line().ok_or(InvalidHeader.at(1))?;
+        let fields = header.split_whitespace().collect::<Vec<_>>();
+        let (format, numbers) = match fields.split_first() {
+            Some((&"aag", numbers)) => (AigerFormat::Ascii, numbers),
+            Some((&"aig", numbers)) => (AigerFormat::Binary, numbers),
+            _ => return Err(InvalidHeader.at(1)),
+        };
+        let numbers = numbers.iter().map(|number| number.parse::<u32>()).collect::<Result<Vec<_>, _>>();
+        let numbers = numbers.map_err(|_| InvalidHeader.at(1))?;
+        // Later versions add counts of constraints and properties after the usual five.
+        let [max_var, num_inputs, num_latches, num_outputs, num_ands, ref extra @ ..] = numbers[..] else {
+            return Err(InvalidHeader.at(1));
+        };
+        if num_latches > 0 {
+            return Err(Latches.at(1));
+        }
+        if extra.iter().any(|&count| count > 0) {
+            return Err(UnsupportedSections.at(1));
+        }
+        if num_outputs != 1 {
+            return Err(OutputCount(num_outputs as usize).at(1));
+        }
+        // Every input, latch and gate defines its own variable.
+        let defined_vars = num_inputs.checked_add(num_latches).and_then(|count| count.checked_add(num_ands));
+        if defined_vars.is_none_or(|count| count > max_var) {
+            return Err(InvalidHeader.at(1));
+        }
+        if max_var.checked_sub(num_inputs) != Some(num_ands) && format == AigerFormat::Binary {
+            return Err(InvalidHeader.at(1));
+        }
+        if max_var > MAX_VARIABLES {
+            return Err(TooLarge.at(1));
+        }
+
+        // Variables take the first nodes, followed by negations.
+        let mut nodes = vec![Node::Const(false); max_var as usize + 1];
+        let mut defined = vec![false; nodes.len()];
+        defined[0] = true;
+        let mut inputs = Vec::with_capacity(num_inputs as usize);
+        let mut uses = Vec::new();
+        let literal = |word: &str, line: usize| {
+            let literal = word.parse::<u32>().map_err(|_| InvalidLiteral(word.to_string()).at(line))?;
+            match literal / 2 <= max_var {
+                true => Ok(literal),
+                false => Err(LiteralOutOfRange(literal).at(line)),
+            }
+        };
+        let variable = |literal: u32, line: usize, defined: &mut Vec<bool>| {
+            let var = literal as usize / 2;
+            if literal % 2 == 1 || var == 0 || defined[var] {
+                return Err(Redefinition(literal).at(line));
+            }
+            defined[var] = true;
+            Ok(var)
+        };
+
+        for index in 0..num_inputs {
+            let var = match format {
+                AigerFormat::Ascii => {
+                    let line = reader.line().ok_or(UnexpectedEnd.at(reader.line + 1))?;
+                    variable(literal(line.trim(), reader.line)?, reader.line, &mut defined)?
+                }
+                AigerFormat::Binary => variable(2 * (index + 1), reader.line, &mut defined)?,
+            };
+            inputs.push(var);
+        }
+        let line = reader.line().ok_or(UnexpectedEnd.at(reader.line + 1))?;
+        let output = literal(line.trim(), reader.line)?;
+        uses.push((output, reader.line));
+        let mut ands = Vec::with_capacity(num_ands as usize);
+        for index in 0..num_ands {
+            let (lhs, rhs0, rhs1) = match format {
+                AigerFormat::Ascii => {
+                    let line = reader.line().ok_or(UnexpectedEnd.at(reader.line + 1))?;
+                    let words = line.split_whitespace().collect::<Vec<_>>();
+                    let [lhs, rhs0, rhs1] = words[..] else {
+                        return Err(InvalidAnd.at(reader.line));
+                    };
+                    (literal(lhs, reader.line)?, literal(rhs0, reader.line)?, literal(rhs1, reader.line)?)
+                }
+                // Gates follow inputs in order, each stored as differences between its literals.
+                AigerFormat::Binary => {
+                    let line = reader.line + 1;
+                    let lhs = 2 * (num_inputs + index + 1);
+                    let rhs0 = reader.decode().and_then(|delta| lhs.checked_sub(delta)).ok_or(InvalidDelta.at(line))?;
+                    let rhs1 = reader.decode().and_then(|delta| rhs0.checked_sub(delta)).ok_or(InvalidDelta.at(line))?;
+                    (lhs, rhs0, rhs1)
+                }
+            };
+            let line = match format {
+                AigerFormat::Ascii => reader.line,
+                AigerFormat::Binary => reader.line + 1,
+            };
+            let var = variable(lhs, line, &mut defined)?;
+            uses.extend([(rhs0, line), (rhs1, line)]);
+            ands.push((var, [rhs0, rhs1], line));
+        }
+        if format == AigerFormat::Binary && num_ands > 0 {
+            reader.line += 1;
+        }
+
+        // Symbol table and comments are both optional.
+        let mut names = inputs.iter().enumerate().map(|(index, _)| format!("i{index}")).collect::<Vec<_>>();
+        while let Some(line) = reader.line() {
+            if line.starts_with('c') {
+                break;
+            }
+            let symbol = line.strip_prefix('i').and_then(|line| line.split_once(' '));
+            let index = symbol.and_then(|(index, _)| index.parse::<usize>().ok());
+            match (symbol, index) {
+                (Some((_, name)), Some(index)) if index < names.len() => names[index] = name.to_string(),
+                _ if line.starts_with(['l', 'o', 'b', 'c', 'j', 'f']) => {}
+                _ => return Err(InvalidSymbol.at(reader.line)),
+            }
+        }
+
+        for (var, name) in inputs.iter().zip(variable_names(names.iter().map(String::as_str))) {
+            nodes[*var] = Node::Var(name);
+        }
+        if let Some((literal, line)) = uses.iter().find(|(literal, _)| !defined[*literal as usize / 2]) {
+            return Err(UndefinedLiteral(*literal).at(*line));
+        }
+        let mut lines = vec![1; nodes.len()];
+        let node = |literal: u32, nodes: &mut Vec<Node>| match literal % 2 {
+            0 => literal as usize / 2,
+            _ => {
+                nodes.push(Node::Not(literal as usize / 2));
+                nodes.len() - 1
+            }
+        };
+        for (var, operands, line) in ands {
+            let operands = operands.map(|literal| node(literal, &mut nodes));
+            nodes[var] = Node::And(operands.to_vec());
+            lines[var] = line;
+        }
+        let output_node = node(output, &mut nodes);
+        network_function(&nodes, output_node).map_err(|error| match error {
+            NetworkError::Cycle(node) => {
+                let var = match nodes[node] {
+                    Node::Not(var) if node > max_var as usize => var,
+                    _ => node,
+                };
+                Cycle(2 * var as u32).at(lines[var])
+            }
+            NetworkError::TooLarge => TooLarge.at(1),
+        })
+    }
+}
+
+impl MultiFunction {
+    /// Writes the outputs as an and-inverter graph in AIGER format they share,
+    /// with names of inputs and outputs in the symbol table.
+    pub fn to_aiger(&self, format: AigerFormat) -> Vec<u8> {
+        let circuit = self.to_circuit(GateSet::AndOrNot);
+        let num_inputs = self.inputs().len() as u32;
+        let mut builder = AigBuilder { num_inputs, ands: Vec::new(), indices: HashMap::new() };
+        let mut literals = Vec::with_capacity(circuit.gates().len());
+        for gate in circuit.gates() {
+            let operands = gate.operands().iter().map(|&operand| literals[operand]).collect::<Vec<u32>>();
+            let literal = match gate {
+                Gate::Input(var) => 2 * (*var as u32 + 1),
+                Gate::Const(val) => *val as u32,
+                Gate::Not(_) => operands[0] ^ 1,
+                Gate::And(_) => operands.into_iter().fold(1, |lhs, rhs| builder.and(lhs, rhs)),
+                Gate::Or(_) => operands.into_iter().fold(0, |lhs, rhs| builder.and(lhs ^ 1, rhs ^ 1) ^ 1),
+                Gate::Nand(_) | Gate::Nor(_) => unreachable!("circuit only has AND, OR and NOT gates"),
+            };
+            literals.push(literal);
+        }
+
+        let num_ands = builder.ands.len() as u32;
+        let num_outputs = circuit.outputs().len();
+        let mut out = Vec::new();
+        let header = if format == AigerFormat::Ascii { "aag" } else { "aig" };
+        writeln!(out, "{header} {} {num_inputs} 0 {num_outputs} {num_ands}", num_inputs + num_ands).unwrap();
+        if format == AigerFormat::Ascii {
+            for input in 1..=num_inputs {
+                writeln!(out, "{}", 2 * input).unwrap();
+            }
+        }
+        for &output in circuit.outputs() {
+            writeln!(out, "{}", literals[output]).unwrap();
+        }
+        for (index, (rhs0, rhs1)) in builder.ands.iter().enumerate() {
+            let lhs = 2 * (num_inputs + index as u32 + 1);
+            match format {
+                AigerFormat::Ascii => writeln!(out, "{lhs} {rhs0} {rhs1}").unwrap(),
+                AigerFormat::Binary => {
+                    encode(&mut out, lhs - rhs0);
+                    encode(&mut out, rhs0 - rhs1);
+                }
+            }
+        }
+        for (index, var) in self.inputs().iter().enumerate() {
+            writeln!(out, "i{index} {var}").unwrap();
+        }
+        for (index, (name, _)) in self.outputs().iter().enumerate() {
+            writeln!(out, "o{index} {name}").unwrap();
+        }
+        writeln!(out, "c").unwrap();
+        for (name, func) in self.outputs() {
+            writeln!(out, "{name} = {func}").unwrap();
+        }
+        out
+    }
+}
+
+/// Appends the number in 7-bit groups, least significant first, with the high bit set on all but the last.
+fn encode(out: &mut Vec<u8>, mut number: u32) {
+    while number >= 0x80 {
+        out.push(number as u8 & 0x7f | 0x80);
+        number >>= 7;
+    }
+    out.push(number as u8);
+}
+
+/// Reads lines and binary numbers of an AIGER file, counting lines.
+struct Reader<'a> {
+    contents: &'a [u8],
+    pos: usize,
+    /// Number of the last line read, with the binary section of gates counting as a single line.
+    line: usize,
+}
+
+impl Reader<'_> {
+    fn line(&mut self) -> Option<String> {
+        if self.pos >= self.contents.len() {
+            return None;
+        }
+        let rest = &self.contents[self.pos..];
+        let end = rest.iter().position(|&byte| byte == b'\n').unwrap_or(rest.len());
+        self.pos += end + 1;
+        self.line += 1;
+        Some(String::from_utf8_lossy(&rest[..end]).trim_end_matches('\r').to_string())
+    }
+
+    /// Reads a number encoded like [`encode`] writes it, if it's complete and fits.
+    fn decode(&mut self) -> Option<u32> {
+        let mut number = 0u64;
+        for shift in (0..35).step_by(7) {
+            let byte = *self.contents.get(self.pos)?;
+            self.pos += 1;
+            number |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return u32::try_from(number).ok();
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct AigerError {
+    line: usize,
+    kind: AigerErrorKind,
+}
+
+impl Display for AigerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AigerErrorKind {
+    #[error("header should be `aag M I L O A` or `aig M I L O A`")]
+    InvalidHeader,
+    #[error("latches are not supported")]
+    Latches,
+    #[error("constraints, properties, and fairness are not supported")]
+    UnsupportedSections,
+    #[error("expected a single output, found {0}")]
+    OutputCount(usize),
+    #[error("`{0}` is not a literal")]
+    InvalidLiteral(String),
+    #[error("literal {0} refers to a variable above the maximal index")]
+    LiteralOutOfRange(u32),
+    #[error("literal {0} should be positive and refer to a variable that isn't defined yet")]
+    Redefinition(u32),
+    #[error("and gate should have three literals")]
+    InvalidAnd,
+    #[error("binary and gate is malformed")]
+    InvalidDelta,
+    #[error("file ends before all inputs, outputs, and gates are defined")]
+    UnexpectedEnd,
+    #[error("symbol table entry should be like `i0 name`")]
+    InvalidSymbol,
+    #[error("literal {0} is neither an input nor defined by an and gate")]
+    UndefinedLiteral(u32),
+    #[error("and gate of literal {0} depends on itself")]
+    Cycle(u32),
+    #[error("circuit is too large to be written as a formula")]
+    TooLarge,
+}
+
+impl AigerErrorKind {
+    pub fn at(self, line: usize) -> AigerError {
+        AigerError { line, kind: self }
+    }
+}
+
+#[cfg(test)]
+mod aiger_tests {
+    use super::{AigerErrorKind, AigerFormat};
+    use crate::function::{Function, MultiFunction};
+
+    #[test]
+    fn aiger_roundtrip() {
+        for formula in ["a & !(b | c) | !a & c", "1", "!x", "a & !a | b", "(a | b) & (a | b | c)"] {
+            let func = Function::parse(formula).unwrap();
+            for format in [AigerFormat::Ascii, AigerFormat::Binary] {
+                let imported = Function::from_aiger(&MultiFunction::from(func.clone()).to_aiger(format)).unwrap();
+                assert_eq!(None, func.compare(&imported).counterexample(), "{formula}");
+            }
+        }
+    }
+
+    #[test]
+    fn aiger_write() {
+        let func = MultiFunction::parse("a | b").unwrap();
+        let ascii = String::from_utf8(func.to_aiger(AigerFormat::Ascii)).unwrap();
+        assert!(ascii.starts_with("aag 3 2 0 1 1\n2\n4\n7\n6 5 3\ni0 a\ni1 b\no0 F\n"));
+        let binary = func.to_aiger(AigerFormat::Binary);
+        assert!(binary.starts_with(b"aig 3 2 0 1 1\n7\n\x01\x02i0 a\n"));
+    }
+
+    #[test]
+    fn aiger_outputs() {
+        let adder = MultiFunction::parse("s = a ^ b ^ c; cout = a & b | c & (a ^ b)").unwrap();
+        let ascii = String::from_utf8(adder.to_aiger(AigerFormat::Ascii)).unwrap();
+        let header = ascii.lines().next().unwrap().split(' ').collect::<Vec<_>>();
+        assert_eq!(["aag", "3", "0", "2"], [header[0], header[2], header[3], header[4]]);
+        assert!(ascii.contains("\ni2 c\no0 s\no1 cout\nc\ns = "));
+        assert!(ascii.contains("\ncout = "));
+        let binary = adder.to_aiger(AigerFormat::Binary);
+        assert!(binary.starts_with(format!("aig {} 3 0 2 ", header[1]).as_bytes()));
+    }
+
+    #[test]
+    fn aiger_errors() {
+        let error = |s: &[u8]| Function::from_aiger(s).unwrap_err();
+        assert_eq!(AigerErrorKind::InvalidHeader.at(1), error(b"aig 1 2\n"));
+        assert_eq!(AigerErrorKind::Latches.at(1), error(b"aag 1 0 1 1 0\n2 3\n2\n"));
+        assert_eq!(AigerErrorKind::UndefinedLiteral(8).at(4), error(b"aag 4 1 0 1 1\n2\n6\n6 2 8\n"));
+        assert_eq!(AigerErrorKind::Redefinition(2).at(4), error(b"aag 2 1 0 1 1\n2\n2\n2 4 4\n"));
+        assert_eq!(AigerErrorKind::Cycle(4).at(4), error(b"aag 3 1 0 1 2\n2\n4\n4 2 6\n6 4 2\n"));
+        assert_eq!(AigerErrorKind::UnexpectedEnd.at(3), error(b"aag 1 1 0 1 0\n2\n"));
+        assert_eq!(AigerErrorKind::InvalidDelta.at(3), error(b"aig 2 1 0 1 1\n4\n\x09\x00"));
+        assert_eq!(AigerErrorKind::InvalidHeader.at(1), error(b"aag 1 4000000000 0 1 0\n"));
+        assert_eq!(AigerErrorKind::InvalidHeader.at(1), error(b"aag 4294967295 4294967295 0 1 1\n"));
+        assert_eq!(AigerErrorKind::InvalidHeader.at(1), error(b"aig 3 1 0 1 1\n4\n"));
+        assert_eq!(AigerErrorKind::TooLarge.at(1), error(b"aag 4294967295 1 0 1 0\n2\n2\n"));
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+};
+
+use thiserror::Error;
+
+use super::{
+    circuit::Gate,
+    network::{network_function, signal_prefix, variable_names, NetworkError, Node},
+    Function, GateSet, MultiFunction,
+};
+
+/// Definition of a signal by `.names`, with the line it starts on.
+struct Names {
+    line: usize,
+    inputs: Vec<String>,
+    /// Input parts of the cover's rows.
+    cubes: Vec<String>,
+    /// Whether the rows are where the signal is true, rather than false.
+    onset: Option<bool>,
+}
+
+impl Function {
+    /// Reads the first model of a BLIF file, which should have a single output
+    /// and describe it with `.names` covers only.
+    ///
+    /// Names of inputs become variables, in lowercase and with characters that aren't allowed replaced.
+    pub fn from_blif(s: &str) -> Result<Function, BlifError> {
+        use BlifErrorKind::*;
+
+        let mut inputs = Vec::<(String, usize)>::new();
+        let mut outputs = Vec::<(String, usize)>::new();
+        let mut definitions = Vec::<(String, Names)>::new();
+        let mut last_line = 1;
+        // Lines ending with a backslash continue on the next one.
+        let mut logical = String::new();
+        let mut start = 1;
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            last_line = line_number;
+            let line = line.split('#').next().unwrap();
+            if logical.is_empty() {
+                start = line_number;
+            }
+            if let Some(line) = line.trim_end().strip_suffix('\\') {
+                logical.push_str(line);
+                logical.push(' ');
+                continue;
+            }
+            logical.push_str(line);
+            let line = std::mem::take(&mut logical);
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let Some(&first) = words.first() else {
+                continue;
+            };
+
+            match first {
+                ".model" => {}
+                ".inputs" => inputs.extend(words[1..].iter().map(|name| (name.to_string(), start))),
+                ".outputs" => outputs.extend(words[1..].iter().map(|name| (name.to_string(), start))),
+                ".names" => {
+                    let Some((output, inputs)) = words[1..].split_last() else {
+                        return Err(MissingSignal.at(start));
+                    };
+                    let inputs = inputs.iter().map(|name| name.to_string()).collect();
+                    let names = Names { line: start, inputs, cubes: Vec::new(), onset: None };
+                    definitions.push((output.to_string(), names));
+                }
+                ".end" => break,
+                command if command.starts_with('.') => return Err(UnsupportedCommand(command.to_string()).at(start)),
+                _ => {
+                    let Some((_, names)) = definitions.last_mut() else {
+                        return Err(CubeOutsideNames.at(start));
+                    };
+                    let (cube, value) = match words.as_slice() {
+                        [value] if names.inputs.is_empty() => ("", *value),
+                        [cube, value] => (*cube, *value),
+                        _ => return Err(InvalidCube(names.inputs.len()).at(start)),
+                    };
+                    let valid = cube.len() == names.inputs.len() && cube.chars().all(|ch| "01-".contains(ch));
+                    let onset = match value {
+                        "1" => true,
+                        "0" => false,
+                        _ => return Err(InvalidCube(names.inputs.len()).at(start)),
+                    };
+                    if !valid {
+                        return Err(InvalidCube(names.inputs.len()).at(start));
+                    }
+                    if names.onset.is_some_and(|previous| previous != onset) {
+                        return Err(MixedCover.at(start));
+                    }
+                    names.onset = Some(onset);
+                    names.cubes.push(cube.to_string());
+                }
+            }
+        }
+        let (output, output_line) = match outputs.as_slice() {
+            [output] => output.clone(),
+            _ => return Err(OutputCount(outputs.len()).at(outputs.first().map_or(last_line, |(_, line)| *line))),
+        };
+
+        // Signals take the first nodes, followed by literals and cubes of the covers.
+        let variables = variable_names(inputs.iter().map(|(name, _)| name.as_str()));
+        let mut signals = HashMap::<&str, usize>::new();
+        let mut lines = Vec::new();
+        let mut nodes = Vec::new();
+        for ((name, line), var) in inputs.iter().zip(variables) {
+            if signals.insert(name, nodes.len()).is_some() {
+                return Err(DuplicateSignal(name.clone()).at(*line));
+            }
+            nodes.push(Node::Var(var));
+            lines.push(*line);
+        }
+        for (name, names) in &definitions {
+            if signals.insert(name, nodes.len()).is_some() {
+                return Err(DuplicateSignal(name.clone()).at(names.line));
+            }
+            nodes.push(Node::Const(false));
+            lines.push(names.line);
+        }
+        // Node of every signal, which owns the nodes of its cover.
+        let mut owners = (0..nodes.len()).collect::<Vec<_>>();
+        for (index, (_, names)) in definitions.iter().enumerate() {
+            let signal = inputs.len() + index;
+            let inputs = names.inputs.iter().map(|name| match signals.get(name.as_str()) {
+                Some(&node) => Ok(node),
+                None => Err(UndefinedSignal(name.clone()).at(names.line)),
+            });
+            let inputs = inputs.collect::<Result<Vec<_>, _>>()?;
+            let mut cubes = Vec::with_capacity(names.cubes.len());
+            for cube in &names.cubes {
+                let mut literals = Vec::new();
+                for (ch, &input) in cube.chars().zip(&inputs) {
+                    match ch {
+                        '1' => literals.push(input),
+                        '0' => {
+                            nodes.push(Node::Not(input));
+                            literals.push(nodes.len() - 1);
+                        }
+                        _ => {}
+                    }
+                }
+                nodes.push(Node::And(literals));
+                cubes.push(nodes.len() - 1);
+            }
+            nodes[signal] = match names.onset {
+                Some(false) => {
+                    nodes.push(Node::Or(cubes));
+                    Node::Not(nodes.len() - 1)
+                }
+                _ => Node::Or(cubes),
+            };
+            owners.resize(nodes.len(), signal);
+        }
+
+        let Some(&output) = signals.get(output.as_str()) else {
+            return Err(UndefinedSignal(output).at(output_line));
+        };
+        network_function(&nodes, output).map_err(|error| match error {
+            NetworkError::Cycle(node) => {
+                let signal = owners[node];
+                let name = signals.iter().find(|(_, index)| **index == signal).unwrap().0;
+                Cycle(name.to_string()).at(lines[signal])
+            }
+            NetworkError::TooLarge => TooLarge.at(output_line),
+        })
+    }
+}
+
+impl MultiFunction {
+    /// Writes the outputs in Berkeley Logic Interchange Format, with a `.names` cover for every gate
+    /// of a circuit they share.
+    pub fn to_blif(&self) -> String {
+        let circuit = self.to_circuit(GateSet::AndOrNot);
+        let inputs = self.inputs().iter().map(|var| var.as_str());
+        let prefix = signal_prefix(inputs.clone().chain(self.outputs().iter().map(|(name, _)| name.as_str())));
+        let name = |index: usize| match circuit.gates()[index] {
+            Gate::Input(var) => self.inputs()[var].to_string(),
+            _ => format!("{prefix}{index}"),
+        };
+
+        let mut output = String::new();
+        for (name, func) in self.outputs() {
+            writeln!(output, "# {name} = {func}").unwrap();
+        }
+        writeln!(output, ".model boolean_function").unwrap();
+        if !self.inputs().is_empty() {
+            writeln!(output, ".inputs {}", inputs.collect::<Vec<_>>().join(" ")).unwrap();
+        }
+        let names = self.outputs().iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        writeln!(output, ".outputs {}", names.join(" ")).unwrap();
+        for (index, gate) in circuit.gates().iter().enumerate() {
+            let operands = gate.operands();
+            let mut signals = operands.iter().map(|&operand| name(operand)).collect::<Vec<_>>();
+            signals.push(name(index));
+            let rows = match gate {
+                Gate::Input(_) => continue,
+                Gate::Const(val) => vec![if *val { "1" } else { "" }.to_string()],
+                Gate::Not(_) => vec!["0 1".to_string()],
+                Gate::And(_) => vec![format!("{} 1", "1".repeat(operands.len()))],
+                Gate::Or(_) => (0..operands.len())
+                    .map(|i| {
+                        let cube = (0..operands.len()).map(|j| if i == j { '1' } else { '-' });
+                        format!("{} 1", cube.collect::<String>())
+                    })
+                    .collect(),
+                Gate::Nand(_) | Gate::Nor(_) => unreachable!("circuit only has AND, OR and NOT gates"),
+            };
+            writeln!(output, ".names {}", signals.join(" ")).unwrap();
+            for row in rows.iter().filter(|row| !row.is_empty()) {
+                writeln!(output, "{row}").unwrap();
+            }
+        }
+        for (&gate, target) in circuit.outputs().iter().zip(names) {
+            writeln!(output, ".names {} {target}\n1 1", name(gate)).unwrap();
+        }
+        writeln!(output, ".end").unwrap();
+        output
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct BlifError {
+    line: usize,
+    kind: BlifErrorKind,
+}
+
+impl Display for BlifError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BlifErrorKind {
+    #[error("`{0}` is not supported, only `.names` covers are")]
+    UnsupportedCommand(String),
+    #[error("`.names` should list its inputs and the signal it defines")]
+    MissingSignal,
+    #[error("row of the cover should be {0} characters of `0`, `1`, or `-` followed by `0` or `1`")]
+    InvalidCube(usize),
+    #[error("row of a cover outside of `.names`")]
+    CubeOutsideNames,
+    #[error("cover has rows for both values of the signal")]
+    MixedCover,
+    #[error("expected a single output, found {0}")]
+    OutputCount(usize),
+    #[error("signal `{0}` is defined more than once")]
+    DuplicateSignal(String),
+    #[error("signal `{0}` is neither an input nor defined by `.names`")]
+    UndefinedSignal(String),
+    #[error("signal `{0}` depends on itself")]
+    Cycle(String),
+    #[error("circuit is too large to be written as a formula")]
+    TooLarge,
+}
+
+impl BlifErrorKind {
+    pub fn at(self, line: usize) -> BlifError {
+        BlifError { line, kind: self }
+    }
+}
+
+#[cfg(test)]
+mod blif_tests {
+    use super::BlifErrorKind;
+    use crate::function::{Function, MultiFunction};
+
+    #[test]
+    fn blif_roundtrip() {
+        for formula in ["a & !(b | c) | !a & c", "1", "x1 | 0", "!(n1 & n2)"] {
+            let func = Function::parse(formula).unwrap();
+            let imported = Function::from_blif(&MultiFunction::from(func.clone()).to_blif()).unwrap();
+            assert_eq!(None, func.compare(&imported).counterexample(), "{formula}");
+        }
+    }
+
+    #[test]
+    fn blif_outputs() {
+        let adder = MultiFunction::parse("s = a ^ b ^ c; cout = a & b | c & (a ^ b)").unwrap();
+        let blif = adder.to_blif();
+        assert!(blif.starts_with("# s = "));
+        assert!(blif.contains("\n# cout = "));
+        assert!(blif.contains("\n.inputs a b c\n.outputs s cout\n"));
+        assert!(blif.ends_with(" cout\n1 1\n.end\n"));
+        // Internal signals don't take names of outputs.
+        let blif = MultiFunction::parse("n1 = a & !b; n2 = !n1 | c").unwrap().to_blif();
+        assert!(blif.contains(".outputs n1 n2\n.names b nn3\n"));
+        assert!(blif.contains("\n.names nn4 n1\n1 1\n"));
+    }
+
+    #[test]
+    fn blif_read() {
+        let blif = "# full adder carry\n.model carry\n.inputs A B \\\n  Cin\n.outputs Cout\n\
+            .names A B Cin Cout\n11- 1\n1-1 1\n-11 1\n.end\n";
+        let func = Function::from_blif(blif).unwrap();
+        assert_eq!("a & b | a & cin | b & cin", func.to_string());
+
+        let offset = Function::from_blif(".inputs a b\n.outputs f\n.names t f\n0 0\n.names a b t\n00 0\n").unwrap();
+        assert_eq!("!!!(!a & !b)", offset.to_string());
+    }
+
+    #[test]
+    fn blif_errors() {
+        let error = |s: &str| Function::from_blif(s).unwrap_err();
+        assert_eq!(BlifErrorKind::MixedCover.at(5), error(".inputs a b\n.outputs f\n.names a b f\n11 1\n00 0\n"));
+        assert_eq!(BlifErrorKind::InvalidCube(2).at(4), error(".inputs a b\n.outputs f\n.names a b f\n1 1\n"));
+        assert_eq!(
+            BlifErrorKind::UnsupportedCommand(".latch".to_string()).at(3),
+            error(".inputs a\n.outputs f\n.latch a f\n"),
+        );
+        assert_eq!(
+            BlifErrorKind::Cycle("f".to_string()).at(3),
+            error(".inputs a\n.outputs f\n.names a g f\n11 1\n.names f g\n0 1\n"),
+        );
+        assert_eq!(
+            BlifErrorKind::UndefinedSignal("b".to_string()).at(3),
+            error(".inputs a\n.outputs f\n.names a b f\n11 1\n"),
+        );
+        assert_eq!(BlifErrorKind::OutputCount(0).at(1), error(".inputs a\n"));
+    }
+}
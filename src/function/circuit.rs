@@ -0,0 +1,402 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::{expr::Expr, Function, MultiFunction, Variable};
+
+/// Logic gate of a circuit, with operands referring to earlier gates by index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Gate {
+    /// Variable of the function, by index.
+    Input(usize),
+    Const(bool),
+    Not(usize),
+    And(Vec<usize>),
+    Or(Vec<usize>),
+    Nand(Vec<usize>),
+    Nor(Vec<usize>),
+}
+
+impl Gate {
+    pub fn operands(&self) -> &[usize] {
+        match self {
+            Gate::Input(_) | Gate::Const(_) => &[],
+            Gate::Not(operand) => std::slice::from_ref(operand),
+            Gate::And(operands) | Gate::Or(operands) | Gate::Nand(operands) | Gate::Nor(operands) => operands,
+        }
+    }
+}
+
+/// Operators a circuit is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GateSet {
+    #[default]
+    AndOrNot,
+    /// Only NAND gates, with inverters being NAND gates with both inputs tied together.
+    Nand,
+    /// Only NOR gates, with inverters being NOR gates with both inputs tied together.
+    Nor,
+}
+
+/// Function as a network of gates, where equal subexpressions are computed once and fan out.
+///
+/// Circuits of several outputs share gates between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Circuit {
+    inputs: Vec<Variable>,
+    /// Gates in topological order, so operands go before gates that use them.
+    gates: Vec<Gate>,
+    outputs: Vec<usize>,
+}
+
+impl Circuit {
+    pub fn inputs(&self) -> &[Variable] {
+        &self.inputs
+    }
+
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    /// Index of the gate that computes the function, or its first output.
+    pub fn output(&self) -> usize {
+        self.outputs[0]
+    }
+
+    /// Indices of the gates that compute every output.
+    pub fn outputs(&self) -> &[usize] {
+        &self.outputs
+    }
+
+    /// Formula of what the gate computes, written like the whole circuit is.
+    pub fn formula(&self, index: usize) -> String {
+        struct Formula<'a>(&'a Circuit, usize);
+        impl Display for Formula<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.write_gate(self.1, f)
+            }
+        }
+        Formula(self, index).to_string()
+    }
+
+    /// Number of gates, not counting inputs and constants.
+    pub fn gate_count(&self) -> usize {
+        let is_logic = |gate: &&Gate| !matches!(gate, Gate::Input(_) | Gate::Const(_));
+        self.gates.iter().filter(is_logic).count()
+    }
+
+    fn write_gate(&self, index: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match &self.gates[index] {
+            Gate::Input(var) => return write!(f, "{}", self.inputs[*var]),
+            Gate::Const(val) => return write!(f, "{}", *val as u8),
+            Gate::Not(operand) => {
+                f.write_str("!")?;
+                return self.write_operand(*operand, f);
+            }
+            Gate::And(_) => " & ",
+            Gate::Or(_) => " | ",
+            Gate::Nand(_) => " \u{2191} ",
+            Gate::Nor(_) => " \u{2193} ",
+        };
+        for (i, operand) in self.gates[index].operands().iter().enumerate() {
+            if i > 0 {
+                f.write_str(symbol)?;
+            }
+            self.write_operand(*operand, f)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the gate, in brackets unless it's an input, a constant or an inverter.
+    fn write_operand(&self, index: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.gates[index] {
+            Gate::Input(_) | Gate::Const(_) | Gate::Not(_) => self.write_gate(index, f),
+            _ => {
+                f.write_str("(")?;
+                self.write_gate(index, f)?;
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// Writes the first output of the circuit as a formula, with `↑` for NAND and `↓` for NOR.
+///
+/// Gates that fan out are written once for every use.
+impl Display for Circuit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_gate(self.output(), f)
+    }
+}
+
+impl Function {
+    /// Builds a circuit of the function from gates of the set.
+    ///
+    /// NAND and NOR circuits are mapped from the formula so that inverters are only added
+    /// where an operand is needed in the other polarity, and pairs of inverters cancel out.
+    pub fn to_circuit(&self, set: GateSet) -> Circuit {
+        MultiFunction::from(self.clone()).to_circuit(set)
+    }
+}
+
+impl MultiFunction {
+    /// Builds a circuit of every output from gates of the set, like [`Function::to_circuit`],
+    /// with gates that outputs have in common shared.
+    pub fn to_circuit(&self, set: GateSet) -> Circuit {
+        let mut builder = Builder { set, gates: Vec::new(), indices: HashMap::new(), inputs: HashMap::new() };
+        for (index, var) in self.inputs().iter().enumerate() {
+            let input = builder.add(Gate::Input(index));
+            builder.inputs.insert(var.clone(), input);
+        }
+        let outputs = self.outputs().iter().map(|(_, func)| builder.build(&func.to_expr(), false)).collect();
+        let (gates, outputs) = builder.prune(outputs);
+        Circuit { inputs: self.inputs().to_vec(), gates, outputs }
+    }
+}
+
+/// Adds gates to a circuit, reusing equal ones.
+struct Builder {
+    set: GateSet,
+    gates: Vec<Gate>,
+    indices: HashMap<Gate, usize>,
+    inputs: HashMap<Variable, usize>,
+}
+
+impl Builder {
+    fn add(&mut self, mut gate: Gate) -> usize {
+        if let Gate::And(operands) | Gate::Or(operands) | Gate::Nand(operands) | Gate::Nor(operands) = &mut gate {
+            operands.sort_unstable();
+        }
+        if let Some(&index) = self.indices.get(&gate) {
+            return index;
+        }
+        self.gates.push(gate.clone());
+        self.indices.insert(gate, self.gates.len() - 1);
+        self.gates.len() - 1
+    }
+
+    /// Adds gates computing the expression, or its negation if `negated` is set.
+    fn build(&mut self, expr: &Expr, negated: bool) -> usize {
+        let (is_and, operands) = match expr {
+            Expr::Const(val) => return self.add(Gate::Const(val ^ negated)),
+            Expr::Var(var) => {
+                let input = self.inputs[var];
+                return if negated { self.invert(input) } else { input };
+            }
+            Expr::Not(inner) => return self.build(inner, !negated),
+            Expr::Xor(lhs, rhs) => {
+                let (lhs, rhs) = (self.build(lhs, false), self.build(rhs, false));
+                return self.xor(lhs, rhs, negated);
+            }
+            Expr::And(operands) => (true, operands),
+            Expr::Or(operands) => (false, operands),
+        };
+        // NAND takes operands as they are for AND, and negated for OR; NOR is the other way around.
+        let (operands_negated, gate, output_negated) = match self.set {
+            GateSet::AndOrNot => (false, if is_and { Gate::And } else { Gate::Or }, false),
+            GateSet::Nand => (!is_and, Gate::Nand as fn(Vec<usize>) -> Gate, is_and),
+            GateSet::Nor => (is_and, Gate::Nor as fn(Vec<usize>) -> Gate, !is_and),
+        };
+        // Explicit brackets around the same operator, like in `a & (b & c)`, would only add inverters.
+        let mut flattened = Vec::with_capacity(operands.len());
+        let mut pending = operands.iter().rev().collect::<Vec<_>>();
+        while let Some(operand) = pending.pop() {
+            match operand.operands() {
+                Some((inner_is_and, inner)) if inner_is_and == is_and => pending.extend(inner.iter().rev()),
+                _ => flattened.push(operand),
+            }
+        }
+        let operands = flattened.into_iter().map(|operand| self.build(operand, operands_negated)).collect();
+        let output = self.add(gate(operands));
+        if negated != output_negated {
+            self.invert(output)
+        } else {
+            output
+        }
+    }
+
+    /// Adds gates computing the exclusive or of two wires, or its negation if `negated` is set.
+    fn xor(&mut self, lhs: usize, rhs: usize, negated: bool) -> usize {
+        let output = match self.set {
+            GateSet::AndOrNot => {
+                let (not_lhs, not_rhs) = (self.invert(lhs), self.invert(rhs));
+                let left = self.add(Gate::And(vec![lhs, not_rhs]));
+                let right = self.add(Gate::And(vec![not_lhs, rhs]));
+                self.add(Gate::Or(vec![left, right]))
+            }
+            // Four gates, with the first one shared by both sides.
+            GateSet::Nand => {
+                let both = self.add(Gate::Nand(vec![lhs, rhs]));
+                let left = self.add(Gate::Nand(vec![lhs, both]));
+                let right = self.add(Gate::Nand(vec![rhs, both]));
+                self.add(Gate::Nand(vec![left, right]))
+            }
+            // The same four gates compute the negation when they're NOR gates.
+            GateSet::Nor => {
+                let either = self.add(Gate::Nor(vec![lhs, rhs]));
+                let left = self.add(Gate::Nor(vec![lhs, either]));
+                let right = self.add(Gate::Nor(vec![rhs, either]));
+                let equal = self.add(Gate::Nor(vec![left, right]));
+                return if negated { equal } else { self.invert(equal) };
+            }
+        };
+        if negated {
+            self.invert(output)
+        } else {
+            output
+        }
+    }
+
+    /// Drops gates no output depends on, like ones whose inverter cancelled out,
+    /// returning the remaining gates and the new indices of the outputs.
+    ///
+    /// Inputs are kept even if they're unused.
+    fn prune(self, mut outputs: Vec<usize>) -> (Vec<Gate>, Vec<usize>) {
+        let mut used = vec![false; self.gates.len()];
+        for &output in &outputs {
+            used[output] = true;
+        }
+        for index in (0..self.gates.len()).rev() {
+            if used[index] {
+                for &operand in self.gates[index].operands() {
+                    used[operand] = true;
+                }
+            }
+        }
+        let mut renumbered = vec![0; self.gates.len()];
+        let mut gates = Vec::new();
+        for (index, mut gate) in self.gates.into_iter().enumerate() {
+            if !used[index] && !matches!(gate, Gate::Input(_)) {
+                continue;
+            }
+            match &mut gate {
+                Gate::Input(_) | Gate::Const(_) => {}
+                Gate::Not(operand) => *operand = renumbered[*operand],
+                Gate::And(operands) | Gate::Or(operands) | Gate::Nand(operands) | Gate::Nor(operands) => {
+                    operands.iter_mut().for_each(|operand| *operand = renumbered[*operand]);
+                }
+            }
+            renumbered[index] = gates.len();
+            gates.push(gate);
+        }
+        outputs.iter_mut().for_each(|output| *output = renumbered[*output]);
+        (gates, outputs)
+    }
+
+    /// Adds an inverter, unless the wire is an inverter itself or a constant.
+    fn invert(&mut self, wire: usize) -> usize {
+        match &self.gates[wire] {
+            Gate::Const(val) => return self.add(Gate::Const(!val)),
+            Gate::Not(input) => return *input,
+            Gate::Nand(operands) | Gate::Nor(operands) if operands.len() == 2 && operands[0] == operands[1] => {
+                return operands[0];
+            }
+            _ => {}
+        }
+        match self.set {
+            GateSet::AndOrNot => self.add(Gate::Not(wire)),
+            GateSet::Nand => self.add(Gate::Nand(vec![wire, wire])),
+            GateSet::Nor => self.add(Gate::Nor(vec![wire, wire])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod circuit_tests {
+    use super::{Circuit, Gate, GateSet};
+    use crate::function::{Function, MultiFunction};
+
+    fn eval(circuit: &Circuit, values: &[bool]) -> bool {
+        eval_gate(circuit, circuit.output(), values)
+    }
+
+    fn eval_gate(circuit: &Circuit, output: usize, values: &[bool]) -> bool {
+        let mut outputs = Vec::with_capacity(circuit.gates.len());
+        for gate in &circuit.gates {
+            let mut operands = gate.operands().iter().map(|&operand| outputs[operand]);
+            let output = match gate {
+                Gate::Input(index) => values[*index],
+                Gate::Const(val) => *val,
+                Gate::Not(_) => !operands.all(|val| val),
+                Gate::And(_) => operands.all(|val| val),
+                Gate::Or(_) => operands.any(|val| val),
+                Gate::Nand(_) => !operands.all(|val| val),
+                Gate::Nor(_) => !operands.any(|val| val),
+            };
+            outputs.push(output);
+        }
+        outputs[output]
+    }
+
+    fn check(formula: &str, set: GateSet) -> usize {
+        let func = Function::parse(formula).unwrap();
+        let circuit = func.to_circuit(set);
+        let n = func.vars().len();
+        for minterm in 0..1u64 << n {
+            let values = (0..n).map(|i| minterm >> (n - 1 - i) & 1 == 1).collect::<Vec<_>>();
+            assert_eq!(func.eval(&values).unwrap(), eval(&circuit, &values), "{formula} as {circuit} at {minterm}");
+        }
+        let allowed = |gate: &Gate| match set {
+            GateSet::AndOrNot => !matches!(gate, Gate::Nand(_) | Gate::Nor(_)),
+            GateSet::Nand => matches!(gate, Gate::Input(_) | Gate::Const(_) | Gate::Nand(_)),
+            GateSet::Nor => matches!(gate, Gate::Input(_) | Gate::Const(_) | Gate::Nor(_)),
+        };
+        assert!(circuit.gates.iter().all(allowed), "{formula} as {circuit}");
+        circuit.gate_count()
+    }
+
+    #[test]
+    fn nand_circuits() {
+        assert_eq!(2, check("a & b", GateSet::Nand));
+        assert_eq!(3, check("a | b", GateSet::Nand));
+        assert_eq!(1, check("!(a & b)", GateSet::Nand));
+        assert_eq!(3, check("a & b | c & d", GateSet::Nand));
+        assert_eq!(0, check("!!a", GateSet::Nand));
+        assert_eq!(0, check("a & a", GateSet::Nand));
+        assert_eq!(3, check("(x | y) & (x | y)", GateSet::Nand));
+        assert_eq!(2, check("a & (b & (c & d))", GateSet::Nand));
+        check("!(a | !b & c) & (a | 1)", GateSet::Nand);
+    }
+
+    #[test]
+    fn nor_circuits() {
+        assert_eq!(2, check("a | b", GateSet::Nor));
+        assert_eq!(3, check("(a | b) & (c | d)", GateSet::Nor));
+        assert_eq!(0, check("a | a", GateSet::Nor));
+        check("a & !b | !(c & a) | 0", GateSet::Nor);
+    }
+
+    #[test]
+    fn xor_circuits() {
+        assert_eq!(5, check("a ^ b", GateSet::AndOrNot));
+        assert_eq!(4, check("a ^ b", GateSet::Nand));
+        assert_eq!(4, check("!(a ^ b)", GateSet::Nor));
+        assert_eq!(5, check("a ^ b", GateSet::Nor));
+        check("a ^ a | !(b ^ c) & (a ^ 1)", GateSet::Nand);
+        check("(a | b) ^ c ^ !a", GateSet::Nor);
+        check("!(a ^ b & c) | b ^ 0", GateSet::AndOrNot);
+        let chain = (0..12).map(|i| format!("x{i:02}")).collect::<Vec<_>>().join(" ^ ");
+        assert_eq!(44, check(&chain, GateSet::Nand));
+    }
+
+    #[test]
+    fn shared_gates() {
+        assert_eq!(4, check("(a & b | c) & (a & b | d)", GateSet::AndOrNot));
+        assert_eq!(7, check("(a & b | c) & (a & b | d)", GateSet::Nand));
+        let circuit = Function::parse("a | b").unwrap().to_circuit(GateSet::Nand);
+        assert_eq!("(a \u{2191} a) \u{2191} (b \u{2191} b)", circuit.to_string());
+    }
+
+    #[test]
+    fn multiple_outputs() {
+        let adder = MultiFunction::parse("s = a ^ b ^ c; cout = a & b | c & (a ^ b)").unwrap();
+        let circuit = adder.to_circuit(GateSet::Nand);
+        assert_eq!(2, circuit.outputs().len());
+        // The sum and the carry share `a ^ b`.
+        assert_eq!(9, circuit.gate_count());
+        for n in 0..8u8 {
+            let values = [n & 4 != 0, n & 2 != 0, n & 1 != 0];
+            for ((_, func), &output) in adder.outputs().iter().zip(circuit.outputs()) {
+                assert_eq!(func.eval(&values).unwrap(), eval_gate(&circuit, output, &values));
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    bool_iterator::BoolIterator,
+    sat::{Cnf, Outcome, Solver},
+};
+
+use super::{Function, Variable, ENUMERATION_LIMIT};
+
+/// Result of comparing two functions over the union of their variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    vars: Vec<Variable>,
+    counterexample: Option<Vec<bool>>,
+}
+
+impl Comparison {
+    /// Variables of both functions, sorted.
+    pub fn vars(&self) -> &[Variable] {
+        self.vars.as_slice()
+    }
+
+    /// An assignment where functions differ.
+    ///
+    /// That's the first such assignment in truth table order,
+    /// unless there are more than [`ENUMERATION_LIMIT`] variables.
+    pub fn counterexample(&self) -> Option<&[bool]> {
+        self.counterexample.as_deref()
+    }
+}
+
+impl Function {
+    /// Evaluates the function with values provided for an arbitrary superset of its variables.
+    pub fn eval_on(&self, vars: &[Variable], values: &[bool]) -> Option<bool> {
+        self.eval_by(|var| {
+            let index = vars.iter().position(|v| v == var)?;
+            values.get(index).copied()
+        })
+    }
+
+    /// Checks whether two functions are equivalent.
+    pub fn compare(&self, other: &Function) -> Comparison {
+        let vars = union(self.vars(), other.vars());
+        let differs = |values: &[bool]| self.eval_on(&vars, values) != other.eval_on(&vars, values);
+        let counterexample = if vars.is_empty() {
+            // `BoolIterator` yields no rows for zero variables.
+            differs(&[]).then(Vec::new)
+        } else if vars.len() > ENUMERATION_LIMIT {
+            self.find_difference(other, &vars)
+        } else {
+            BoolIterator::with_width(vars.len())
+                .expect("at most `ENUMERATION_LIMIT` variables")
+                .map(|row| row.values().rev().collect::<Vec<_>>())
+                .find(|values| differs(values))
+        };
+        Comparison { vars, counterexample }
+    }
+
+    /// Finds an assignment where functions differ using SAT solver.
+    fn find_difference(&self, other: &Function, vars: &[Variable]) -> Option<Vec<bool>> {
+        let mut cnf = Cnf::with_vars(vars.len() as u32);
+        let lhs = self.tseitin(&mut cnf, vars);
+        let rhs = other.tseitin(&mut cnf, vars);
+        // Outputs differ iff `lhs ^ rhs`.
+        cnf.add_clause([lhs, rhs]);
+        cnf.add_clause([!lhs, !rhs]);
+        match Solver::new(&cnf).solve() {
+            Outcome::Sat(mut model) => {
+                model.truncate(vars.len());
+                Some(model)
+            }
+            Outcome::Unsat => None,
+        }
+    }
+}
+
+/// Merges two sorted lists of variables.
+fn union(lhs: &[Variable], rhs: &[Variable]) -> Vec<Variable> {
+    let mut vars = [lhs, rhs].concat();
+    vars.sort_unstable();
+    vars.dedup();
+    vars
+}
+
+#[cfg(test)]
+mod compare_tests {
+    use super::{Function, Variable};
+
+    fn vars(names: &[&str]) -> Vec<Variable> {
+        names.iter().map(|name| Variable::new(name).unwrap()).collect()
+    }
+
+    #[test]
+    fn compare_equivalent() {
+        let lhs = Function::parse("!(x & y)").unwrap();
+        let rhs = Function::parse("!x | !y").unwrap();
+        let cmp = lhs.compare(&rhs);
+        assert_eq!(vars(&["x", "y"]), cmp.vars());
+        assert_eq!(None, cmp.counterexample());
+    }
+
+    #[test]
+    fn compare_counterexample() {
+        let lhs = Function::parse("x | y").unwrap();
+        let rhs = Function::parse("x & y").unwrap();
+        let cmp = lhs.compare(&rhs);
+        assert_eq!(Some([false, true].as_slice()), cmp.counterexample());
+    }
+
+    #[test]
+    fn compare_different_vars() {
+        let lhs = Function::parse("x | !x").unwrap();
+        let rhs = Function::parse("y | 1").unwrap();
+        let cmp = lhs.compare(&rhs);
+        assert_eq!(vars(&["x", "y"]), cmp.vars());
+        assert_eq!(None, cmp.counterexample());
+
+        let rhs = Function::parse("y").unwrap();
+        let cmp = lhs.compare(&rhs);
+        assert_eq!(Some([false, false].as_slice()), cmp.counterexample());
+    }
+
+    #[test]
+    fn compare_large() {
+        let letters = ('a'..='t').map(String::from).collect::<Vec<_>>();
+        let lhs = Function::parse(&format!("!({})", letters.join(" | "))).unwrap();
+        let rhs = Function::parse(&format!("!{}", letters.join(" & !"))).unwrap();
+        assert_eq!(None, lhs.compare(&rhs).counterexample());
+
+        let rhs = Function::parse(&format!("!{} | t", letters.join(" & !"))).unwrap();
+        let cmp = lhs.compare(&rhs);
+        let values = cmp.counterexample().unwrap();
+        assert_ne!(lhs.eval_on(cmp.vars(), values), rhs.eval_on(cmp.vars(), values));
+    }
+
+    #[test]
+    fn compare_constants() {
+        let lhs = Function::parse("1").unwrap();
+        let rhs = Function::parse("!0").unwrap();
+        assert_eq!(None, lhs.compare(&rhs).counterexample());
+
+        let rhs = Function::parse("0").unwrap();
+        assert_eq!(Some([].as_slice()), lhs.compare(&rhs).counterexample());
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt::Display;
+
+use crate::bdd::Bdd;
+
+use super::{Function, Variable};
+
+/// A product of literals, where every variable is either true, false or absent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cube(pub(super) Vec<Option<bool>>);
+
+impl Cube {
+    /// Value of every variable, or `None` if the cube doesn't depend on it.
+    pub fn values(&self) -> &[Option<bool>] {
+        self.0.as_slice()
+    }
+}
+
+/// Writes values as `1`, `0` or `-` for absent variables, like `1-0`.
+impl Display for Cube {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for val in self.0.iter() {
+            let ch = match val {
+                Some(true) => '1',
+                Some(false) => '0',
+                None => '-',
+            };
+            write!(f, "{ch}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Function {
+    /// Splits all assignments into disjoint cubes on which the function is constant.
+    ///
+    /// Cubes are paths of the function's decision diagram, listed in truth table order,
+    /// each paired with the value of the function on it.
+    pub fn cubes(&self) -> Vec<(Cube, bool)> {
+        let mut bdd = Bdd::new(self.vars().len());
+        let root = self.to_bdd(&mut bdd, self.vars());
+        bdd.paths(root)
+            .into_iter()
+            .map(|(path, value)| (Cube(path), value))
+            .collect()
+    }
+
+    /// Splits assignments of `inputs` into disjoint cubes like [`Function::cubes`],
+    /// with `None` instead of the value on cubes of don't-care rows.
+    pub fn cubes_with_dont_care(
+        &self,
+        inputs: &[Variable],
+        dont_care: Option<&Function>,
+    ) -> Vec<(Cube, Option<bool>)> {
+        // An extra last variable is the output on don't-care rows, so only their paths test it.
+        let mut bdd = Bdd::new(inputs.len() + 1);
+        let mut root = self.to_bdd(&mut bdd, inputs);
+        if let Some(dont_care) = dont_care {
+            let dont_care = dont_care.to_bdd(&mut bdd, inputs);
+            let extra = bdd.var(inputs.len());
+            let (care, rows) = (bdd.not(dont_care), bdd.and(dont_care, extra));
+            let cared = bdd.and(care, root);
+            root = bdd.or(cared, rows);
+        }
+        let cubes = bdd.paths(root).into_iter().filter_map(|(mut path, value)| match path.pop().flatten() {
+            Some(false) => None,
+            Some(true) => Some((Cube(path), None)),
+            None => Some((Cube(path), Some(value))),
+        });
+        cubes.collect()
+    }
+}
+
+#[cfg(test)]
+mod cube_tests {
+    use crate::{
+        bool_iterator::BoolIterator,
+        function::{Cube, Function},
+    };
+
+    #[test]
+    fn cubes_of_function() {
+        let func = Function::parse("a & !b | a & b & c").unwrap();
+        let cubes = func
+            .cubes()
+            .iter()
+            .map(|(cube, value)| format!("{cube} {}", *value as u8))
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["0-- 0", "10- 1", "110 0", "111 1"], cubes);
+    }
+
+    #[test]
+    fn cubes_partition_assignments() {
+        let func = Function::parse("a & (b | !c) | !a & d").unwrap();
+        let cubes = func.cubes();
+        let size = |cube: &Cube| 1 << cube.values().iter().filter(|val| val.is_none()).count();
+        assert_eq!(16, cubes.iter().map(|(cube, _)| size(cube)).sum::<u32>());
+        for row in BoolIterator::new(4) {
+            let values = row.values().rev().collect::<Vec<_>>();
+            let matching = cubes
+                .iter()
+                .filter(|(cube, _)| {
+                    let mut pairs = cube.values().iter().zip(&values);
+                    pairs.all(|(cube, val)| cube.is_none_or(|cube| cube == *val))
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(1, matching.len());
+            assert_eq!(func.eval(&values), Some(matching[0].1));
+        }
+    }
+
+    #[test]
+    fn cubes_of_dont_cares() {
+        let func = Function::parse("a & !b | c").unwrap();
+        let dont_care = Function::parse("a & b").unwrap();
+        let cubes = func
+            .cubes_with_dont_care(func.vars(), Some(&dont_care))
+            .iter()
+            .map(|(cube, value)| format!("{cube} {}", value.map_or("X", |value| if value { "1" } else { "0" })))
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["0-0 0", "0-1 1", "10- 1", "11- X"], cubes);
+        let plain = func.cubes().into_iter().map(|(cube, value)| (cube, Some(value))).collect::<Vec<_>>();
+        assert_eq!(plain, func.cubes_with_dont_care(func.vars(), None));
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::{HashMap, HashSet};
+
+use thiserror::Error;
+
+use super::{
+    minterms::parse_sum,
+    network::{network_function, NetworkError, Node},
+    parse::PostfixToken,
+    Function, MultiFunction, ParseError, Variable,
+};
+
+impl MultiFunction {
+    /// Parses a formula, or definitions of named outputs separated by `;`, like `s = a ^ b; c = a & b`.
+    ///
+    /// A single formula is the output `F`.
+    /// Definitions may use outputs defined before or after them, and the rest of their variables are inputs.
+    /// A formula may also list minterms, like `Σm(1, 3, 7) + d(10..15)` over `a`, `b`, `c` and `d`.
+    /// Those variables are inputs, named like `d_2` if an output is named `d`.
+    /// Don't-care rows listed that way, or by formulas like `d(a & b)`, are don't-cares for every output.
+    pub fn parse(s: &str) -> Result<MultiFunction, DefinitionError> {
+        let mut parts = Vec::new();
+        let mut dont_cares = Vec::new();
+        for (index, part) in s.split(';').filter(|part| !part.trim().is_empty()).enumerate() {
+            let number = index + 1;
+            match dont_care_formula(part) {
+                Some(formula) => {
+                    let func = Function::parse(formula).map_err(|error| DefinitionError::Formula(number, error))?;
+                    dont_cares.push(func);
+                }
+                None => parts.push((number, part)),
+            }
+        }
+        let multi = match parts.as_slice() {
+            [] => return Err(DefinitionError::MissingFormula),
+            [(_, part)] if !part.contains('=') => {
+                let (func, dont_care) = match parse_sum(part) {
+                    Some(sum) => sum?,
+                    None => (Function::parse(part)?, None),
+                };
+                dont_cares.extend(dont_care);
+                func.into()
+            }
+            _ => definitions(&parts, &mut dont_cares)?,
+        };
+        Ok(dont_cares.into_iter().fold(multi, MultiFunction::with_dont_care))
+    }
+
+    /// Input with don't-care parts like `d(a & b)` and separators replaced by spaces,
+    /// so that a single formula remains at the same place.
+    pub fn without_dont_cares(s: &str) -> String {
+        let mut blanked = String::with_capacity(s.len());
+        for (index, part) in s.split(';').enumerate() {
+            if index > 0 {
+                blanked.push(' ');
+            }
+            match dont_care_formula(part) {
+                Some(_) => blanked.extend(part.chars().map(|ch| " ".repeat(ch.len_utf8()))),
+                None => blanked.push_str(part),
+            }
+        }
+        blanked
+    }
+}
+
+/// Formula of a part like `d(a & b)`, if it lists don't-care rows.
+///
+/// Brackets are left for the formula to check.
+fn dont_care_formula(part: &str) -> Option<&str> {
+    part.trim().strip_prefix('d').filter(|rest| rest.trim_start().starts_with('('))
+}
+
+/// Outputs defined by numbered parts like `name = formula`, with don't-care rows their minterm lists have.
+fn definitions(parts: &[(usize, &str)], dont_cares: &mut Vec<Function>) -> Result<MultiFunction, DefinitionError> {
+    let mut definitions = Vec::<(Variable, Function)>::with_capacity(parts.len());
+    let mut listed = Vec::with_capacity(parts.len());
+    let mut listed_dont_cares = Vec::new();
+    for &(number, part) in parts {
+        let Some((name, formula)) = part.split_once('=') else {
+            return Err(DefinitionError::MissingName(number));
+        };
+        let Some(name) = Variable::new(name.trim()) else {
+            return Err(DefinitionError::MissingName(number));
+        };
+        if definitions.iter().any(|(defined, _)| *defined == name) {
+            return Err(DefinitionError::Duplicate(name.to_string()));
+        }
+        let sum = parse_sum(formula);
+        listed.push(sum.is_some());
+        let func = match sum {
+            Some(sum) => {
+                let (func, dont_care) = sum?;
+                listed_dont_cares.extend(dont_care);
+                func
+            }
+            None => Function::parse(formula).map_err(|error| DefinitionError::Formula(number, error))?,
+        };
+        definitions.push((name, func));
+    }
+    let renamed = listed_names(&definitions, &listed);
+    for ((_, func), _) in definitions.iter_mut().zip(&listed).filter(|(_, listed)| **listed) {
+        rename(func, &renamed);
+    }
+    for mut dont_care in listed_dont_cares {
+        rename(&mut dont_care, &renamed);
+        dont_cares.push(dont_care);
+    }
+
+    // Definitions take the first nodes, followed by the ones of their formulas.
+    let mut nodes = vec![Node::Const(false); definitions.len()];
+    let mut inputs = HashMap::<&Variable, usize>::new();
+    let mut owners = (0..definitions.len()).collect::<Vec<_>>();
+    for (index, (_, func)) in definitions.iter().enumerate() {
+        let mut stack = Vec::<usize>::new();
+        for token in &func.postfix {
+            let node = match token {
+                PostfixToken::Var(var) => {
+                    match definitions.iter().position(|(name, _)| name == var) {
+                        Some(definition) => {
+                            stack.push(definition);
+                            continue;
+                        }
+                        None => match inputs.get(var) {
+                            Some(&input) => {
+                                stack.push(input);
+                                continue;
+                            }
+                            None => {
+                                inputs.insert(var, nodes.len());
+                                Node::Var(var.clone())
+                            }
+                        },
+                    }
+                }
+                PostfixToken::Const(val) => Node::Const(*val),
+                PostfixToken::Not => Node::Not(stack.pop().unwrap()),
+                PostfixToken::And | PostfixToken::Or | PostfixToken::Xor => {
+                    let rhs = stack.pop().unwrap();
+                    let lhs = stack.pop().unwrap();
+                    match token {
+                        PostfixToken::And => Node::And(vec![lhs, rhs]),
+                        PostfixToken::Or => Node::Or(vec![lhs, rhs]),
+                        _ => Node::Xor([lhs, rhs]),
+                    }
+                }
+            };
+            stack.push(nodes.len());
+            nodes.push(node);
+        }
+        // A conjunction of a single operand stands for it.
+        nodes[index] = Node::And(vec![stack.pop().unwrap()]);
+        owners.resize(nodes.len(), index);
+    }
+
+    let outputs = (0..definitions.len()).map(|index| {
+        let func = network_function(&nodes, index).map_err(|error| match error {
+            NetworkError::Cycle(node) => DefinitionError::Cycle(definitions[owners[node]].0.to_string()),
+            NetworkError::TooLarge => DefinitionError::TooLarge,
+        })?;
+        Ok((definitions[index].0.to_string(), func))
+    });
+    Ok(MultiFunction::new(outputs.collect::<Result<_, DefinitionError>>()?))
+}
+
+/// New names for variables of minterm lists that share a name with a definition, like `f_2` for `f`
+/// in `f = Σm(1, 3, 40)`, since they are always inputs.
+fn listed_names(definitions: &[(Variable, Function)], listed: &[bool]) -> HashMap<Variable, Variable> {
+    let names = definitions.iter().map(|(name, _)| name).collect::<HashSet<_>>();
+    let taken = definitions.iter().flat_map(|(name, func)| std::iter::once(name).chain(&func.variables));
+    let mut taken = taken.cloned().collect::<HashSet<_>>();
+    let mut renamed = HashMap::new();
+    for ((_, func), _) in definitions.iter().zip(listed).filter(|(_, listed)| **listed) {
+        for var in func.variables.iter().filter(|var| names.contains(var)) {
+            if renamed.contains_key(var) {
+                continue;
+            }
+            let mut candidates = (2..).map(|suffix| Variable::new(&format!("{var}_{suffix}")).unwrap());
+            let name = candidates.find(|name| !taken.contains(name)).unwrap();
+            taken.insert(name.clone());
+            renamed.insert(var.clone(), name);
+        }
+    }
+    renamed
+}
+
+/// Renames variables of a function that have a new name.
+fn rename(func: &mut Function, renamed: &HashMap<Variable, Variable>) {
+    let tokens = func.postfix.iter_mut().filter_map(|token| match token {
+        PostfixToken::Var(var) => Some(var),
+        _ => None,
+    });
+    for var in func.variables.iter_mut().chain(tokens) {
+        if let Some(name) = renamed.get(var) {
+            *var = name.clone();
+        }
+    }
+    func.variables.sort();
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DefinitionError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("definition {0}: {1}")]
+    Formula(usize, ParseError),
+    #[error("definition {0} should start with a name and `=`")]
+    MissingName(usize),
+    #[error("`{0}` is defined more than once")]
+    Duplicate(String),
+    #[error("`{0}` depends on itself")]
+    Cycle(String),
+    #[error("definitions are too large to be written out")]
+    TooLarge,
+    #[error("there should be a formula besides don't-care rows")]
+    MissingFormula,
+    #[error("minterms should be listed like `Σm(1, 3, 7) + d(10..15)`")]
+    MintermList,
+    #[error("minterm {0} needs more than 16 variables")]
+    MintermTooLarge(u64),
+    #[error("minterm {0} is listed both as true and as a don't-care")]
+    MintermOverlap(u64),
+}
+
+#[cfg(test)]
+mod definitions_tests {
+    use super::DefinitionError;
+    use crate::function::{MultiFunction, ParseTree};
+
+    #[test]
+    fn full_adder() {
+        let adder = MultiFunction::parse("s = a ^ b ^ c; cout = a & b | c & (a ^ b);").unwrap();
+        assert_eq!(vec!["a", "b", "c"], adder.input_names());
+        let names = adder.outputs().iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["s", "cout"], names);
+        for n in 0..8u8 {
+            let values = [n & 4 != 0, n & 2 != 0, n & 1 != 0];
+            assert_eq!(Some(n.count_ones() % 2 == 1), adder.outputs()[0].1.eval(&values));
+            assert_eq!(Some(n.count_ones() >= 2), adder.outputs()[1].1.eval(&values));
+        }
+    }
+
+    #[test]
+    fn references() {
+        let defs = MultiFunction::parse("f = g | c; g = a & b; h = !f").unwrap();
+        assert_eq!("a & b | c", defs.outputs()[0].1.to_string());
+        assert_eq!("a & b", defs.outputs()[1].1.to_string());
+        assert_eq!("!(a & b | c)", defs.outputs()[2].1.to_string());
+
+        let single = MultiFunction::parse("a | b;").unwrap();
+        assert_eq!(("F", "a | b"), (single.outputs()[0].0.as_str(), single.first().to_string().as_str()));
+    }
+
+    #[test]
+    fn definition_errors() {
+        let error = |s: &str| MultiFunction::parse(s).unwrap_err();
+        assert_eq!(DefinitionError::MissingName(2), error("f = a; b | c"));
+        assert_eq!(DefinitionError::MissingName(1), error("F = a"));
+        assert_eq!(DefinitionError::Duplicate("f".to_string()), error("f = a; f = b"));
+        assert_eq!(DefinitionError::Cycle("f".to_string()), error("f = g & a; g = !f"));
+        assert!(matches!(error("f = a; g = (b"), DefinitionError::Formula(2, _)));
+        assert!(matches!(error("a |"), DefinitionError::Parse(_)));
+        assert!(matches!(error("f = a; d(b"), DefinitionError::Formula(2, _)));
+        assert_eq!(DefinitionError::MissingFormula, error("d(a)"));
+        assert_eq!(DefinitionError::MintermList, error("f = Σm(1, 2"));
+    }
+
+    #[test]
+    fn blanked_dont_cares() {
+        assert_eq!("a & b        ", MultiFunction::without_dont_cares("a & b; d(a|c)"));
+        let tree = ParseTree::parse(&MultiFunction::without_dont_cares("a & b; d(a|c)")).unwrap();
+        assert_eq!(0..5, tree.nodes().last().unwrap().span);
+        assert_eq!("       x | y ", MultiFunction::without_dont_cares("d(!x); x | y;"));
+        assert_eq!("f = a  g = b", MultiFunction::without_dont_cares("f = a; g = b"));
+
+        // Variables only don't-care rows use are inputs of the tree's function too.
+        let text = "a & b; d(c)";
+        let multi = MultiFunction::parse(text).unwrap();
+        let tree = ParseTree::parse(&MultiFunction::without_dont_cares(text)).unwrap();
+        assert_ne!(multi.first(), tree.function());
+        assert_eq!(Some(multi.first()), tree.with_inputs(multi.inputs()).as_ref().map(ParseTree::function));
+        assert_eq!(None, tree.with_inputs(&multi.inputs()[1..]));
+    }
+
+    #[test]
+    fn minterm_list_inputs() {
+        let defs = MultiFunction::parse("f = Σm(1, 3, 40); g = !f").unwrap();
+        assert_eq!(vec!["a", "b", "c", "d", "e", "f_2"], defs.input_names());
+        assert_eq!(Some(true), defs.outputs()[0].1.eval(&[true, false, true, false, false, false]));
+        assert_eq!(Some(false), defs.outputs()[1].1.eval(&[false, false, false, false, true, true]));
+
+        let defs = MultiFunction::parse("a = Σm(1, 2); c = a & b").unwrap();
+        assert_eq!(vec!["a_2", "b"], defs.input_names());
+        assert_eq!(Some(true), defs.outputs()[0].1.eval(&[false, true]));
+        assert_eq!(Some(false), defs.outputs()[1].1.eval(&[true, true]));
+        assert_eq!(Some(true), defs.outputs()[1].1.eval(&[false, true]));
+    }
+
+    #[test]
+    fn dont_care_rows() {
+        let decoder = MultiFunction::parse("Σm(1, 3, 7) + d(10..15)").unwrap();
+        assert_eq!("F", decoder.outputs()[0].0);
+        assert!(decoder.is_dont_care(&[true, true, false, false]));
+        assert!(!decoder.is_dont_care(&[true, false, false, true]));
+
+        let defs = MultiFunction::parse("f = a & b; g = Σm(2) + d(0); d(c)").unwrap();
+        assert_eq!(vec!["a", "b", "c"], defs.input_names());
+        assert_eq!("f = a & b; g = a & !b; d(c | !a & !b)", defs.to_string());
+    }
+}
@@ -0,0 +1,96 @@
+use std::ops::Range;
+
+use thiserror::Error;
+
+use super::{compare::Comparison, rewrite::Law, Function, ParseError};
+
+/// Check of a single equality between neighbouring formulas of a derivation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equality {
+    /// Bytes of the chain taken by the formula on the left, without surrounding whitespace.
+    pub lhs: Range<usize>,
+    pub rhs: Range<usize>,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// Formulas are equivalent, and one becomes the other by applying the law once.
+    Law(Law),
+    /// Formulas are equivalent, but it takes more than a single law to show it.
+    Equivalent,
+    /// Formulas differ, at the counterexample of the comparison.
+    Differs(Comparison),
+}
+
+/// Checks a derivation written as a chain of formulas, like `a & (a | b) = a | a & b = a`.
+pub fn check_derivation(chain: &str) -> Result<Vec<Equality>, DerivationError> {
+    let mut formulas = Vec::new();
+    let mut start = 0;
+    for (index, part) in chain.split('=').enumerate() {
+        let trimmed = part.trim_start();
+        let offset = start + part.len() - trimmed.len();
+        let span = offset..offset + trimmed.trim_end().len();
+        let func = Function::parse(part).map_err(|error| DerivationError::Formula(index + 1, error))?;
+        formulas.push((span, func));
+        start += part.len() + '='.len_utf8();
+    }
+    if formulas.len() < 2 {
+        return Err(DerivationError::SingleFormula);
+    }
+
+    let equalities = formulas.windows(2).map(|pair| {
+        let [(lhs_span, lhs), (rhs_span, rhs)] = pair else { unreachable!() };
+        let comparison = lhs.compare(rhs);
+        let verdict = match comparison.counterexample() {
+            Some(_) => Verdict::Differs(comparison),
+            None => match lhs.to_expr().law_between(&rhs.to_expr()) {
+                Some(law) => Verdict::Law(law),
+                None => Verdict::Equivalent,
+            },
+        };
+        Equality { lhs: lhs_span.clone(), rhs: rhs_span.clone(), verdict }
+    });
+    Ok(equalities.collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum DerivationError {
+    #[error("formula {0}: {1}")]
+    Formula(usize, ParseError),
+    #[error("expected at least two formulas separated by `=`")]
+    SingleFormula,
+}
+
+#[cfg(test)]
+mod derivation_tests {
+    use super::{check_derivation, DerivationError, Verdict};
+    use crate::function::rewrite::Law;
+
+    #[test]
+    fn derivation_valid() {
+        let chain = "!(a & !b) = !a | !!b = !a | b";
+        let equalities = check_derivation(chain).unwrap();
+        assert_eq!(2, equalities.len());
+        assert_eq!(Verdict::Law(Law::DeMorgan), equalities[0].verdict);
+        assert_eq!(Verdict::Law(Law::DoubleNegation), equalities[1].verdict);
+        assert_eq!("!a | !!b", &chain[equalities[1].lhs.clone()]);
+        assert_eq!("!a | b", &chain[equalities[1].rhs.clone()]);
+    }
+
+    #[test]
+    fn derivation_invalid() {
+        let equalities = check_derivation("a & (b | c) = a & b | c = a & b | a & c").unwrap();
+        let Verdict::Differs(comparison) = &equalities[0].verdict else {
+            panic!("first step is wrong");
+        };
+        assert_eq!(Some([false, false, true].as_slice()), comparison.counterexample());
+        assert_eq!(Verdict::Equivalent, check_derivation("a | !a & b = a | b").unwrap()[0].verdict);
+    }
+
+    #[test]
+    fn derivation_errors() {
+        assert_eq!(Err(DerivationError::SingleFormula), check_derivation("a & b"));
+        assert!(matches!(check_derivation("a = b = (c"), Err(DerivationError::Formula(3, _))));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    bool_iterator::BoolIterator,
+    sat::{Cnf, Lit, Var},
+};
+
+use super::{parse::PostfixToken, Function, Variable};
+
+/// A way to express a function in conjunctive normal form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CnfEncoding {
+    /// Tseitin transformation, which is linear in the size of the formula but adds variables.
+    Tseitin,
+    /// Product of maxterms, which is exponential in the number of variables and takes at most 64 of them.
+    Canonical,
+}
+
+impl Function {
+    /// Builds a function from a formula in conjunctive normal form.
+    ///
+    /// CNF variable `i` is named `x{i + 1}`, following DIMACS numbering.
+    /// Variables that don't occur in any clause aren't variables of the function.
+    pub fn from_cnf(cnf: &Cnf) -> Function {
+        let name = |var: Var| Variable::new(&format!("x{}", var.0 + 1)).unwrap();
+
+        let mut postfix = Vec::new();
+        for (i, clause) in cnf.clauses().iter().enumerate() {
+            for (j, lit) in clause.iter().enumerate() {
+                postfix.push(PostfixToken::Var(name(lit.var())));
+                if lit.is_negative() {
+                    postfix.push(PostfixToken::Not);
+                }
+                if j > 0 {
+                    postfix.push(PostfixToken::Or);
+                }
+            }
+            if clause.is_empty() {
+                postfix.push(PostfixToken::Const(false));
+            }
+            if i > 0 {
+                postfix.push(PostfixToken::And);
+            }
+        }
+        if postfix.is_empty() {
+            postfix.push(PostfixToken::Const(true));
+        }
+
+        let mut vars = cnf
+            .clauses()
+            .iter()
+            .flatten()
+            .map(|lit| lit.var())
+            .collect::<Vec<_>>();
+        vars.sort_unstable();
+        vars.dedup();
+        let variables = vars.into_iter().map(name).collect();
+        Function { variables, postfix }
+    }
+
+    /// Expresses the function in conjunctive normal form.
+    ///
+    /// Variables of the function are the first CNF variables, in the same order.
+    pub fn to_cnf(&self, encoding: CnfEncoding) -> Cnf {
+        let mut cnf = Cnf::with_vars(self.vars().len() as u32);
+        match encoding {
+            CnfEncoding::Tseitin => {
+                let root = self.tseitin(&mut cnf, self.vars());
+                cnf.add_clause([root]);
+            }
+            CnfEncoding::Canonical if self.vars().is_empty() => {
+                if self.eval(&[]) == Some(false) {
+                    cnf.add_clause([]);
+                }
+            }
+            CnfEncoding::Canonical => {
+                // Every row where function is false is excluded by a clause.
+                let mut values = Vec::with_capacity(self.vars().len());
+                for row in BoolIterator::with_width(self.vars().len()).expect("too many variables for canonical CNF") {
+                    values.clear();
+                    values.extend(row.values());
+                    if self.eval(&values) == Some(false) {
+                        let clause = values
+                            .iter()
+                            .enumerate()
+                            .map(|(i, val)| Lit::new(Var(i as u32), *val))
+                            .collect::<Vec<_>>();
+                        cnf.add_clause(clause);
+                    }
+                }
+            }
+        }
+        cnf
+    }
+
+    /// Writes the function in DIMACS CNF format, with variable names in comments.
+    pub fn to_dimacs(&self, encoding: CnfEncoding) -> String {
+        let comments = self
+            .vars()
+            .iter()
+            .enumerate()
+            .map(|(i, var)| format!("{} = {var}", i + 1))
+            .collect::<Vec<_>>();
+        self.to_cnf(encoding).to_dimacs(&comments)
+    }
+}
+
+#[cfg(test)]
+mod dimacs_tests {
+    use super::CnfEncoding;
+    use crate::{
+        bool_iterator::BoolIterator,
+        function::Function,
+        sat::{Cnf, Outcome, Solver},
+    };
+
+    #[test]
+    fn from_cnf() {
+        let cnf = Cnf::from_dimacs("p cnf 10 2\n1 -10 0\n2 0\n").unwrap();
+        let func = Function::from_cnf(&cnf);
+        assert_eq!("(x1 | !x10) & x2", func.to_string());
+        let names = func.vars().iter().map(|var| var.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["x1", "x2", "x10"], names);
+    }
+
+    #[test]
+    fn from_cnf_trivial() {
+        let func = Function::from_cnf(&Cnf::from_dimacs("p cnf 0 0\n").unwrap());
+        assert_eq!(Some(true), func.eval(&[]));
+
+        let func = Function::from_cnf(&Cnf::from_dimacs("p cnf 1 2\n1 0\n0\n").unwrap());
+        assert_eq!(Some(false), func.eval(&[true]));
+    }
+
+    #[test]
+    fn canonical_cnf() {
+        let func = Function::parse("a & !b | c").unwrap();
+        let cnf = func.to_cnf(CnfEncoding::Canonical);
+        assert_eq!(3, cnf.clauses().len());
+        // Variables `a`, `b`, `c` become `x1`, `x2`, `x3` in the same order.
+        let imported = Function::from_cnf(&cnf);
+        for row in BoolIterator::new(3) {
+            let values = row.values().collect::<Vec<_>>();
+            assert_eq!(func.eval(&values), imported.eval(&values));
+        }
+    }
+
+    #[test]
+    fn tseitin_cnf() {
+        let func = Function::parse("a & !a").unwrap();
+        let cnf = func.to_cnf(CnfEncoding::Tseitin);
+        assert_eq!(Outcome::Unsat, Solver::new(&cnf).solve());
+
+        let func = Function::parse("a & !b").unwrap();
+        let cnf = func.to_cnf(CnfEncoding::Tseitin);
+        match Solver::new(&cnf).solve() {
+            Outcome::Sat(model) => assert_eq!([true, false], model[..2]),
+            Outcome::Unsat => panic!("function is satisfiable"),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt::Display;
+
+use super::{parse::PostfixToken, Function, Variable};
+
+/// Formula as a tree, with chains of the same operator gathered into a single node.
+///
+/// Operands of `And` and `Or` nodes are in the order they're written in.
+/// Exclusive or keeps its two operands, so chains like `a ^ b ^ c` nest to the left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Const(bool),
+    Var(Variable),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Xor(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Conjunction or disjunction of operands, depending on `is_and`.
+    ///
+    /// Without operands it's the identity of the operator, and a single operand is returned as is.
+    pub fn join(is_and: bool, mut operands: Vec<Expr>) -> Expr {
+        match operands.len() {
+            0 => Expr::Const(is_and),
+            1 => operands.pop().unwrap(),
+            _ if is_and => Expr::And(operands),
+            _ => Expr::Or(operands),
+        }
+    }
+
+    pub fn not(self) -> Expr {
+        Expr::Not(Box::new(self))
+    }
+
+    /// Operands of `And` or `Or` node, and whether it's `And`.
+    pub fn operands(&self) -> Option<(bool, &[Expr])> {
+        match self {
+            Expr::And(operands) => Some((true, operands)),
+            Expr::Or(operands) => Some((false, operands)),
+            _ => None,
+        }
+    }
+}
+
+impl Function {
+    /// Builds the formula tree.
+    ///
+    /// Chains like `a & b & c` become a single node, but explicit brackets like in `a & (b & c)` are kept.
+    pub fn to_expr(&self) -> Expr {
+        let mut stack = Vec::<Expr>::with_capacity(16);
+        for token in self.postfix.iter() {
+            let expr = match token {
+                PostfixToken::And | PostfixToken::Or => {
+                    let rhs = stack.pop().unwrap();
+                    let lhs = stack.pop().unwrap();
+                    let is_and = *token == PostfixToken::And;
+                    let mut operands = match lhs {
+                        Expr::And(operands) if is_and => operands,
+                        Expr::Or(operands) if !is_and => operands,
+                        lhs => vec![lhs],
+                    };
+                    operands.push(rhs);
+                    Expr::join(is_and, operands)
+                }
+                PostfixToken::Xor => {
+                    let rhs = stack.pop().unwrap();
+                    let lhs = stack.pop().unwrap();
+                    Expr::Xor(Box::new(lhs), Box::new(rhs))
+                }
+                PostfixToken::Not => stack.pop().unwrap().not(),
+                PostfixToken::Var(var) => Expr::Var(var.clone()),
+                PostfixToken::Const(val) => Expr::Const(*val),
+            };
+            stack.push(expr);
+        }
+        stack.pop().unwrap()
+    }
+}
+
+/// Writes the formula in infix notation, like [`Function`] does.
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wrapped = |expr: &Expr, wrap: bool| match wrap {
+            true => format!("({expr})"),
+            false => expr.to_string(),
+        };
+        match self {
+            Expr::Const(val) => write!(f, "{}", *val as u8),
+            Expr::Var(var) => write!(f, "{var}"),
+            Expr::Not(inner) => {
+                let wrap = matches!(**inner, Expr::And(_) | Expr::Or(_) | Expr::Xor(..));
+                write!(f, "!{}", wrapped(inner, wrap))
+            }
+            Expr::And(operands) | Expr::Or(operands) => {
+                let is_and = matches!(self, Expr::And(_));
+                let operands = operands.iter().map(|operand| {
+                    // Operands of the same operator only appear in explicit brackets.
+                    let wrap = match operand {
+                        Expr::And(_) | Expr::Xor(..) => is_and,
+                        Expr::Or(_) => true,
+                        _ => false,
+                    };
+                    wrapped(operand, wrap)
+                });
+                let separator = if is_and { " & " } else { " | " };
+                f.write_str(&operands.collect::<Vec<_>>().join(separator))
+            }
+            Expr::Xor(lhs, rhs) => {
+                let lhs = wrapped(lhs, matches!(**lhs, Expr::Or(_)));
+                let rhs = wrapped(rhs, matches!(**rhs, Expr::Or(_) | Expr::Xor(..)));
+                write!(f, "{lhs} ^ {rhs}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod expr_tests {
+    use super::Expr;
+    use crate::function::Function;
+
+    #[test]
+    fn expr_roundtrip() {
+        let formulas = ["a & b & c", "a & (b & c)", "!(a | b) & c | 0", "(a | b) & !!c", "a | b | (c | d)"];
+        for formula in formulas.into_iter().chain(["a ^ b ^ c", "a ^ (b ^ c)", "(a ^ b) & c | !(a | b ^ c)"]) {
+            let func = Function::parse(formula).unwrap();
+            let expr = func.to_expr();
+            assert_eq!(formula, expr.to_string());
+        }
+    }
+
+    #[test]
+    fn expr_chains() {
+        let expr = Function::parse("a & b & c | d").unwrap().to_expr();
+        let Expr::Or(operands) = &expr else { panic!("root should be `|`") };
+        assert_eq!(Some((true, 3)), operands[0].operands().map(|(is_and, ops)| (is_and, ops.len())));
+        assert_eq!(Expr::Const(true), Expr::join(true, Vec::new()));
+    }
+}
@@ -0,0 +1,492 @@
+use std::fmt::Write;
+
+use crate::bool_iterator::BoolIterator;
+
+use super::{circuit::Gate, expr::Expr, network::signal_prefix, Function, GateSet, MultiFunction, Variable};
+
+/// Name of the generated module or entity; its testbench gets `_tb` appended.
+const MODULE: &str = "boolean_function";
+
+/// Hardware description language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hdl {
+    Verilog,
+    Vhdl,
+}
+
+/// How the output of the generated module is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HdlBody {
+    /// Single continuous assignment of the formula.
+    Assignment,
+    /// One gate per line, from gates of the set.
+    Netlist(GateSet),
+}
+
+impl Hdl {
+    /// Extension of source files in the language.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Hdl::Verilog => "v",
+            Hdl::Vhdl => "vhd",
+        }
+    }
+
+    /// Name of the variable in the language, escaped if it's a reserved word or a name the generated code uses.
+    fn name(self, var: &Variable) -> String {
+        let name = var.to_string();
+        match self {
+            Hdl::Verilog if VERILOG_RESERVED.contains(&name.as_str()) => format!("\\{name} "),
+            Hdl::Verilog => name,
+            // Names are case-insensitive, so `f` is the output, and basic identifiers can't have
+            // consecutive underscores or end with one.
+            Hdl::Vhdl => {
+                let reserved = VHDL_RESERVED.contains(&name.as_str()) || name == "f";
+                match reserved || name.contains("__") || name.ends_with('_') {
+                    true => format!("\\{name}\\"),
+                    false => name,
+                }
+            }
+        }
+    }
+
+    /// Name of the output port, escaped like names of variables unless it's `F`.
+    fn port(self, name: &str) -> String {
+        match Variable::new(name) {
+            Some(var) => self.name(&var),
+            None => name.to_string(),
+        }
+    }
+
+    fn constant(self, val: bool) -> &'static str {
+        match (self, val) {
+            (Hdl::Verilog, false) => "1'b0",
+            (Hdl::Verilog, true) => "1'b1",
+            (Hdl::Vhdl, false) => "'0'",
+            (Hdl::Vhdl, true) => "'1'",
+        }
+    }
+
+    fn write_expr(self, expr: &Expr, out: &mut String) {
+        // VHDL has no precedence between `and`, `xor` and `or`, so every operand that is an operation gets brackets.
+        let write_operand = |operand: &Expr, out: &mut String, wrap: bool| {
+            let wrap = wrap || self == Hdl::Vhdl && matches!(operand, Expr::And(_) | Expr::Or(_) | Expr::Xor(..));
+            if wrap {
+                out.push('(');
+            }
+            self.write_expr(operand, out);
+            if wrap {
+                out.push(')');
+            }
+        };
+        match expr {
+            Expr::Const(val) => out.push_str(self.constant(*val)),
+            Expr::Var(var) => out.push_str(&self.name(var)),
+            Expr::Not(inner) => {
+                out.push_str(if self == Hdl::Verilog { "~" } else { "not " });
+                // VHDL doesn't allow `not not a`, as `not` only applies to a primary.
+                let nested = self == Hdl::Vhdl && matches!(**inner, Expr::Not(_));
+                write_operand(inner, out, nested || matches!(**inner, Expr::And(_) | Expr::Or(_) | Expr::Xor(..)));
+            }
+            Expr::And(operands) | Expr::Or(operands) => {
+                let is_and = matches!(expr, Expr::And(_));
+                let separator = match (self, is_and) {
+                    (Hdl::Verilog, true) => " & ",
+                    (Hdl::Verilog, false) => " | ",
+                    (Hdl::Vhdl, true) => " and ",
+                    (Hdl::Vhdl, false) => " or ",
+                };
+                for (i, operand) in operands.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(separator);
+                    }
+                    // Explicit brackets around the same operator are kept, like in the formula.
+                    let wrap = match operand {
+                        Expr::And(_) | Expr::Xor(..) => is_and,
+                        Expr::Or(_) => true,
+                        _ => false,
+                    };
+                    write_operand(operand, out, wrap);
+                }
+            }
+            // Verilog's `^` binds between `&` and `|`, like in the formula.
+            Expr::Xor(lhs, rhs) => {
+                write_operand(lhs, out, matches!(**lhs, Expr::Or(_)));
+                out.push_str(if self == Hdl::Verilog { " ^ " } else { " xor " });
+                write_operand(rhs, out, matches!(**rhs, Expr::Or(_) | Expr::Xor(..)));
+            }
+        }
+    }
+}
+
+impl Function {
+    /// Writes a synthesizable module with an input port per variable and output `F`.
+    pub fn to_hdl(&self, hdl: Hdl, body: HdlBody) -> String {
+        MultiFunction::from(self.clone()).to_hdl(hdl, body)
+    }
+}
+
+impl MultiFunction {
+    /// Writes a synthesizable module with an input port per input and an output port per output.
+    ///
+    /// Netlists of several outputs share gates.
+    pub fn to_hdl(&self, hdl: Hdl, body: HdlBody) -> String {
+        let names = self.inputs().iter().map(|var| hdl.name(var)).collect::<Vec<_>>();
+        let ports = self.outputs().iter().map(|(name, _)| hdl.port(name)).collect::<Vec<_>>();
+        let mut out = String::new();
+        // Gates of a netlist go before the assignments of outputs.
+        let mut gates = Vec::new();
+        let mut assignments = Vec::new();
+        let mut signals = Vec::new();
+        match body {
+            HdlBody::Assignment => {
+                for (port, (_, func)) in ports.iter().zip(self.outputs()) {
+                    let mut expr = String::new();
+                    hdl.write_expr(&func.to_expr(), &mut expr);
+                    assignments.push((port.clone(), expr));
+                }
+            }
+            HdlBody::Netlist(set) => {
+                let circuit = self.to_circuit(set);
+                let inputs = self.inputs().iter().map(|var| var.as_str());
+                let prefix = signal_prefix(inputs.chain(self.outputs().iter().map(|(name, _)| name.as_str())));
+                let wire = |index: usize| match circuit.gates()[index] {
+                    Gate::Input(var) => names[var].clone(),
+                    Gate::Const(val) => hdl.constant(val).to_string(),
+                    _ => format!("{prefix}{index}"),
+                };
+                for (index, gate) in circuit.gates().iter().enumerate() {
+                    let operands = gate.operands().iter().map(|&operand| wire(operand)).collect::<Vec<_>>();
+                    let (primitive, operator) = match gate {
+                        Gate::Input(_) | Gate::Const(_) => continue,
+                        Gate::Not(_) => ("not", ""),
+                        Gate::And(_) => ("and", " and "),
+                        Gate::Or(_) => ("or", " or "),
+                        Gate::Nand(_) => ("nand", " and "),
+                        Gate::Nor(_) => ("nor", " or "),
+                    };
+                    signals.push(wire(index));
+                    let expr = match hdl {
+                        Hdl::Verilog => format!("{primitive} ({}, {});", wire(index), operands.join(", ")),
+                        Hdl::Vhdl if operands.len() == 1 => format!("not {}", operands[0]),
+                        // Unlike `and` and `or`, `nand` and `nor` can't be chained.
+                        Hdl::Vhdl if operands.len() == 2 && primitive.starts_with('n') => {
+                            format!("{} {primitive} {}", operands[0], operands[1])
+                        }
+                        Hdl::Vhdl if primitive.starts_with('n') => format!("not ({})", operands.join(operator)),
+                        Hdl::Vhdl => operands.join(operator),
+                    };
+                    gates.push((wire(index), expr));
+                }
+                for (port, &output) in ports.iter().zip(circuit.outputs()) {
+                    assignments.push((port.clone(), wire(output)));
+                }
+            }
+        }
+
+        match hdl {
+            Hdl::Verilog => {
+                for (name, func) in self.outputs() {
+                    writeln!(out, "// {name} = {func}").unwrap();
+                }
+                writeln!(out, "module {MODULE} (").unwrap();
+                for name in &names {
+                    writeln!(out, "    input wire {name},").unwrap();
+                }
+                let outputs = ports.iter().map(|port| format!("    output wire {port}")).collect::<Vec<_>>();
+                writeln!(out, "{}\n);", outputs.join(",\n")).unwrap();
+                if !signals.is_empty() {
+                    writeln!(out, "    wire {};", signals.join(", ")).unwrap();
+                }
+                for (_, instance) in gates {
+                    writeln!(out, "    {instance}").unwrap();
+                }
+                for (target, expr) in assignments {
+                    writeln!(out, "    assign {target} = {expr};").unwrap();
+                }
+                writeln!(out, "endmodule").unwrap();
+            }
+            Hdl::Vhdl => {
+                for (name, func) in self.outputs() {
+                    writeln!(out, "-- {name} = {func}").unwrap();
+                }
+                writeln!(out, "library ieee;\nuse ieee.std_logic_1164.all;\n").unwrap();
+                writeln!(out, "entity {MODULE} is\n    port (").unwrap();
+                for name in &names {
+                    writeln!(out, "        {name} : in std_logic;").unwrap();
+                }
+                let outputs = ports.iter().map(|port| format!("        {port} : out std_logic")).collect::<Vec<_>>();
+                writeln!(out, "{}\n    );\nend entity;\n", outputs.join(";\n")).unwrap();
+                writeln!(out, "architecture rtl of {MODULE} is").unwrap();
+                if !signals.is_empty() {
+                    writeln!(out, "    signal {} : std_logic;", signals.join(", ")).unwrap();
+                }
+                writeln!(out, "begin").unwrap();
+                for (target, expr) in gates.into_iter().chain(assignments) {
+                    writeln!(out, "    {target} <= {expr};").unwrap();
+                }
+                writeln!(out, "end architecture;").unwrap();
+            }
+        }
+        out
+    }
+
+    /// Writes a testbench that sets the inputs of the module to every row of the truth table
+    /// and reports rows where any output differs from its function.
+    ///
+    /// Rows are numbered like truth table rows, with the first input being the most significant bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than 64 inputs.
+    pub fn hdl_testbench(&self, hdl: Hdl) -> String {
+        let names = self.inputs().iter().map(|var| hdl.name(var)).collect::<Vec<_>>();
+        let outputs = self.outputs().iter().map(|(name, _)| (name.as_str(), hdl.port(name))).collect::<Vec<_>>();
+        let ports = outputs.iter().map(|(_, port)| port.clone()).collect::<Vec<_>>();
+        let n = self.inputs().len();
+        // There's a single row without variables, which the iterator doesn't yield.
+        let rows = BoolIterator::with_width(n).expect("testbench is only written for few variables");
+        let rows = rows.map(|row| row.values().rev().collect::<Vec<_>>());
+        let rows = if n == 0 { vec![Vec::new()] } else { rows.collect() };
+
+        let mut out = String::new();
+        let (assign, template) = match hdl {
+            Hdl::Verilog => {
+                let connections = names.iter().chain(&ports).map(|name| format!(".{name}({name})"));
+                let regs = match names.is_empty() {
+                    true => String::new(),
+                    false => format!("\n    reg {};", names.join(", ")),
+                };
+                write!(out, "`timescale 1ns / 1ps\n\nmodule {MODULE}_tb;{regs}").unwrap();
+                out.push_str(&verilog_check(&outputs));
+                let connections = connections.collect::<Vec<_>>().join(", ");
+                write!(out, "\n    {MODULE} dut ({connections});\n\n    initial begin\n").unwrap();
+                (" = ", VERILOG_END)
+            }
+            Hdl::Vhdl => {
+                let signals = names.iter().chain(&ports).map(String::as_str).collect::<Vec<_>>();
+                let connections = signals.iter().map(|name| format!("{name} => {name}")).collect::<Vec<_>>();
+                writeln!(out, "library ieee;\nuse ieee.std_logic_1164.all;\n").unwrap();
+                writeln!(out, "entity {MODULE}_tb is\nend entity;\n").unwrap();
+                writeln!(out, "architecture test of {MODULE}_tb is").unwrap();
+                writeln!(out, "    signal {} : std_logic;", signals.join(", ")).unwrap();
+                writeln!(out, "begin\n    dut : entity work.{MODULE} port map ({});", connections.join(", ")).unwrap();
+                writeln!(out, "{}    begin", vhdl_check(&outputs)).unwrap();
+                (" <= ", VHDL_END)
+            }
+        };
+        for (index, values) in rows.iter().enumerate() {
+            out.push_str("        ");
+            for (name, val) in names.iter().zip(values) {
+                write!(out, "{name}{assign}{}; ", hdl.constant(*val)).unwrap();
+            }
+            let bits = self.outputs().iter().map(|(_, func)| if func.eval(values).unwrap() { '1' } else { '0' });
+            let bits = bits.collect::<String>();
+            let expected = match (hdl, outputs.as_slice()) {
+                (_, [_]) => hdl.constant(bits == "1").to_string(),
+                (Hdl::Verilog, _) => format!("{}'b{bits}", outputs.len()),
+                (Hdl::Vhdl, _) => format!("\"{bits}\""),
+            };
+            writeln!(out, "check({index}, {expected});").unwrap();
+        }
+        out.push_str(template);
+        out
+    }
+}
+
+/// Declarations of the Verilog testbench, with a task that checks outputs against the expected values,
+/// which are a vector for several outputs.
+fn verilog_check(outputs: &[(&str, String)]) -> String {
+    let ports = outputs.iter().map(|(_, port)| port.as_str()).collect::<Vec<_>>().join(", ");
+    let names = outputs.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ");
+    let (width, actual, label) = match outputs {
+        [_] => (String::new(), ports.clone(), names),
+        _ => (format!("[{}:0] ", outputs.len() - 1), format!("{{{ports}}}"), format!("{{{names}}}")),
+    };
+    format!(
+        r#"
+    wire {ports};
+    integer errors = 0;
+
+    task check(input integer row, input {width}expected);
+        begin
+            #1;
+            if ({actual} !== expected) begin
+                $display("row %0d: {label} = %b, expected %b", row, {actual}, expected);
+                errors = errors + 1;
+            end
+        end
+    endtask
+"#
+    )
+}
+
+const VERILOG_END: &str = r#"        if (errors == 0)
+            $display("PASSED");
+        else
+            $display("FAILED: %0d rows differ", errors);
+        $finish;
+    end
+endmodule
+"#;
+
+/// Start of the VHDL testbench process, with a procedure that checks outputs against the expected values,
+/// which are a vector for several outputs.
+fn vhdl_check(outputs: &[(&str, String)]) -> String {
+    let (kind, expected) = match outputs {
+        [_] => ("std_logic".to_string(), vec!["expected".to_string()]),
+        _ => {
+            let expected = (0..outputs.len()).map(|i| format!("expected({i})"));
+            (format!("std_logic_vector(0 to {})", outputs.len() - 1), expected.collect())
+        }
+    };
+    let differs = outputs.iter().zip(&expected).map(|((_, port), expected)| format!("{port} /= {expected}"));
+    let actual = outputs.iter().enumerate().map(|(i, (name, port))| {
+        let separator = if i == 0 { ": " } else { ", " };
+        format!("\"{separator}{name} = \" & std_logic'image({port})")
+    });
+    let actual = actual.collect::<Vec<_>>().join(" & ");
+    let expected = expected.iter().map(|expected| format!("std_logic'image({expected})"));
+    let expected = expected.collect::<Vec<_>>().join(" & \", \" & ");
+    let differs = differs.collect::<Vec<_>>().join(" or ");
+    format!(
+        r#"
+    stimulus : process
+        variable errors : natural := 0;
+
+        procedure check(row : natural; expected : {kind}) is
+        begin
+            wait for 1 ns;
+            if {differs} then
+                report "row " & integer'image(row) & {actual}
+                    & ", expected " & {expected} severity error;
+                errors := errors + 1;
+            end if;
+        end procedure;
+"#
+    )
+}
+
+const VHDL_END: &str = r#"        if errors = 0 then
+            report "PASSED";
+        else
+            report "FAILED: " & integer'image(errors) & " rows differ" severity failure;
+        end if;
+        wait;
+    end process;
+end architecture;
+"#;
+
+/// Keywords of Verilog 2005, and names used by the generated code.
+const VERILOG_RESERVED: &[&str] = &[
+    "always", "and", "assign", "automatic", "begin", "buf", "bufif0", "bufif1", "case", "casex", "casez",
+    "cell", "cmos", "config", "deassign", "default", "defparam", "design", "disable", "edge", "else", "end",
+    "endcase", "endconfig", "endfunction", "endgenerate", "endmodule", "endprimitive", "endspecify",
+    "endtable", "endtask", "event", "for", "force", "forever", "fork", "function", "generate", "genvar",
+    "highz0", "highz1", "if", "ifnone", "incdir", "include", "initial", "inout", "input", "instance",
+    "integer", "join", "large", "liblist", "library", "localparam", "macromodule", "medium", "module",
+    "nand", "negedge", "nmos", "nor", "noshowcancelled", "not", "notif0", "notif1", "or", "output",
+    "parameter", "pmos", "posedge", "primitive", "pull0", "pull1", "pulldown", "pullup",
+    "pulsestyle_ondetect", "pulsestyle_onevent", "rcmos", "real", "realtime", "reg", "release", "repeat",
+    "rnmos", "rpmos", "rtran", "rtranif0", "rtranif1", "scalared", "showcancelled", "signed", "small",
+    "specify", "specparam", "strong0", "strong1", "supply0", "supply1", "table", "task", "time", "tran",
+    "tranif0", "tranif1", "tri", "tri0", "tri1", "triand", "trior", "trireg", "unsigned", "use", "uwire",
+    "vectored", "wait", "wand", "weak0", "weak1", "while", "wire", "wor", "xnor", "xor",
+    MODULE, "boolean_function_tb", "check", "dut", "errors", "expected", "row",
+];
+
+/// Reserved words of VHDL 2008, and names used by the generated code.
+const VHDL_RESERVED: &[&str] = &[
+    "abs", "access", "after", "alias", "all", "and", "architecture", "array", "assert", "assume",
+    "assume_guarantee", "attribute", "begin", "block", "body", "buffer", "bus", "case", "component",
+    "configuration", "constant", "context", "cover", "default", "disconnect", "downto", "else", "elsif",
+    "end", "entity", "exit", "fairness", "file", "for", "force", "function", "generate", "generic", "group",
+    "guarded", "if", "impure", "in", "inertial", "inout", "is", "label", "library", "linkage", "literal",
+    "loop", "map", "mod", "nand", "new", "next", "nor", "not", "null", "of", "on", "open", "or", "others",
+    "out", "package", "parameter", "port", "postponed", "procedure", "process", "property", "protected",
+    "pure", "range", "record", "register", "reject", "release", "rem", "report", "restrict",
+    "restrict_guarantee", "return", "rol", "ror", "select", "sequence", "severity", "shared", "signal",
+    "sla", "sll", "sra", "srl", "strong", "subtype", "then", "to", "transport", "type", "unaffected",
+    "units", "until", "use", "variable", "vmode", "vprop", "vunit", "wait", "when", "while", "with",
+    "xnor", "xor",
+    MODULE, "boolean_function_tb", "check", "dut", "errors", "expected", "ieee", "integer", "natural",
+    "row", "rtl", "std", "std_logic", "std_logic_1164", "stimulus", "test", "work",
+];
+
+#[cfg(test)]
+mod hdl_tests {
+    use super::{Hdl, HdlBody};
+    use crate::function::{Function, GateSet, MultiFunction};
+
+    #[test]
+    fn verilog_module() {
+        let func = Function::parse("a & !(b | c) | 1").unwrap();
+        let verilog = func.to_hdl(Hdl::Verilog, HdlBody::Assignment);
+        assert!(verilog.contains("    input wire a,\n    input wire b,\n    input wire c,\n    output wire F\n);"));
+        assert!(verilog.contains("    assign F = a & ~(b | c) | 1'b1;\n"));
+
+        let netlist = Function::parse("a | b").unwrap().to_hdl(Hdl::Verilog, HdlBody::Netlist(GateSet::Nand));
+        assert!(netlist.contains("    wire n2, n3, n4;\n"));
+        assert!(netlist.contains("    nand (n4, n2, n3);\n    assign F = n4;\n"));
+    }
+
+    #[test]
+    fn vhdl_entity() {
+        let func = Function::parse("a & b & (c | !d)").unwrap();
+        let vhdl = func.to_hdl(Hdl::Vhdl, HdlBody::Assignment);
+        assert!(vhdl.contains("    F <= a and b and (c or not d);\n"));
+        let vhdl = Function::parse("!!a | !!!b").unwrap().to_hdl(Hdl::Vhdl, HdlBody::Assignment);
+        assert!(vhdl.contains("    F <= not (not a) or not (not (not b));\n"));
+        let verilog = Function::parse("!!a").unwrap().to_hdl(Hdl::Verilog, HdlBody::Assignment);
+        assert!(verilog.contains("    assign F = ~~a;\n"));
+        let func = Function::parse("a & (b ^ c) ^ !(a | b)").unwrap();
+        let verilog = func.to_hdl(Hdl::Verilog, HdlBody::Assignment);
+        assert!(verilog.contains("    assign F = a & (b ^ c) ^ ~(a | b);\n"));
+        let vhdl = func.to_hdl(Hdl::Vhdl, HdlBody::Assignment);
+        assert!(vhdl.contains("    F <= (a and (b xor c)) xor not (a or b);\n"));
+
+        let netlist = Function::parse("!(a | b | c)").unwrap().to_hdl(Hdl::Vhdl, HdlBody::Netlist(GateSet::Nor));
+        assert!(netlist.contains("    n3 <= not (a or b or c);\n    F <= n3;\n"));
+    }
+
+    #[test]
+    fn reserved_names() {
+        let func = Function::parse("and & f & n1 & x__1").unwrap();
+        let verilog = func.to_hdl(Hdl::Verilog, HdlBody::Netlist(GateSet::AndOrNot));
+        assert!(verilog.contains("and (nn4, \\and , f, n1, x__1);"));
+        let vhdl = func.to_hdl(Hdl::Vhdl, HdlBody::Assignment);
+        assert!(vhdl.contains("F <= \\and\\ and \\f\\ and n1 and \\x__1\\;"));
+    }
+
+    #[test]
+    fn testbench_rows() {
+        let func = MultiFunction::parse("a & !b").unwrap();
+        let verilog = func.hdl_testbench(Hdl::Verilog);
+        assert!(verilog.contains("        a = 1'b1; b = 1'b0; check(2, 1'b1);\n"));
+        assert_eq!(4, verilog.matches("check(").count() - 1);
+        let vhdl = func.hdl_testbench(Hdl::Vhdl);
+        assert!(vhdl.contains("        a <= '0'; b <= '1'; check(1, '0');\n"));
+
+        let constant = MultiFunction::parse("1").unwrap().hdl_testbench(Hdl::Verilog);
+        assert!(constant.contains("        check(0, 1'b1);\n"));
+    }
+
+    #[test]
+    fn multiple_outputs() {
+        let adder = MultiFunction::parse("s = a ^ b ^ c; cout = a & b | c & (a ^ b)").unwrap();
+        let verilog = adder.to_hdl(Hdl::Verilog, HdlBody::Assignment);
+        assert!(verilog.contains("    input wire c,\n    output wire s,\n    output wire cout\n);\n"));
+        assert!(verilog.contains("    assign s = a ^ b ^ c;\n    assign cout = a & b | c & (a ^ b);\n"));
+        let netlist = adder.to_hdl(Hdl::Vhdl, HdlBody::Netlist(GateSet::Nand));
+        assert!(netlist.contains("        s : out std_logic;\n        cout : out std_logic\n    );\n"));
+        assert!(netlist.contains("    s <= n10;\n    cout <= n11;\n"));
+
+        let verilog = adder.hdl_testbench(Hdl::Verilog);
+        assert!(verilog.contains("    task check(input integer row, input [1:0] expected);\n"));
+        assert!(verilog.contains("dut (.a(a), .b(b), .c(c), .s(s), .cout(cout));"));
+        assert!(verilog.contains("        a = 1'b0; b = 1'b1; c = 1'b1; check(3, 2'b01);\n"));
+        let vhdl = adder.hdl_testbench(Hdl::Vhdl);
+        assert!(vhdl.contains("            if s /= expected(0) or cout /= expected(1) then\n"));
+        assert!(vhdl.contains("        a <= '1'; b <= '0'; c <= '0'; check(4, \"10\");\n"));
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{parse::PostfixToken, Cube, Function, MultiFunction, Variable};
+
+/// Maximal number of inputs for which functions are minimized, as that takes every assignment
+/// and every prime implicant into account.
+pub const MINIMIZATION_LIMIT: usize = 10;
+
+/// Outputs that share product terms when minimized jointly, as their sets are kept in a bit mask.
+const GROUP_SIZE: usize = 64;
+
+/// Products of literals that make up a sum of products for every output of a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cover {
+    inputs: Vec<Variable>,
+    names: Vec<String>,
+    /// Distinct products, each with the outputs that use it.
+    terms: Vec<(Cube, Vec<bool>)>,
+}
+
+/// A product of literals, where variable `i` of `width` is the bit `width - 1 - i` like in a minterm index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Implicant {
+    /// Bits of the variables the product doesn't depend on.
+    mask: u32,
+    /// Values of the other variables, with masked bits cleared.
+    value: u32,
+}
+
+impl Implicant {
+    fn contains(self, minterm: u32) -> bool {
+        minterm & !self.mask == self.value
+    }
+
+    fn minterms(self) -> impl Iterator<Item = u32> {
+        // Enumerates subsets of the mask, from the whole mask down to the empty one.
+        let mut subset = Some(self.mask);
+        std::iter::from_fn(move || {
+            let current = subset?;
+            subset = (current != 0).then(|| (current - 1) & self.mask);
+            Some(self.value | current)
+        })
+    }
+
+    fn to_cube(self, width: usize) -> Cube {
+        let values = (0..width).map(|i| {
+            let bit = 1 << (width - 1 - i);
+            (self.mask & bit == 0).then_some(self.value & bit != 0)
+        });
+        Cube(values.collect())
+    }
+}
+
+impl MultiFunction {
+    /// Finds a sum of products with few terms and literals for every output.
+    ///
+    /// If `joint`, a product may be used by several outputs and is counted once,
+    /// so an output may take a smaller product than it would on its own if another output needs it anyway.
+    /// The cover is built greedily after taking essential primes, so it isn't always the smallest one.
+    /// Products may cover don't-care rows whenever that makes them smaller, whatever the outputs are there.
+    ///
+    /// There should be at most [`MINIMIZATION_LIMIT`] inputs.
+    pub fn minimize(&self, joint: bool) -> Cover {
+        let width = self.inputs().len();
+        assert!(width <= MINIMIZATION_LIMIT, "too many inputs to minimize");
+        let rows = (0..1u32 << width)
+            .map(|minterm| (0..width).map(|i| minterm >> (width - 1 - i) & 1 == 1).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let dont_cares = rows.iter().map(|row| self.is_dont_care(row)).collect::<Vec<_>>();
+        let ones = self
+            .outputs()
+            .iter()
+            .map(|(_, func)| {
+                let ones = rows.iter().zip(&dont_cares).map(|(row, dont_care)| !dont_care && func.eval(row).unwrap());
+                ones.collect()
+            })
+            .collect::<Vec<Vec<bool>>>();
+
+        let size = if joint { GROUP_SIZE } else { 1 };
+        let mut terms = HashMap::<Implicant, Vec<bool>>::new();
+        for (group, ones) in ones.chunks(size).enumerate() {
+            let tags = (0..1usize << width)
+                .map(|minterm| {
+                    let outputs = ones.iter().enumerate().filter(|(_, ones)| ones[minterm]);
+                    outputs.fold(0u64, |tag, (output, _)| tag | 1 << output)
+                })
+                .collect::<Vec<_>>();
+            // Products are grown over don't-care rows as if every output were true there.
+            let all = u64::MAX >> (64 - ones.len());
+            let grown = tags.iter().zip(&dont_cares).map(|(tag, dont_care)| if *dont_care { all } else { *tag });
+            for (implicant, used) in select(&tags, &primes(width, &grown.collect::<Vec<_>>())) {
+                let outputs = terms.entry(implicant).or_insert_with(|| vec![false; self.outputs().len()]);
+                for output in (0..ones.len()).filter(|output| used & 1 << output != 0) {
+                    outputs[group * size + output] = true;
+                }
+            }
+        }
+        let mut terms = terms.into_iter().collect::<Vec<_>>();
+        terms.sort_unstable_by_key(|(implicant, _)| (std::cmp::Reverse(implicant.value), implicant.mask));
+        Cover {
+            inputs: self.inputs().to_vec(),
+            names: self.outputs().iter().map(|(name, _)| name.clone()).collect(),
+            terms: terms.into_iter().map(|(implicant, outputs)| (implicant.to_cube(width), outputs)).collect(),
+        }
+    }
+}
+
+/// Products that are implicants of the outputs in their tag, but of none of them once a variable is dropped.
+///
+/// `tags` has the set of outputs that are true, or don't matter, for every minterm.
+fn primes(width: usize, tags: &[u64]) -> Vec<(Implicant, u64)> {
+    let mut level = (0..tags.len() as u32)
+        .filter(|&minterm| tags[minterm as usize] != 0)
+        .map(|minterm| (Implicant { mask: 0, value: minterm }, tags[minterm as usize]))
+        .collect::<HashMap<_, _>>();
+    let mut primes = Vec::new();
+    while !level.is_empty() {
+        let mut next = HashMap::new();
+        // Implicants that are merged into a larger one for all of their outputs.
+        let mut merged = HashSet::new();
+        for (&implicant, &tag) in &level {
+            for bit in (0..width).map(|i| 1 << i) {
+                if (implicant.mask | implicant.value) & bit != 0 {
+                    continue;
+                }
+                let other = Implicant { value: implicant.value | bit, ..implicant };
+                let Some(&other_tag) = level.get(&other) else {
+                    continue;
+                };
+                let shared = tag & other_tag;
+                if shared == 0 {
+                    continue;
+                }
+                next.insert(Implicant { mask: implicant.mask | bit, ..implicant }, shared);
+                if shared == tag {
+                    merged.insert(implicant);
+                }
+                if shared == other_tag {
+                    merged.insert(other);
+                }
+            }
+        }
+        primes.extend(level.into_iter().filter(|(implicant, _)| !merged.contains(implicant)));
+        level = next;
+    }
+    primes.sort_unstable();
+    primes
+}
+
+/// Chooses primes that cover every output at every minterm, with outputs each chosen prime is used for.
+fn select(tags: &[u64], primes: &[(Implicant, u64)]) -> Vec<(Implicant, u64)> {
+    let mut uncovered = tags.to_vec();
+    let mut chosen = Vec::<(Implicant, u64)>::new();
+    let choose = |index: usize, uncovered: &mut Vec<u64>, chosen: &mut Vec<(Implicant, u64)>| {
+        let (implicant, tag) = primes[index];
+        let used = implicant.minterms().fold(0, |used, minterm| used | uncovered[minterm as usize] & tag);
+        for minterm in implicant.minterms() {
+            uncovered[minterm as usize] &= !tag;
+        }
+        chosen.push((implicant, used));
+    };
+
+    // A prime is essential if it's the only one that covers some output at some minterm.
+    let mut covering = vec![Vec::new(); tags.len()];
+    for (index, (implicant, _)) in primes.iter().enumerate() {
+        for minterm in implicant.minterms() {
+            covering[minterm as usize].push(index);
+        }
+    }
+    for (minterm, primes_at) in covering.iter().enumerate() {
+        let mut outputs = tags[minterm];
+        while outputs != 0 {
+            let output = outputs & outputs.wrapping_neg();
+            outputs &= !output;
+            let mut candidates = primes_at.iter().filter(|&&index| primes[index].1 & output != 0);
+            if let (Some(&index), None) = (candidates.next(), candidates.next()) {
+                if uncovered[minterm] & output != 0 {
+                    choose(index, &mut uncovered, &mut chosen);
+                }
+            }
+        }
+    }
+
+    // Then the prime that covers most of what's left is taken, preferring fewer literals.
+    loop {
+        let gain = |&(implicant, tag): &(Implicant, u64)| {
+            implicant.minterms().map(|minterm| (uncovered[minterm as usize] & tag).count_ones()).sum::<u32>()
+        };
+        let best = (0..primes.len())
+            .map(|index| (gain(&primes[index]), primes[index].0.mask.count_ones(), std::cmp::Reverse(index)))
+            .max()
+            .filter(|(gain, _, _)| *gain > 0);
+        let Some((_, _, std::cmp::Reverse(index))) = best else {
+            break;
+        };
+        choose(index, &mut uncovered, &mut chosen);
+    }
+
+    // Products chosen early may have become redundant for some outputs.
+    for index in (0..chosen.len()).rev() {
+        let (implicant, used) = chosen[index];
+        for output in (0..64).map(|output| 1u64 << output).filter(|output| used & output != 0) {
+            let redundant = implicant.minterms().all(|minterm| {
+                tags[minterm as usize] & output == 0
+                    || chosen.iter().enumerate().any(|(other, &(cover, cover_used))| {
+                        other != index && cover_used & output != 0 && cover.contains(minterm)
+                    })
+            });
+            if redundant {
+                chosen[index].1 &= !output;
+            }
+        }
+    }
+    chosen.retain(|(_, used)| *used != 0);
+    chosen
+}
+
+impl Cover {
+    /// Collects products over `inputs`, each with whether every output uses it.
+    pub(super) fn new(inputs: Vec<Variable>, names: Vec<String>, terms: Vec<(Cube, Vec<bool>)>) -> Self {
+        Self { inputs, names, terms }
+    }
+
+    /// Inputs of the function, in the order of the values of products.
+    pub fn inputs(&self) -> &[Variable] {
+        self.inputs.as_slice()
+    }
+
+    /// Names of outputs.
+    pub fn names(&self) -> &[String] {
+        self.names.as_slice()
+    }
+
+    /// Distinct products, each with whether every output uses it.
+    pub fn terms(&self) -> &[(Cube, Vec<bool>)] {
+        self.terms.as_slice()
+    }
+
+    /// Number of literals in all distinct products.
+    pub fn literals(&self) -> usize {
+        let literals = self.terms.iter().map(|(cube, _)| cube.values().iter().flatten().count());
+        literals.sum()
+    }
+
+    /// Sum of the products the output uses, or `0` if there are none.
+    pub fn function(&self, output: usize) -> Function {
+        let mut postfix = Vec::new();
+        let terms = self.terms.iter().filter(|(_, outputs)| outputs[output]);
+        for (i, (cube, _)) in terms.enumerate() {
+            let literals = self.inputs.iter().zip(cube.values()).filter_map(|(var, val)| Some((var, (*val)?)));
+            let start = postfix.len();
+            for (j, (var, val)) in literals.enumerate() {
+                postfix.push(PostfixToken::Var(var.clone()));
+                if !val {
+                    postfix.push(PostfixToken::Not);
+                }
+                if j > 0 {
+                    postfix.push(PostfixToken::And);
+                }
+            }
+            if postfix.len() == start {
+                postfix.push(PostfixToken::Const(true));
+            }
+            if i > 0 {
+                postfix.push(PostfixToken::Or);
+            }
+        }
+        if postfix.is_empty() {
+            postfix.push(PostfixToken::Const(false));
+        }
+        Function { variables: self.inputs.clone(), postfix }
+    }
+}
+
+#[cfg(test)]
+mod minimize_tests {
+    use super::{primes, Implicant};
+    use crate::function::{Function, MultiFunction};
+
+    fn multi(outputs: &[(&str, &str)]) -> MultiFunction {
+        let outputs = outputs.iter().map(|(name, formula)| (name.to_string(), Function::parse(formula).unwrap()));
+        MultiFunction::new(outputs.collect())
+    }
+
+    #[test]
+    fn prime_implicants() {
+        // a & b | c over a, b, c.
+        let tags = (0..8).map(|minterm| (minterm & 1 != 0 || minterm >= 6) as u64).collect::<Vec<_>>();
+        assert_eq!(
+            vec![(Implicant { mask: 0b001, value: 0b110 }, 1), (Implicant { mask: 0b110, value: 0b001 }, 1)],
+            primes(3, &tags),
+        );
+    }
+
+    #[test]
+    fn minimize_single() {
+        let cover = multi(&[("F", "a & b & c | a & b & !c | !a & b & c | a & !b & c")]).minimize(false);
+        assert_eq!("a & b | a & c | b & c", cover.function(0).to_string());
+        assert_eq!(6, cover.literals());
+
+        let cover = multi(&[("t", "a | !a"), ("f", "a & !a")]).minimize(true);
+        assert_eq!("1", cover.function(0).to_string());
+        assert_eq!("0", cover.function(1).to_string());
+    }
+
+    #[test]
+    fn minimize_shares_terms() {
+        let multi = multi(&[("f", "x & y | z"), ("g", "x & y & !z")]);
+        let separate = multi.minimize(false);
+        let joint = multi.minimize(true);
+        assert_eq!(3, separate.terms().len());
+        assert_eq!(2, joint.terms().len());
+        assert_eq!("x & y & !z | z", joint.function(0).to_string());
+        for cover in [separate, joint] {
+            for (output, (_, func)) in multi.outputs().iter().enumerate() {
+                assert_eq!(None, func.compare(&cover.function(output)).counterexample());
+            }
+        }
+    }
+
+    #[test]
+    fn minimize_dont_cares() {
+        let multi = MultiFunction::parse("Σm(1, 3, 7, 11, 15) + d(0, 2, 5)").unwrap();
+        let cover = multi.minimize(false);
+        assert_eq!("c & d | !a & !b", cover.function(0).to_string());
+
+        let multi = MultiFunction::parse("f = a & b & c; g = a & !b & c; d(a & !c)").unwrap();
+        let joint = multi.minimize(true);
+        assert_eq!("a & b", joint.function(0).to_string());
+        assert_eq!("a & !b", joint.function(1).to_string());
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashSet;
+
+use super::{network::MAX_TOKENS, Cover, Cube, DefinitionError, Function, Variable, ENUMERATION_LIMIT};
+
+/// Function that is true exactly at provided minterm indices, with the first variable being the most significant.
+///
+/// Consecutive minterms are merged into cubes, so ranges stay short.
+pub(super) fn sum_of_minterms(vars: &[Variable], minterms: &mut Vec<u64>) -> Function {
+    let width = vars.len();
+    minterms.sort_unstable();
+    minterms.dedup();
+    let mut terms = Vec::new();
+    let mut index = 0;
+    while index < minterms.len() {
+        let start = minterms[index];
+        let mut end = start;
+        while index + 1 < minterms.len() && minterms[index + 1] == end + 1 {
+            index += 1;
+            end += 1;
+        }
+        index += 1;
+        // The run is split into aligned blocks, each of which is a cube.
+        let mut low = start;
+        while low <= end {
+            let mut size = 1u64;
+            while low.is_multiple_of(size * 2) && low + size * 2 - 1 <= end && size * 2 <= 1 << width {
+                size *= 2;
+            }
+            let values = (0..width).map(|i| {
+                let bit = 1 << (width - 1 - i);
+                (bit >= size).then_some(low & bit != 0)
+            });
+            terms.push((Cube(values.collect()), vec![true]));
+            low += size;
+        }
+    }
+    Cover::new(vars.to_vec(), vec!["F".to_string()], terms).function(0)
+}
+
+/// Reads a list of minterms with don't-cares, like `Σm(1, 3, 7) + d(10..15)`, or `None` if it isn't one.
+///
+/// `Σ` may be left out, and ranges include both ends.
+/// Variables are `a`, `b` and so on, as many as the largest minterm index needs.
+/// Returns the function with don't-care rows, if any are listed.
+pub(super) fn parse_sum(s: &str) -> Option<Result<(Function, Option<Function>), DefinitionError>> {
+    let s = s.trim();
+    let rest = s.strip_prefix('Σ').unwrap_or(s).trim_start();
+    let rest = rest.strip_prefix('m').map(str::trim_start).filter(|rest| rest.starts_with('('));
+    let rest = match rest {
+        Some(rest) => rest,
+        None if s.starts_with('Σ') => return Some(Err(DefinitionError::MintermList)),
+        None => return None,
+    };
+    Some(sum(rest))
+}
+
+fn sum(s: &str) -> Result<(Function, Option<Function>), DefinitionError> {
+    let (ones, rest) = list(s).ok_or(DefinitionError::MintermList)?;
+    let dont_cares = match rest.trim() {
+        "" => None,
+        rest => {
+            let rest = rest.strip_prefix('+').map(str::trim_start).ok_or(DefinitionError::MintermList)?;
+            let rest = rest.strip_prefix('d').map(str::trim_start).ok_or(DefinitionError::MintermList)?;
+            match list(rest) {
+                Some((dont_cares, "")) => Some(dont_cares),
+                _ => return Err(DefinitionError::MintermList),
+            }
+        }
+    };
+
+    let largest = ones.iter().chain(dont_cares.iter().flatten()).max().copied().unwrap_or(0);
+    let width = (u64::BITS - largest.leading_zeros()).max(1) as usize;
+    if width > ENUMERATION_LIMIT {
+        return Err(DefinitionError::MintermTooLarge(largest));
+    }
+    let listed = dont_cares.iter().flatten().collect::<HashSet<_>>();
+    if let Some(&minterm) = ones.iter().find(|minterm| listed.contains(minterm)) {
+        return Err(DefinitionError::MintermOverlap(minterm));
+    }
+    let vars = (b'a'..).take(width).map(|ch| Variable::new(&(ch as char).to_string()).unwrap()).collect::<Vec<_>>();
+    let mut ones = ones;
+    let func = sum_of_minterms(&vars, &mut ones);
+    let dont_care = dont_cares.map(|mut dont_cares| sum_of_minterms(&vars, &mut dont_cares));
+    let too_large = |func: &Function| func.postfix.len() > MAX_TOKENS;
+    if too_large(&func) || dont_care.as_ref().is_some_and(too_large) {
+        return Err(DefinitionError::TooLarge);
+    }
+    Ok((func, dont_care))
+}
+
+/// Reads a bracketed list of minterms and ranges, like `(1, 3..5)`, returning the rest of the string.
+fn list(s: &str) -> Option<(Vec<u64>, &str)> {
+    let (list, rest) = s.strip_prefix('(')?.split_once(')')?;
+    let mut minterms = Vec::new();
+    for item in list.split(',').map(str::trim) {
+        if item.is_empty() && !list.contains(',') {
+            continue;
+        }
+        let (start, end) = item.split_once("..").unwrap_or((item, item));
+        let (start, end) = (start.trim().parse::<u64>().ok()?, end.trim().parse::<u64>().ok()?);
+        if start > end {
+            return None;
+        }
+        if end >> ENUMERATION_LIMIT != 0 {
+            // Such indices are reported once the number of variables is known.
+            minterms.push(end);
+            continue;
+        }
+        minterms.extend(start..=end);
+    }
+    Some((minterms, rest))
+}
+
+#[cfg(test)]
+mod minterms_tests {
+    use super::{parse_sum, sum_of_minterms};
+    use crate::function::{DefinitionError, Function};
+
+    #[test]
+    fn minterm_ranges() {
+        let func = Function::parse("a | b | c").unwrap();
+        assert_eq!("!a & b | a", sum_of_minterms(func.vars(), &mut vec![2, 3, 4, 5, 6, 7]).to_string());
+        assert_eq!("!a & !b & c | a & b & !c", sum_of_minterms(func.vars(), &mut vec![6, 1]).to_string());
+        assert_eq!("1", sum_of_minterms(func.vars(), &mut (0..8).collect()).to_string());
+        assert_eq!("0", sum_of_minterms(func.vars(), &mut vec![]).to_string());
+    }
+
+    #[test]
+    fn minterm_lists() {
+        let (func, dont_care) = parse_sum("Σm(1, 3, 7) + d(10..15)").unwrap().unwrap();
+        let vars = func.vars().iter().map(|var| var.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["a", "b", "c", "d"], vars);
+        assert_eq!("!a & !b & !c & d | !a & !b & c & d | !a & b & c & d", func.to_string());
+        assert_eq!("a & !b & c | a & b", dont_care.unwrap().to_string());
+        let (func, dont_care) = parse_sum("m ( 0 )").unwrap().unwrap();
+        assert_eq!(("!a", None), (func.to_string().as_str(), dont_care));
+
+        assert!(parse_sum("a | m").is_none());
+        assert_eq!(Some(Err(DefinitionError::MintermList)), parse_sum("Σ(1)"));
+        assert_eq!(Some(Err(DefinitionError::MintermList)), parse_sum("Σm(1, x)"));
+        assert_eq!(Some(Err(DefinitionError::MintermList)), parse_sum("Σm(1) d(2)"));
+        assert_eq!(Some(Err(DefinitionError::MintermList)), parse_sum("Σm(5..3)"));
+        assert_eq!(Some(Err(DefinitionError::MintermOverlap(3))), parse_sum("Σm(1, 3) + d(2..4)"));
+        let overlap = parse_sum("Σm(0..32767, 40000) + d(32768..65535)");
+        assert_eq!(Some(Err(DefinitionError::MintermOverlap(40000))), overlap);
+        assert_eq!(Some(Err(DefinitionError::MintermTooLarge(70000))), parse_sum("Σm(70000)"));
+    }
+}
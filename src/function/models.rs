@@ -0,0 +1,148 @@
+use crate::bdd::{Bdd, NodeRef, Solutions};
+
+use super::{parse::PostfixToken, Function, Variable};
+
+/// Satisfying assignments of a function, projected onto some of its variables.
+#[derive(Debug, Clone)]
+pub struct Models {
+    vars: Vec<Variable>,
+    /// Whether each variable of the function is projected onto.
+    projected: Vec<bool>,
+    bdd: Bdd,
+    root: NodeRef,
+}
+
+impl Models {
+    /// Variables that models assign, in the same order as in the function.
+    pub fn vars(&self) -> &[Variable] {
+        self.vars.as_slice()
+    }
+
+    /// Number of distinct models, or `u128::MAX` if there are more.
+    pub fn count(&self) -> u128 {
+        self.bdd.count(self.root, &self.projected)
+    }
+
+    /// All models in truth table order, with values of [`Models::vars`].
+    pub fn iter(&self) -> Solutions<'_> {
+        self.bdd.solutions(self.root, &self.projected)
+    }
+}
+
+impl Function {
+    /// Builds a decision diagram of the function, with `inputs[i]` being diagram's variable `i`.
+    pub fn to_bdd(&self, bdd: &mut Bdd, inputs: &[Variable]) -> NodeRef {
+        let nodes = self
+            .vars()
+            .iter()
+            .map(|var| {
+                let index = inputs
+                    .iter()
+                    .position(|input| input == var)
+                    .expect("every variable of the function should be an input");
+                bdd.var(index)
+            })
+            .collect::<Vec<_>>();
+        self.compose_bdd(bdd, &nodes)
+    }
+
+    /// Builds a decision diagram of the function, with its variable `i` replaced by diagram `nodes[i]`.
+    pub fn compose_bdd(&self, bdd: &mut Bdd, nodes: &[NodeRef]) -> NodeRef {
+        let mut stack = Vec::<NodeRef>::with_capacity(16);
+        for token in self.postfix.iter() {
+            let node = match token {
+                PostfixToken::And => {
+                    let (a, b) = (stack.pop().unwrap(), stack.pop().unwrap());
+                    bdd.and(a, b)
+                }
+                PostfixToken::Or => {
+                    let (a, b) = (stack.pop().unwrap(), stack.pop().unwrap());
+                    bdd.or(a, b)
+                }
+                PostfixToken::Xor => {
+                    let (a, b) = (stack.pop().unwrap(), stack.pop().unwrap());
+                    bdd.xor(a, b)
+                }
+                PostfixToken::Not => {
+                    let a = stack.pop().unwrap();
+                    bdd.not(a)
+                }
+                PostfixToken::Const(val) => bdd.constant(*val),
+                PostfixToken::Var(var) => nodes[self.var_index(var).unwrap()],
+            };
+            stack.push(node);
+        }
+        stack.pop().expect("function should not be empty")
+    }
+
+    /// Satisfying assignments projected onto provided variables.
+    ///
+    /// Two models are the same if they differ only in variables that aren't projected onto.
+    /// Variables that aren't variables of the function are ignored.
+    pub fn models_over(&self, projection: &[Variable]) -> Models {
+        let mut bdd = Bdd::new(self.vars().len());
+        let root = self.to_bdd(&mut bdd, self.vars());
+        let projected = self
+            .vars()
+            .iter()
+            .map(|var| projection.contains(var))
+            .collect::<Vec<_>>();
+        let quantified = projected.iter().map(|projected| !projected).collect::<Vec<_>>();
+        let root = bdd.exists(root, &quantified);
+        let vars = self
+            .vars()
+            .iter()
+            .filter(|var| projection.contains(var))
+            .cloned()
+            .collect();
+        Models { vars, projected, bdd, root }
+    }
+}
+
+#[cfg(test)]
+mod models_tests {
+    use crate::function::{Function, Variable};
+
+    #[test]
+    fn count_models() {
+        let func = Function::parse("a & b | c").unwrap();
+        assert_eq!(5, func.models_over(func.vars()).count());
+        let func = Function::parse("a & !a").unwrap();
+        assert_eq!(0, func.models_over(func.vars()).count());
+        let func = Function::parse("1").unwrap();
+        assert_eq!(1, func.models_over(func.vars()).count());
+    }
+
+    #[test]
+    fn count_many_models() {
+        // Half of all assignments of 26 variables.
+        let letters = ('a'..='z').map(String::from).collect::<Vec<_>>();
+        let func = Function::parse(&format!("({}) & a", letters.join(" | "))).unwrap();
+        assert_eq!(1 << 25, func.models_over(func.vars()).count());
+    }
+
+    #[test]
+    fn enumerate_models() {
+        let func = Function::parse("a & b | c").unwrap();
+        let models = func.models_over(func.vars());
+        let expected = (0..8u8)
+            .map(|n| vec![n & 4 != 0, n & 2 != 0, n & 1 != 0])
+            .filter(|v| func.eval(v) == Some(true))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, models.iter().collect::<Vec<_>>());
+        assert_eq!(Some(expected[3].clone()), models.iter().nth(3));
+    }
+
+    #[test]
+    fn project_models() {
+        let func = Function::parse("a & b | c").unwrap();
+        let projection = [Variable::new("a").unwrap(), Variable::new("b").unwrap()];
+        let models = func.models_over(&projection);
+        assert_eq!(&projection, models.vars());
+        assert_eq!(4, models.count());
+
+        let models = func.models_over(&[]);
+        assert_eq!(1, models.count());
+        assert_eq!(vec![Vec::<bool>::new()], models.iter().collect::<Vec<_>>());
+    }
+}
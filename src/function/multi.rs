@@ -0,0 +1,203 @@
+use std::fmt::Display;
+
+use super::{parse::PostfixToken, Function, Variable};
+
+/// Several named functions of the same inputs, like the outputs of a circuit.
+///
+/// Every output is a function of all inputs, even if it doesn't depend on some of them,
+/// so their truth tables line up.
+/// Rows may be don't-cares, like input combinations that can't occur, where no output matters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiFunction {
+    inputs: Vec<Variable>,
+    outputs: Vec<(String, Function)>,
+    /// Function of the inputs that is true on don't-care rows, if there are any.
+    dont_care: Option<Function>,
+}
+
+impl MultiFunction {
+    /// Combines functions into outputs, with inputs being all of their variables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no outputs.
+    pub fn new(outputs: Vec<(String, Function)>) -> Self {
+        let mut inputs = outputs.iter().flat_map(|(_, func)| func.vars().iter().cloned()).collect::<Vec<_>>();
+        inputs.sort_unstable();
+        inputs.dedup();
+        Self::with_inputs(inputs, outputs)
+    }
+
+    /// Combines functions into outputs of provided sorted inputs, which should include all of their variables.
+    pub(super) fn with_inputs(inputs: Vec<Variable>, outputs: Vec<(String, Function)>) -> Self {
+        assert!(!outputs.is_empty(), "there should be at least one output");
+        let outputs = outputs
+            .into_iter()
+            .map(|(name, func)| {
+                debug_assert!(func.vars().iter().all(|var| inputs.binary_search(var).is_ok()));
+                (name, Function { variables: inputs.clone(), postfix: func.postfix })
+            })
+            .collect();
+        Self { inputs, outputs, dont_care: None }
+    }
+
+    /// Makes rows where `dont_care` is true don't-cares, besides the ones that already are.
+    ///
+    /// Its variables become inputs too.
+    pub fn with_dont_care(self, dont_care: Function) -> Self {
+        let mut inputs = self.inputs;
+        inputs.extend(dont_care.vars().iter().cloned());
+        inputs.sort_unstable();
+        inputs.dedup();
+        let mut postfix = dont_care.postfix;
+        if let Some(existing) = self.dont_care {
+            postfix.splice(0..0, existing.postfix);
+            postfix.push(PostfixToken::Or);
+        }
+        let mut multi = Self::with_inputs(inputs, self.outputs);
+        multi.dont_care = Some(Function { variables: multi.inputs.clone(), postfix });
+        multi
+    }
+
+    /// Makes the row with provided minterm index a don't-care, or not one if it is.
+    pub fn toggle_dont_care(&self, minterm: u64) -> Self {
+        // Exclusive or with the product that is true only on the row flips it.
+        let width = self.inputs.len();
+        let mut postfix = self.dont_care.as_ref().map(|dont_care| dont_care.postfix.clone()).unwrap_or_default();
+        for (i, var) in self.inputs.iter().enumerate() {
+            postfix.push(PostfixToken::Var(var.clone()));
+            if minterm.checked_shr((width - 1 - i) as u32).unwrap_or(0) & 1 == 0 {
+                postfix.push(PostfixToken::Not);
+            }
+            if i > 0 {
+                postfix.push(PostfixToken::And);
+            }
+        }
+        if width == 0 {
+            postfix.push(PostfixToken::Const(true));
+        }
+        if self.dont_care.is_some() {
+            postfix.push(PostfixToken::Xor);
+        }
+        let dont_care = Function { variables: self.inputs.clone(), postfix };
+        let mut multi = Self::with_inputs(self.inputs.clone(), self.outputs.clone());
+        // Toggling the last don't-care row leaves none.
+        if dont_care.satisfy().is_some() {
+            multi.dont_care = Some(dont_care);
+        }
+        multi
+    }
+
+    /// Variables shared by all outputs, sorted.
+    pub fn inputs(&self) -> &[Variable] {
+        self.inputs.as_slice()
+    }
+
+    /// Names of the [`MultiFunction::inputs`], for comparing them in tests.
+    #[cfg(test)]
+    pub(crate) fn input_names(&self) -> Vec<&str> {
+        self.inputs.iter().map(Variable::as_str).collect()
+    }
+
+    /// Names of outputs with their functions, whose variables are the [`MultiFunction::inputs`].
+    pub fn outputs(&self) -> &[(String, Function)] {
+        self.outputs.as_slice()
+    }
+
+    /// The first output.
+    pub fn first(&self) -> &Function {
+        &self.outputs[0].1
+    }
+
+    /// Function of the inputs that is true on don't-care rows, if there are any.
+    pub fn dont_care(&self) -> Option<&Function> {
+        self.dont_care.as_ref()
+    }
+
+    /// Checks whether the row with provided values of inputs is a don't-care.
+    pub fn is_dont_care(&self, values: &[bool]) -> bool {
+        self.dont_care.as_ref().is_some_and(|dont_care| dont_care.eval(values).unwrap())
+    }
+}
+
+/// Writes a single output named `F` as its formula, and other outputs as definitions, like `s = a; c = b`.
+///
+/// Don't-care rows follow as `d(formula)`, like `a & b; d(!a)`.
+impl Display for MultiFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.outputs.as_slice() {
+            [(name, func)] if name == "F" => write!(f, "{func}")?,
+            outputs => {
+                let definitions = outputs.iter().map(|(name, func)| format!("{name} = {func}"));
+                f.write_str(&definitions.collect::<Vec<_>>().join("; "))?
+            }
+        }
+        match &self.dont_care {
+            Some(dont_care) => write!(f, "; d({dont_care})"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A single output named `F`.
+impl From<Function> for MultiFunction {
+    fn from(func: Function) -> Self {
+        Self::new(vec![("F".to_string(), func)])
+    }
+}
+
+#[cfg(test)]
+mod multi_tests {
+    use super::MultiFunction;
+    use crate::function::Function;
+
+    #[test]
+    fn shared_inputs() {
+        let sum = Function::parse("a & !b | !a & b").unwrap();
+        let carry = Function::parse("b & c").unwrap();
+        let multi = MultiFunction::new(vec![("s".to_string(), sum), ("carry".to_string(), carry)]);
+        assert_eq!(vec!["a", "b", "c"], multi.input_names());
+        for (_, func) in multi.outputs() {
+            assert_eq!(multi.inputs(), func.vars());
+        }
+        assert_eq!(Some(true), multi.outputs()[0].1.eval(&[true, false, true]));
+        assert_eq!(Some(false), multi.outputs()[1].1.eval(&[true, false, true]));
+        assert_eq!("s = a & !b | !a & b; carry = b & c", multi.to_string());
+        assert_eq!(multi, MultiFunction::parse(&multi.to_string()).unwrap());
+
+        let listed = MultiFunction::parse("f = Σm(1, 3, 40); g = !f; h = Σm(2, 32) + d(41)").unwrap();
+        assert_eq!(listed, MultiFunction::parse(&listed.to_string()).unwrap());
+    }
+
+    #[test]
+    fn dont_care_rows() {
+        let multi = MultiFunction::from(Function::parse("a & b").unwrap());
+        let multi = multi.with_dont_care(Function::parse("!a & c").unwrap());
+        assert_eq!(vec!["a", "b", "c"], multi.input_names());
+        assert!(multi.is_dont_care(&[false, true, true]));
+        assert!(!multi.is_dont_care(&[true, true, true]));
+        assert_eq!("a & b; d(!a & c)", multi.to_string());
+        assert_eq!(multi, MultiFunction::parse(&multi.to_string()).unwrap());
+
+        let toggled = multi.toggle_dont_care(1).toggle_dont_care(7);
+        let rows = (0..8).filter(|&n: &u8| toggled.is_dont_care(&[n & 4 != 0, n & 2 != 0, n & 1 != 0]));
+        assert_eq!(vec![3, 7], rows.collect::<Vec<_>>());
+        assert_eq!(None, toggled.toggle_dont_care(3).toggle_dont_care(7).dont_care());
+    }
+
+    #[test]
+    fn toggle_many_inputs() {
+        let formula = (0..20).map(|i| format!("x{i:02}")).collect::<Vec<_>>().join(" & ");
+        let multi = MultiFunction::parse(&formula).unwrap();
+        let row = |minterm: u32| (0..20).map(|i| minterm >> (19 - i) & 1 == 1).collect::<Vec<_>>();
+        let toggled = multi.toggle_dont_care(0xF0F0F).toggle_dont_care(1);
+        assert!(toggled.is_dont_care(&row(0xF0F0F)));
+        assert!(toggled.is_dont_care(&row(1)));
+        assert!(!toggled.is_dont_care(&row(0xF0F0E)));
+        let toggled = toggled.toggle_dont_care(1);
+        assert!(!toggled.is_dont_care(&row(1)));
+        assert!(toggled.is_dont_care(&row(0xF0F0F)));
+        assert_eq!(toggled, MultiFunction::parse(&toggled.to_string()).unwrap());
+        assert_eq!(None, toggled.toggle_dont_care(0xF0F0F).dont_care());
+    }
+}
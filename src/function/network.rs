@@ -0,0 +1,161 @@
+use super::{parse::PostfixToken, Function, Variable};
+
+/// Largest number of tokens of a formula built from shared parts, like nodes of a network,
+/// which may be much larger than the parts as they're written out for every use.
+pub(super) const MAX_TOKENS: usize = 1 << 16;
+
+/// Node of a logic network read from a file, with operands referring to other nodes by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Node {
+    Var(Variable),
+    Const(bool),
+    Not(usize),
+    And(Vec<usize>),
+    Or(Vec<usize>),
+    Xor([usize; 2]),
+}
+
+impl Node {
+    fn operands(&self) -> &[usize] {
+        match self {
+            Node::Var(_) | Node::Const(_) => &[],
+            Node::Not(operand) => std::slice::from_ref(operand),
+            Node::And(operands) | Node::Or(operands) => operands,
+            Node::Xor(operands) => operands,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum NetworkError {
+    /// The node depends on itself.
+    Cycle(usize),
+    TooLarge,
+}
+
+/// Builds the function computed by the node, with variables of the nodes it depends on.
+///
+/// `And` and `Or` nodes without operands are constants.
+pub(super) fn network_function(nodes: &[Node], output: usize) -> Result<Function, NetworkError> {
+    let mut postfix = vec![None::<Vec<PostfixToken>>; nodes.len()];
+    // Nodes whose operands are being built, which would form a cycle if they're reached again.
+    let mut pending = vec![false; nodes.len()];
+    let mut stack = vec![(output, false)];
+    while let Some((node, expanded)) = stack.pop() {
+        if postfix[node].is_some() {
+            continue;
+        }
+        if !expanded {
+            pending[node] = true;
+            stack.push((node, true));
+            for &operand in nodes[node].operands() {
+                if pending[operand] {
+                    return Err(NetworkError::Cycle(operand));
+                }
+                if postfix[operand].is_none() {
+                    stack.push((operand, false));
+                }
+            }
+            continue;
+        }
+        pending[node] = false;
+
+        let operand = |index: usize| postfix[index].as_deref().unwrap();
+        let tokens = match &nodes[node] {
+            Node::Var(var) => vec![PostfixToken::Var(var.clone())],
+            Node::Const(val) => vec![PostfixToken::Const(*val)],
+            Node::Not(inner) => [operand(*inner), &[PostfixToken::Not]].concat(),
+            Node::Xor([lhs, rhs]) => [operand(*lhs), operand(*rhs), &[PostfixToken::Xor]].concat(),
+            Node::And(operands) | Node::Or(operands) => {
+                let is_and = matches!(nodes[node], Node::And(_));
+                let mut tokens = Vec::new();
+                for (i, &index) in operands.iter().enumerate() {
+                    tokens.extend_from_slice(operand(index));
+                    if i > 0 {
+                        tokens.push(if is_and { PostfixToken::And } else { PostfixToken::Or });
+                    }
+                    if tokens.len() > MAX_TOKENS {
+                        return Err(NetworkError::TooLarge);
+                    }
+                }
+                if operands.is_empty() {
+                    tokens.push(PostfixToken::Const(is_and));
+                }
+                tokens
+            }
+        };
+        if tokens.len() > MAX_TOKENS {
+            return Err(NetworkError::TooLarge);
+        }
+        postfix[node] = Some(tokens);
+    }
+
+    let postfix = postfix[output].take().unwrap();
+    let mut variables = postfix
+        .iter()
+        .filter_map(|token| match token {
+            PostfixToken::Var(var) => Some(var.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    variables.sort_unstable();
+    variables.dedup();
+    Ok(Function { variables, postfix })
+}
+
+/// Turns names from a file into distinct variables, in lowercase and with characters
+/// that aren't allowed replaced by underscores.
+pub(super) fn variable_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<Variable> {
+    let mut variables = Vec::<Variable>::new();
+    for name in names {
+        let mut sanitized = name
+            .to_lowercase()
+            .chars()
+            .map(|ch| if Variable::is_continuation(ch) { ch } else { '_' })
+            .collect::<String>();
+        if !sanitized.starts_with(Variable::is_start) {
+            sanitized.insert(0, 'x');
+        }
+        let mut candidate = sanitized.clone();
+        let mut suffix = 1;
+        while variables.iter().any(|var| var.as_str() == candidate) {
+            suffix += 1;
+            candidate = format!("{sanitized}_{suffix}");
+        }
+        variables.push(Variable::new(&candidate).unwrap());
+    }
+    variables
+}
+
+/// Prefix of names of internal signals, which none of the names of inputs and outputs starts with.
+pub(super) fn signal_prefix<'a>(names: impl IntoIterator<Item = &'a str> + Clone) -> String {
+    let mut prefix = "n".to_string();
+    while names.clone().into_iter().any(|name| name.starts_with(&prefix)) {
+        prefix.push('n');
+    }
+    prefix
+}
+
+#[cfg(test)]
+mod network_tests {
+    use super::{network_function, variable_names, NetworkError, Node};
+
+    #[test]
+    fn shared_nodes() {
+        let [a, b] = ["a", "b"].map(|name| Node::Var(variable_names([name]).pop().unwrap()));
+        let nodes = vec![a, b, Node::And(vec![0, 1]), Node::Not(2), Node::Or(vec![2, 3, 0])];
+        let func = network_function(&nodes, 4).unwrap();
+        assert_eq!("a & b | !(a & b) | a", func.to_string());
+        assert_eq!(Some(true), network_function(&[Node::And(Vec::new())], 0).unwrap().eval(&[]));
+
+        let cycle = vec![Node::Not(1), Node::And(vec![0])];
+        assert_eq!(Err(NetworkError::Cycle(0)), network_function(&cycle, 0));
+    }
+
+    #[test]
+    fn sanitized_names() {
+        let names = variable_names(["G1gat", "x.1", "3", "x_1", "a"]);
+        let names = names.iter().map(|var| var.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["g1gat", "x_1", "x3", "x_1_2", "a"], names);
+    }
+}
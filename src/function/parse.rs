@@ -1,11 +1,58 @@
-use std::fmt::Display;
+use std::{fmt::Display, ops::Range};
 
 use thiserror::Error;
 
-use super::Function;
+use super::{Function, Variable};
 
 impl Function {
     pub fn parse(s: &str) -> Result<Self, ParseError> {
+        Self::parse_spanned(s).map(|(func, _)| func)
+    }
+
+    /// Parses the formula, also returning the span of text each postfix token comes from.
+    pub(super) fn parse_spanned(s: &str) -> Result<(Self, Vec<Range<usize>>), ParseError> {
+        let (mut variables, infix) = Self::tokenize(s)?;
+        let (postfix, spans) = Self::into_postfix(infix.into_iter(), |_, _, _| {})
+            .into_iter()
+            .unzip();
+
+        variables.sort_unstable();
+        Ok((Function { variables, postfix }, spans))
+    }
+
+    /// Records every step of translating the formula into postfix notation.
+    pub fn conversion_steps(s: &str) -> Result<Vec<ConversionStep>, ParseError> {
+        let (_, infix) = Self::tokenize(s)?;
+        let mut steps = Vec::<ConversionStep>::with_capacity(infix.len());
+        let mut output_len = 0;
+        Self::into_postfix(infix.into_iter(), |token, op_stack, output| {
+            let popped = output.len() > output_len + token.map_or(0, |token| token.is_operand() as usize);
+            output_len = output.len();
+            let action = match token {
+                Some(InfixToken::Variable(_) | InfixToken::Const(_)) => "Add to output",
+                Some(InfixToken::And | InfixToken::Or | InfixToken::Xor) if popped => {
+                    "Pop operators that bind at least as tightly, then push"
+                }
+                Some(
+                    InfixToken::And | InfixToken::Or | InfixToken::Xor | InfixToken::Not | InfixToken::LeftBracket,
+                ) => "Push onto stack",
+                Some(InfixToken::RightBracket) => "Pop operators down to the opening bracket",
+                None => "Pop remaining operator",
+            };
+            steps.push(ConversionStep {
+                input: token.map(InfixToken::to_string),
+                action,
+                stack: op_stack.iter().map(|(entry, _)| char::from(*entry)).collect(),
+                output: output.iter().map(|(token, _)| token.to_string()).collect(),
+            });
+        });
+        Ok(steps)
+    }
+
+    /// Splits the formula into tokens, checking that they're in valid order.
+    ///
+    /// Variables are listed in order of their first occurrence.
+    fn tokenize(s: &str) -> Result<(Vec<Variable>, Vec<Spanned<InfixToken>>), ParseError> {
         use ParseErrorKind::*;
 
         let mut variables = Vec::with_capacity(3);
@@ -13,17 +60,24 @@ impl Function {
 
         let mut bracket_number = 0isize;
         let mut previous = TokenSeqType::Operator;
-        for (pos, ch) in s.char_indices() {
+        let mut chars = s.char_indices().peekable();
+        while let Some((pos, ch)) = chars.next() {
             if ch.is_whitespace() {
                 continue;
             }
+            let mut end = pos + ch.len_utf8();
             let token = match ch {
                 '&' => InfixToken::And,
                 '|' => InfixToken::Or,
+                '^' => InfixToken::Xor,
                 '!' => InfixToken::Not,
-                var @ ('a'..='z') => {
+                ch if Variable::is_start(ch) => {
+                    while let Some((next, _)) = chars.next_if(|(_, ch)| Variable::is_continuation(*ch)) {
+                        end = next + 1;
+                    }
+                    let var = Variable::new(&s[pos..end]).expect("name should be valid");
                     if !variables.contains(&var) {
-                        variables.push(var);
+                        variables.push(var.clone());
                     }
                     InfixToken::Variable(var)
                 }
@@ -42,9 +96,9 @@ impl Function {
                 }
                 ch => return Err(IllegalCharacter(ch).at(pos)),
             };
-            previous.matches(token).map_err(|e| e.at(pos))?;
-            previous = token.into();
-            infix.push(token);
+            previous.matches(&token).map_err(|e| e.at(pos))?;
+            previous = (&token).into();
+            infix.push((token, pos..end));
         }
         if bracket_number > 0 {
             return Err(UnclosedParenthesis.at(s.len()));
@@ -52,87 +106,92 @@ impl Function {
         if previous == TokenSeqType::Operator {
             return Err(UnexpectedEOF.at(s.len()));
         }
-        let postfix = Self::into_postfix(infix.into_iter());
-
-        variables.sort_unstable();
-        Ok(Function { variables, postfix })
+        Ok((variables, infix))
     }
 
     /// Translates infix notation into postfix notation.
-    fn into_postfix(infix: impl Iterator<Item = InfixToken>) -> Vec<PostfixToken> {
-        let mut op_stack = Vec::<OpStackEntry>::new();
-        let mut output = Vec::<PostfixToken>::new();
-        for token in infix {
-            match token {
-                InfixToken::Not => op_stack.push(OpStackEntry::Not),
-                op @ (InfixToken::And | InfixToken::Or) => {
-                    loop {
-                        let top_priority = match op_stack.last() {
-                            Some(OpStackEntry::Not) => 2,
-                            Some(OpStackEntry::And) => 1,
-                            Some(OpStackEntry::Or) => 0,
-                            None | Some(OpStackEntry::LeftBracket) => break,
-                        };
-                        let cur_priority = match op {
-                            InfixToken::And => 1,
-                            InfixToken::Or => 0,
-                            _ => unreachable!(),
-                        };
-                        if top_priority < cur_priority {
+    ///
+    /// Spans are carried along with the tokens; brackets don't make it to the output.
+    /// `observe` is called with the operator stack and the output after every input token,
+    /// and after every operator popped once the input ends.
+    fn into_postfix(
+        infix: impl Iterator<Item = Spanned<InfixToken>>,
+        mut observe: impl FnMut(Option<&InfixToken>, &[Spanned<OpStackEntry>], &[Spanned<PostfixToken>]),
+    ) -> Vec<Spanned<PostfixToken>> {
+        let mut op_stack = Vec::<Spanned<OpStackEntry>>::new();
+        let mut output = Vec::<Spanned<PostfixToken>>::new();
+        for (token, span) in infix {
+            match token.clone() {
+                InfixToken::Not => op_stack.push((OpStackEntry::Not, span)),
+                op @ (InfixToken::And | InfixToken::Or | InfixToken::Xor) => {
+                    let entry = match op {
+                        InfixToken::And => OpStackEntry::And,
+                        InfixToken::Or => OpStackEntry::Or,
+                        _ => OpStackEntry::Xor,
+                    };
+                    while let Some(&(top, _)) = op_stack.last() {
+                        if top == OpStackEntry::LeftBracket || top.priority() < entry.priority() {
                             break;
                         }
-                        output.push(match op_stack.pop().unwrap() {
-                            OpStackEntry::And => PostfixToken::And,
-                            OpStackEntry::Or => PostfixToken::Or,
-                            OpStackEntry::Not => PostfixToken::Not,
-                            OpStackEntry::LeftBracket => unreachable!(),
-                        });
+                        let (top, top_span) = op_stack.pop().unwrap();
+                        Self::push_operator(&mut output, top, top_span);
                     }
-                    op_stack.push(match op {
-                        InfixToken::And => OpStackEntry::And,
-                        InfixToken::Or => OpStackEntry::Or,
-                        InfixToken::Not => OpStackEntry::Not,
-                        _ => unreachable!(),
-                    });
+                    op_stack.push((entry, span));
                 }
-                InfixToken::LeftBracket => op_stack.push(OpStackEntry::LeftBracket),
+                InfixToken::LeftBracket => op_stack.push((OpStackEntry::LeftBracket, span)),
                 InfixToken::RightBracket => loop {
-                    match op_stack.last() {
-                        Some(OpStackEntry::LeftBracket) => {
-                            op_stack.pop();
-                            break;
-                        }
-                        Some(&token) => {
-                            op_stack.pop();
-                            let token = match token {
-                                OpStackEntry::And => PostfixToken::And,
-                                OpStackEntry::Or => PostfixToken::Or,
-                                OpStackEntry::Not => PostfixToken::Not,
-                                OpStackEntry::LeftBracket => unreachable!(),
-                            };
-                            output.push(token);
-                        }
+                    match op_stack.pop() {
+                        Some((OpStackEntry::LeftBracket, _)) => break,
+                        Some((token, span)) => Self::push_operator(&mut output, token, span),
                         None => {
                             panic!("No right bracket");
                         }
                     }
                 },
-                InfixToken::Variable(var) => output.push(PostfixToken::Var(var)),
-                InfixToken::Const(val) => output.push(PostfixToken::Const(val)),
+                InfixToken::Variable(var) => output.push((PostfixToken::Var(var), span)),
+                InfixToken::Const(val) => output.push((PostfixToken::Const(val), span)),
             }
+            observe(Some(&token), &op_stack, &output);
         }
 
         output.reserve_exact(op_stack.len());
-        while let Some(token) = op_stack.pop() {
-            output.push(match token {
-                OpStackEntry::LeftBracket => panic!("No left bracket"),
-                OpStackEntry::And => PostfixToken::And,
-                OpStackEntry::Or => PostfixToken::Or,
-                OpStackEntry::Not => PostfixToken::Not,
-            });
+        while let Some((token, span)) = op_stack.pop() {
+            if token == OpStackEntry::LeftBracket {
+                panic!("No left bracket");
+            }
+            Self::push_operator(&mut output, token, span);
+            observe(None, &op_stack, &output);
         }
         output
     }
+
+    /// Applies the operator to operands at the end of the output.
+    fn push_operator(output: &mut Vec<Spanned<PostfixToken>>, entry: OpStackEntry, span: Range<usize>) {
+        let token = match entry {
+            OpStackEntry::And => PostfixToken::And,
+            OpStackEntry::Or => PostfixToken::Or,
+            OpStackEntry::Xor => PostfixToken::Xor,
+            OpStackEntry::Not => PostfixToken::Not,
+            OpStackEntry::LeftBracket => unreachable!(),
+        };
+        output.push((token, span));
+    }
+}
+
+/// Token along with bytes of the formula it comes from.
+type Spanned<T> = (T, Range<usize>);
+
+/// State of the shunting-yard algorithm after a single input token,
+/// or after popping an operator once the input ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionStep {
+    /// Token of the formula, or `None` once the input ends.
+    pub input: Option<String>,
+    /// What was done with the token.
+    pub action: &'static str,
+    /// Operators and opening brackets, from the bottom.
+    pub stack: String,
+    pub output: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
@@ -155,7 +214,7 @@ pub enum ParseErrorKind {
     UnmatchedParenthesis,
     #[error("more brackets opened than closed")]
     UnclosedParenthesis,
-    #[error("expected one of: '&', '|', or ')'; got '{0}'")]
+    #[error("expected one of: '&', '|', '^', or ')'; got '{0}'")]
     ExpectedOperator(char),
     #[error("expected one of: variable, constant, '!', or '('; got '{0}'")]
     ExpectedOperand(char),
@@ -178,12 +237,12 @@ enum TokenSeqType {
 }
 
 impl TokenSeqType {
-    pub fn matches(&self, next: InfixToken) -> Result<(), ParseErrorKind> {
+    pub fn matches(&self, next: &InfixToken) -> Result<(), ParseErrorKind> {
         use {InfixToken::*, TokenSeqType::*};
 
         match self {
             Operand => match next {
-                And | Or | RightBracket => Ok(()),
+                And | Or | Xor | RightBracket => Ok(()),
                 _ => Err(ParseErrorKind::ExpectedOperator(next.into())),
             },
             Operator => match next {
@@ -194,13 +253,14 @@ impl TokenSeqType {
     }
 }
 
-impl From<InfixToken> for TokenSeqType {
-    fn from(value: InfixToken) -> Self {
+impl From<&InfixToken> for TokenSeqType {
+    fn from(value: &InfixToken) -> Self {
         use {InfixToken::*, TokenSeqType::*};
 
         match value {
             And => Operator,
             Or => Operator,
+            Xor => Operator,
             Not => Operator,
             Variable(_) => Operand,
             Const(_) => Operand,
@@ -210,24 +270,43 @@ impl From<InfixToken> for TokenSeqType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum InfixToken {
     And,
     Or,
+    Xor,
     Not,
-    Variable(char),
+    Variable(Variable),
     Const(bool),
     LeftBracket,
     RightBracket,
 }
 
-impl Into<char> for InfixToken {
-    fn into(self) -> char {
+impl InfixToken {
+    fn is_operand(&self) -> bool {
+        matches!(self, InfixToken::Variable(_) | InfixToken::Const(_))
+    }
+}
+
+/// Token as it's written in formulas.
+impl Display for InfixToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            InfixToken::Variable(var) => write!(f, "{var}"),
+            token => write!(f, "{}", char::from(token)),
+        }
+    }
+}
+
+/// First character of the token.
+impl From<&InfixToken> for char {
+    fn from(value: &InfixToken) -> Self {
+        match value {
             InfixToken::And => '&',
             InfixToken::Or => '|',
+            InfixToken::Xor => '^',
             InfixToken::Not => '!',
-            InfixToken::Variable(ch) => ch,
+            InfixToken::Variable(var) => var.as_str().chars().next().unwrap(),
             InfixToken::Const(true) => '1',
             InfixToken::Const(false) => '0',
             InfixToken::LeftBracket => '(',
@@ -236,26 +315,84 @@ impl Into<char> for InfixToken {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PostfixToken {
     And,
     Or,
+    Xor,
     Not,
-    Var(char),
+    Var(Variable),
     Const(bool),
 }
 
+impl PostfixToken {
+    /// Number of operands the token takes from the stack.
+    pub fn arity(&self) -> usize {
+        match self {
+            PostfixToken::And | PostfixToken::Or | PostfixToken::Xor => 2,
+            PostfixToken::Not => 1,
+            PostfixToken::Var(_) | PostfixToken::Const(_) => 0,
+        }
+    }
+}
+
+/// Operator symbol, variable name or constant, as it's written in formulas.
+impl Display for PostfixToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PostfixToken::And => f.write_str("&"),
+            PostfixToken::Or => f.write_str("|"),
+            PostfixToken::Xor => f.write_str("^"),
+            PostfixToken::Not => f.write_str("!"),
+            PostfixToken::Var(var) => write!(f, "{var}"),
+            PostfixToken::Const(val) => write!(f, "{}", *val as u8),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum OpStackEntry {
     LeftBracket,
     And,
     Or,
+    Xor,
     Not,
 }
 
+impl OpStackEntry {
+    /// How tightly the operator binds, with `!` before `&`, then `^`, then `|`.
+    fn priority(self) -> u8 {
+        match self {
+            OpStackEntry::Or | OpStackEntry::LeftBracket => 0,
+            OpStackEntry::Xor => 1,
+            OpStackEntry::And => 2,
+            OpStackEntry::Not => 3,
+        }
+    }
+}
+
+impl From<OpStackEntry> for char {
+    fn from(value: OpStackEntry) -> Self {
+        match value {
+            OpStackEntry::LeftBracket => '(',
+            OpStackEntry::And => '&',
+            OpStackEntry::Or => '|',
+            OpStackEntry::Xor => '^',
+            OpStackEntry::Not => '!',
+        }
+    }
+}
+
 #[cfg(test)]
 mod parse_tests {
-    use crate::function::{parse::PostfixToken, Function};
+    use crate::function::{
+        parse::{ParseErrorKind, PostfixToken},
+        Function, Variable,
+    };
+
+    fn var(name: &str) -> Variable {
+        Variable::new(name).unwrap()
+    }
 
     #[test]
     fn parse_one() {
@@ -281,12 +418,12 @@ mod parse_tests {
     fn parse_chained_and() {
         let parsed = Function::parse("x & 1 & y");
         let expected = Function {
-            variables: vec!['x', 'y'],
+            variables: vec![var("x"), var("y")],
             postfix: vec![
-                PostfixToken::Var('x'),
+                PostfixToken::Var(var("x")),
                 PostfixToken::Const(true),
                 PostfixToken::And,
-                PostfixToken::Var('y'),
+                PostfixToken::Var(var("y")),
                 PostfixToken::And,
             ],
         };
@@ -297,10 +434,10 @@ mod parse_tests {
     fn parse_chained_or() {
         let parsed = Function::parse("x | y | 0");
         let expected = Function {
-            variables: vec!['x', 'y'],
+            variables: vec![var("x"), var("y")],
             postfix: vec![
-                PostfixToken::Var('x'),
-                PostfixToken::Var('y'),
+                PostfixToken::Var(var("x")),
+                PostfixToken::Var(var("y")),
                 PostfixToken::Or,
                 PostfixToken::Const(false),
                 PostfixToken::Or,
@@ -313,12 +450,12 @@ mod parse_tests {
     fn parse_combined() {
         let parsed = Function::parse("1 & x | y");
         let expected = Function {
-            variables: vec!['x', 'y'],
+            variables: vec![var("x"), var("y")],
             postfix: vec![
                 PostfixToken::Const(true),
-                PostfixToken::Var('x'),
+                PostfixToken::Var(var("x")),
                 PostfixToken::And,
-                PostfixToken::Var('y'),
+                PostfixToken::Var(var("y")),
                 PostfixToken::Or,
             ],
         };
@@ -329,11 +466,11 @@ mod parse_tests {
     fn parse_parenthesis() {
         let parsed = Function::parse("1 & (x | y)");
         let expected = Function {
-            variables: vec!['x', 'y'],
+            variables: vec![var("x"), var("y")],
             postfix: vec![
                 PostfixToken::Const(true),
-                PostfixToken::Var('x'),
-                PostfixToken::Var('y'),
+                PostfixToken::Var(var("x")),
+                PostfixToken::Var(var("y")),
                 PostfixToken::Or,
                 PostfixToken::And,
             ],
@@ -341,19 +478,72 @@ mod parse_tests {
         assert_eq!(Ok(expected), parsed);
     }
 
+    #[test]
+    fn parse_long_names() {
+        let parsed = Function::parse("cin & x10 | x2_a");
+        let expected = Function {
+            variables: vec![var("cin"), var("x2_a"), var("x10")],
+            postfix: vec![
+                PostfixToken::Var(var("cin")),
+                PostfixToken::Var(var("x10")),
+                PostfixToken::And,
+                PostfixToken::Var(var("x2_a")),
+                PostfixToken::Or,
+            ],
+        };
+        assert_eq!(Ok(expected), parsed);
+    }
+
+    #[test]
+    fn parse_xor() {
+        let func = Function::parse("a & b ^ c | d").unwrap();
+        let tokens = func.postfix.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(vec!["a", "b", "&", "c", "^", "d", "|"], tokens);
+        let parity = Function::parse("a ^ b ^ c").unwrap();
+        for n in 0..8u8 {
+            let values = [n & 4 != 0, n & 2 != 0, n & 1 != 0];
+            assert_eq!(Some(n.count_ones() % 2 == 1), parity.eval(&values));
+        }
+
+        // Chains stay as long as they're written.
+        let chain = (0..40).map(|i| format!("x{i}")).collect::<Vec<_>>().join(" ^ ");
+        assert_eq!(79, Function::parse(&chain).unwrap().postfix.len());
+    }
+
+    #[test]
+    fn parse_adjacent_names() {
+        let parsed = Function::parse("ab c");
+        assert_eq!(Err(ParseErrorKind::ExpectedOperator('c').at(3)), parsed);
+    }
+
+    #[test]
+    fn conversion_steps() {
+        let steps = Function::conversion_steps("a & (b | c)").unwrap();
+        let stacks = steps.iter().map(|step| step.stack.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["", "&", "&(", "&(", "&(|", "&(|", "&", ""], stacks);
+        assert_eq!(Some(")"), steps[6].input.as_deref());
+        assert_eq!(vec!["a", "b", "c", "|"], steps[6].output);
+        assert_eq!((None, 5), (steps[7].input.as_deref(), steps[7].output.len()));
+
+        let steps = Function::conversion_steps("a & b | c").unwrap();
+        assert_eq!("Push onto stack", steps[1].action);
+        assert_eq!("Pop operators that bind at least as tightly, then push", steps[3].action);
+        assert_eq!(vec!["a", "b", "&"], steps[3].output);
+    }
+
     #[test]
     fn parse_complex() {
         let parsed = Function::parse("!x & (y | z) | !z");
         let expected = Function {
-            variables: vec!['x', 'y', 'z'],
+            variables: vec![var("x"), var("y"), var("z")],
             postfix: vec![
-                PostfixToken::Var('x'),
+                PostfixToken::Var(var("x")),
                 PostfixToken::Not,
-                PostfixToken::Var('y'),
-                PostfixToken::Var('z'),
+                PostfixToken::Var(var("y")),
+                PostfixToken::Var(var("z")),
                 PostfixToken::Or,
                 PostfixToken::And,
-                PostfixToken::Var('z'),
+                PostfixToken::Var(var("z")),
                 PostfixToken::Not,
                 PostfixToken::Or,
             ],
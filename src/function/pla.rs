@@ -0,0 +1,338 @@
+use std::fmt::{Display, Write};
+
+use thiserror::Error;
+
+use super::{network::variable_names, parse::PostfixToken, Cover, Cube, Function, MultiFunction, Variable};
+
+/// Largest number of inputs or outputs a file may declare, which bounds the names made up for them.
+const MAX_SIZE: usize = 1 << 16;
+
+impl MultiFunction {
+    /// Writes the function in Berkeley PLA format, with every output split into disjoint cubes.
+    ///
+    /// Cubes that several outputs share are listed once.
+    /// Don't-care rows, if any, are cubes with `-` for every output, so the logic type becomes `fd`.
+    pub fn to_pla(&self) -> String {
+        let mut terms = Vec::<(Cube, Vec<bool>)>::new();
+        for (output, (_, func)) in self.outputs().iter().enumerate() {
+            for (cube, _) in func.cubes().into_iter().filter(|(_, value)| *value) {
+                let index = match terms.iter().position(|(term, _)| *term == cube) {
+                    Some(index) => index,
+                    None => {
+                        terms.push((cube, vec![false; self.outputs().len()]));
+                        terms.len() - 1
+                    }
+                };
+                terms[index].1[output] = true;
+            }
+        }
+        let names = self.outputs().iter().map(|(name, _)| name.clone()).collect();
+        let dont_cares = match self.dont_care() {
+            Some(dont_care) => {
+                let cubes = dont_care.cubes().into_iter().filter(|(_, value)| *value);
+                cubes.map(|(cube, _)| cube).collect()
+            }
+            None => Vec::new(),
+        };
+        write_pla(&Cover::new(self.inputs().to_vec(), names, terms), &dont_cares)
+    }
+
+    /// Reads a Berkeley PLA file, with an output for every column of the output part of cubes.
+    ///
+    /// Outputs are true where some cube has `1` in their column, and false elsewhere, except on don't-care rows.
+    /// Those are rows of cubes with `-` in the column for logic types `fd` and `fdr`,
+    /// and rows no cube has `0` or `1` in the column for types `fr` and `fdr`.
+    /// Every output should have the same don't-care rows.
+    /// Names of inputs and outputs become distinct variables, so that outputs can be written as definitions,
+    /// in lowercase and with characters that aren't allowed replaced.
+    /// Unnamed inputs are `x0`, `x1` and so on, and unnamed outputs are `f0`, `f1` and so on.
+    pub fn from_pla(s: &str) -> Result<MultiFunction, PlaError> {
+        use PlaErrorKind::*;
+
+        let mut inputs = None::<(usize, usize)>;
+        let mut outputs = None::<(usize, usize)>;
+        let mut input_names = None::<(Vec<&str>, usize)>;
+        let mut output_names = None::<(Vec<&str>, usize)>;
+        let mut cubes = Vec::<(String, String)>::new();
+        let mut logic_type = ("f", 1);
+        let mut last_line = 1;
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            last_line = line_number;
+            let line = line.split('#').next().unwrap();
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let Some(&first) = words.first() else {
+                continue;
+            };
+
+            if first.starts_with('.') && ![".p", ".e", ".end"].contains(&first) && !cubes.is_empty() {
+                return Err(MisplacedCommand(first.to_string()).at(line_number));
+            }
+            match first {
+                ".i" | ".o" => {
+                    let count = match words.as_slice() {
+                        [_, count] => count.parse().map_err(|_| InvalidNumber(first.to_string()).at(line_number))?,
+                        _ => return Err(InvalidNumber(first.to_string()).at(line_number)),
+                    };
+                    if count > MAX_SIZE {
+                        return Err(TooMany(first.to_string(), MAX_SIZE).at(line_number));
+                    }
+                    let size = if first == ".i" { &mut inputs } else { &mut outputs };
+                    *size = Some((count, line_number));
+                }
+                ".ilb" => input_names = Some((words[1..].to_vec(), line_number)),
+                ".ob" => output_names = Some((words[1..].to_vec(), line_number)),
+                // The number of cubes is only a hint.
+                ".p" => {}
+                ".type" => match words.get(1) {
+                    Some(&kind @ ("f" | "fd" | "fr" | "fdr")) => logic_type = (kind, line_number),
+                    kind => return Err(UnsupportedType(kind.unwrap_or(&"").to_string()).at(line_number)),
+                },
+                ".e" | ".end" => break,
+                command if command.starts_with('.') => {
+                    return Err(UnsupportedCommand(command.to_string()).at(line_number));
+                }
+                _ => {
+                    let (Some((inputs, _)), Some((outputs, _))) = (inputs, outputs) else {
+                        return Err(MissingSize.at(line_number));
+                    };
+                    // Input and output parts may be separated by spaces, or not.
+                    let cube = words.concat();
+                    let valid = cube.is_ascii()
+                        && inputs.checked_add(outputs) == Some(cube.len())
+                        && cube[..inputs].chars().all(|ch| "01-".contains(ch))
+                        && cube[inputs..].chars().all(|ch| "01-~".contains(ch));
+                    if !valid {
+                        return Err(InvalidCube(inputs, outputs).at(line_number));
+                    }
+                    let (input, output) = cube.split_at(inputs);
+                    cubes.push((input.to_string(), output.to_string()));
+                }
+            }
+        }
+        let (Some((inputs, _)), Some((outputs, outputs_line))) = (inputs, outputs) else {
+            return Err(MissingSize.at(last_line));
+        };
+        if outputs == 0 {
+            return Err(NoOutputs.at(outputs_line));
+        }
+
+        let input_names = match input_names {
+            Some((names, _)) if names.len() == inputs => names.iter().map(|name| name.to_string()).collect(),
+            Some((_, line)) => return Err(NameCount(".ilb".to_string(), inputs).at(line)),
+            None => (0..inputs).map(|i| format!("x{i}")).collect::<Vec<_>>(),
+        };
+        let output_names = match output_names {
+            Some((names, _)) if names.len() == outputs => names.iter().map(|name| name.to_string()).collect(),
+            Some((_, line)) => return Err(NameCount(".ob".to_string(), outputs).at(line)),
+            None => (0..outputs).map(|i| format!("f{i}")).collect::<Vec<_>>(),
+        };
+        let mut vars = variable_names(input_names.iter().chain(&output_names).map(String::as_str));
+        let output_names = vars.split_off(inputs).iter().map(Variable::to_string).collect::<Vec<_>>();
+        let cube = |input: &str| {
+            let values = input.chars().map(|ch| match ch {
+                '-' => None,
+                ch => Some(ch == '1'),
+            });
+            Cube(values.collect())
+        };
+        let column = |symbol: char| {
+            let outputs = |output: &str| output.chars().map(|ch| ch == symbol).collect();
+            let terms = cubes.iter().map(|(input, output)| (cube(input), outputs(output)));
+            Cover::new(vars.clone(), output_names.clone(), terms.collect())
+        };
+        let (ones, zeros, dashes) = (column('1'), column('0'), column('-'));
+        let mut sorted = vars.clone();
+        sorted.sort_unstable();
+
+        let (kind, type_line) = logic_type;
+        let dont_care_of = |output: usize| {
+            let mut postfix = Vec::new();
+            if kind.contains('r') {
+                postfix.extend(ones.function(output).postfix);
+                postfix.extend(zeros.function(output).postfix);
+                postfix.extend([PostfixToken::Or, PostfixToken::Not]);
+            }
+            if kind.contains('d') {
+                postfix.extend(dashes.function(output).postfix);
+                if kind.contains('r') {
+                    postfix.push(PostfixToken::Or);
+                }
+            }
+            if postfix.is_empty() {
+                postfix.push(PostfixToken::Const(false));
+            }
+            Function { variables: sorted.clone(), postfix }
+        };
+        let dont_care = dont_care_of(0);
+        for (output, name) in output_names.iter().enumerate().skip(1) {
+            if dont_care.compare(&dont_care_of(output)).counterexample().is_some() {
+                return Err(DontCareMismatch(name.clone()).at(type_line));
+            }
+        }
+        let outputs = output_names.iter().enumerate().map(|(i, name)| (name.clone(), ones.function(i)));
+        let multi = MultiFunction::with_inputs(sorted.clone(), outputs.collect());
+        let none = Function { variables: sorted, postfix: vec![PostfixToken::Const(false)] };
+        match dont_care.compare(&none).counterexample() {
+            Some(_) => Ok(multi.with_dont_care(dont_care)),
+            None => Ok(multi),
+        }
+    }
+}
+
+impl Cover {
+    /// Writes the products in Berkeley PLA format, with `1` for every output that uses them.
+    pub fn to_pla(&self) -> String {
+        write_pla(self, &[])
+    }
+}
+
+/// Writes products of the cover followed by `dont_cares` cubes, which have `-` for every output.
+fn write_pla(cover: &Cover, dont_cares: &[Cube]) -> String {
+    let mut output = String::new();
+    for (index, name) in cover.names().iter().enumerate() {
+        writeln!(output, "# {name} = {}", cover.function(index)).unwrap();
+    }
+    writeln!(output, ".i {}", cover.inputs().len()).unwrap();
+    writeln!(output, ".o {}", cover.names().len()).unwrap();
+    if !cover.inputs().is_empty() {
+        let names = cover.inputs().iter().map(Variable::as_str).collect::<Vec<_>>();
+        writeln!(output, ".ilb {}", names.join(" ")).unwrap();
+    }
+    writeln!(output, ".ob {}", cover.names().join(" ")).unwrap();
+    if !dont_cares.is_empty() {
+        writeln!(output, ".type fd").unwrap();
+    }
+    writeln!(output, ".p {}", cover.terms().len() + dont_cares.len()).unwrap();
+    for (cube, outputs) in cover.terms() {
+        let outputs = outputs.iter().map(|used| if *used { '1' } else { '0' }).collect::<String>();
+        writeln!(output, "{cube} {outputs}").unwrap();
+    }
+    for cube in dont_cares {
+        writeln!(output, "{cube} {}", "-".repeat(cover.names().len())).unwrap();
+    }
+    writeln!(output, ".e").unwrap();
+    output
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct PlaError {
+    line: usize,
+    kind: PlaErrorKind,
+}
+
+impl Display for PlaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PlaErrorKind {
+    #[error("`{0}` is not supported")]
+    UnsupportedCommand(String),
+    #[error("`{0}` should be followed by a number")]
+    InvalidNumber(String),
+    #[error("`{0}` should come before the cubes")]
+    MisplacedCommand(String),
+    #[error("logic type `{0}` is not supported, only `f`, `fd`, `fr` and `fdr` are")]
+    UnsupportedType(String),
+    #[error("`.i` and `.o` should give the number of inputs and outputs before the cubes")]
+    MissingSize,
+    #[error("cube should have {0} inputs of `0`, `1` or `-` followed by {1} outputs of `0`, `1`, `-` or `~`")]
+    InvalidCube(usize, usize),
+    #[error("`{0}` should list {1} names")]
+    NameCount(String, usize),
+    #[error("there should be at least one output")]
+    NoOutputs,
+    #[error("`{0}` should be at most {1}")]
+    TooMany(String, usize),
+    #[error("output `{0}` has other don't-care rows than the first one, but they're shared by all outputs")]
+    DontCareMismatch(String),
+}
+
+impl PlaErrorKind {
+    pub fn at(self, line: usize) -> PlaError {
+        PlaError { line, kind: self }
+    }
+}
+
+#[cfg(test)]
+mod pla_tests {
+    use super::PlaErrorKind;
+    use crate::function::{Function, MultiFunction};
+
+    #[test]
+    fn pla_roundtrip() {
+        let outputs = [("s", "a & !b | !a & b"), ("carry", "a & b"), ("one", "1")];
+        let outputs = outputs.map(|(name, formula)| (name.to_string(), Function::parse(formula).unwrap()));
+        let multi = MultiFunction::new(outputs.to_vec());
+        let imported = MultiFunction::from_pla(&multi.to_pla()).unwrap();
+        assert_eq!(multi.inputs(), imported.inputs());
+        for ((name, func), (imported_name, imported)) in multi.outputs().iter().zip(imported.outputs()) {
+            assert_eq!(name, imported_name);
+            assert_eq!(None, func.compare(imported).counterexample(), "{name}");
+        }
+
+        let decoder = MultiFunction::parse("Σm(1, 3, 7) + d(10..15)").unwrap();
+        let pla = decoder.to_pla();
+        assert!(pla.contains(".type fd\n") && pla.contains("\n11-- -\n"));
+        let imported = MultiFunction::from_pla(&pla).unwrap();
+        assert_eq!(None, decoder.dont_care().unwrap().compare(imported.dont_care().unwrap()).counterexample());
+    }
+
+    #[test]
+    fn pla_read() {
+        let pla = "# two outputs\n.i 3\n.o 2\n.ilb B A Cin\n.ob S Cout\n.type fd\n.p 3\n\
+            1-1 1~\n01- ~1\n110 11\n.e\n";
+        let multi = MultiFunction::from_pla(pla).unwrap();
+        assert_eq!(vec!["a", "b", "cin"], multi.input_names());
+        assert_eq!("s", multi.outputs()[0].0);
+        assert_eq!("b & cin | b & a & !cin", multi.outputs()[0].1.to_string());
+        assert_eq!("!b & a | b & a & !cin", multi.outputs()[1].1.to_string());
+
+        let unnamed = MultiFunction::from_pla(".i 2\n.o 1\n11 1\n").unwrap();
+        assert_eq!("f0", unnamed.outputs()[0].0);
+        assert_eq!("x0 & x1", unnamed.first().to_string());
+        assert_eq!(None, multi.dont_care());
+        let constant = MultiFunction::from_pla(".i 0\n.o 1\n 1\n").unwrap();
+        assert_eq!("1", constant.first().to_string());
+    }
+
+    #[test]
+    fn pla_dont_cares() {
+        let dont_care = |pla: &str| MultiFunction::from_pla(pla).unwrap().dont_care().map(ToString::to_string);
+        assert_eq!(Some("x0 & !x1".to_string()), dont_care(".i 2\n.o 2\n.type fd\n11 11\n10 --\n"));
+        assert_eq!(None, dont_care(".i 2\n.o 1\n.type f\n11 1\n10 -\n"));
+        assert_eq!(None, dont_care(".i 1\n.o 1\n.type fr\n1 1\n0 0\n"));
+
+        // Rows that aren't listed don't matter.
+        let multi = MultiFunction::from_pla(".i 2\n.o 1\n.type fr\n11 1\n0- 0\n").unwrap();
+        let dont_care = multi.dont_care().unwrap();
+        assert_eq!(None, dont_care.compare(&Function::parse("x0 & !x1").unwrap()).counterexample());
+        assert_eq!("x0 & x1", multi.first().to_string());
+        let multi = MultiFunction::from_pla(".i 2\n.o 1\n.type fdr\n11 1\n00 0\n01 -\n").unwrap();
+        let dont_care = multi.dont_care().unwrap();
+        assert_eq!(None, dont_care.compare(&Function::parse("x0 ^ x1").unwrap()).counterexample());
+    }
+
+    #[test]
+    fn pla_errors() {
+        let error = |s: &str| MultiFunction::from_pla(s).unwrap_err();
+        assert_eq!(PlaErrorKind::MissingSize.at(2), error(".i 2\n11 1\n"));
+        assert_eq!(PlaErrorKind::InvalidCube(2, 1).at(3), error(".i 2\n.o 1\n1x 1\n"));
+        assert_eq!(PlaErrorKind::InvalidCube(2, 1).at(3), error(".i 2\n.o 1\n11 ~1\n"));
+        assert_eq!(PlaErrorKind::NameCount(".ilb".to_string(), 2).at(3), error(".i 2\n.o 1\n.ilb a\n"));
+        assert_eq!(PlaErrorKind::InvalidNumber(".o".to_string()).at(2), error(".i 2\n.o two\n"));
+        assert_eq!(PlaErrorKind::UnsupportedType("r".to_string()).at(1), error(".type r\n"));
+        assert_eq!(PlaErrorKind::UnsupportedCommand(".mv".to_string()).at(1), error(".mv 3 0 2 2\n"));
+        assert_eq!(PlaErrorKind::MisplacedCommand(".o".to_string()).at(4), error(".i 1\n.o 1\n1 1\n.o 2\n"));
+        assert_eq!(PlaErrorKind::NoOutputs.at(2), error(".i 1\n.o 0\n"));
+        let too_many = PlaErrorKind::TooMany(".i".to_string(), 1 << 16).at(1);
+        assert_eq!(too_many, error(".i 18446744073709551615\n.o 1\n1 1\n"));
+        assert_eq!(too_many, error(".i 4000000000\n.o 1\n"));
+        let mismatch = PlaErrorKind::DontCareMismatch("g".to_string()).at(4);
+        assert_eq!(mismatch, error(".i 2\n.o 2\n.ob f g\n.type fd\n11 11\n10 -0\n"));
+        assert_eq!(mismatch, error(".i 1\n.o 2\n.ob f g\n.type fr\n1 10\n0 0~\n"));
+    }
+}
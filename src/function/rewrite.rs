@@ -0,0 +1,306 @@
+use std::fmt::Display;
+
+use super::{expr::Expr, Function};
+
+/// Largest number of steps a simplification may take.
+const MAX_STEPS: usize = 256;
+
+/// Law of Boolean algebra that justifies a single rewriting step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Law {
+    /// `!!a = a`.
+    DoubleNegation,
+    /// `!(a & b) = !a | !b`, and the dual.
+    DeMorgan,
+    /// `a & (b & c) = a & b & c`, and the dual.
+    Associativity,
+    /// `a & b = b & a`, and the dual; never applied by simplification.
+    Commutativity,
+    /// `a & 1 = a`, and the dual.
+    Identity,
+    /// `a & 0 = 0`, and the dual.
+    Domination,
+    /// `a & a = a`, and the dual.
+    Idempotence,
+    /// `a & !a = 0`, `!0 = 1`, and the duals.
+    Complement,
+    /// `a | a & b = a`, and the dual.
+    Absorption,
+    /// `a & b | !a & c | b & c = a & b | !a & c`, and the dual.
+    Consensus,
+    /// `a & b | a & c = a & (b | c)`, and the dual.
+    Distributivity,
+}
+
+impl Law {
+    pub fn name(self) -> &'static str {
+        match self {
+            Law::DoubleNegation => "double negation",
+            Law::DeMorgan => "De Morgan's law",
+            Law::Associativity => "associativity",
+            Law::Commutativity => "commutativity",
+            Law::Identity => "identity",
+            Law::Domination => "domination",
+            Law::Idempotence => "idempotence",
+            Law::Complement => "complement",
+            Law::Absorption => "absorption",
+            Law::Consensus => "consensus",
+            Law::Distributivity => "distributivity",
+        }
+    }
+}
+
+impl Display for Law {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Expr {
+    /// Every way to apply a single law somewhere in the formula, innermost subexpressions first.
+    ///
+    /// Laws are only applied in the direction that simplifies the formula,
+    /// with negations pushed towards variables.
+    /// Rewritten formulas are only built as the iterator reaches them.
+    pub fn rewrites(&self) -> Box<dyn Iterator<Item = (Law, Expr)> + '_> {
+        let nested: Box<dyn Iterator<Item = (Law, Expr)>> = match self {
+            Expr::Const(_) | Expr::Var(_) => Box::new(std::iter::empty()),
+            Expr::Not(inner) => Box::new(inner.rewrites().map(|(law, inner)| (law, inner.not()))),
+            Expr::Xor(lhs, rhs) => {
+                let xor = |lhs: Expr, rhs: Expr| Expr::Xor(Box::new(lhs), Box::new(rhs));
+                let lhs_rewrites = lhs.rewrites().map(move |(law, lhs)| (law, xor(lhs, (**rhs).clone())));
+                let rhs_rewrites = rhs.rewrites().map(move |(law, rhs)| (law, xor((**lhs).clone(), rhs)));
+                Box::new(lhs_rewrites.chain(rhs_rewrites))
+            }
+            Expr::And(operands) | Expr::Or(operands) => {
+                let is_and = matches!(self, Expr::And(_));
+                Box::new(operands.iter().enumerate().flat_map(move |(i, operand)| {
+                    operand.rewrites().map(move |(law, rewritten)| {
+                        let mut operands = operands.clone();
+                        operands[i] = rewritten;
+                        (law, Expr::join(is_and, operands))
+                    })
+                }))
+            }
+        };
+        Box::new(nested.chain(std::iter::once_with(|| self.local_rewrites()).flatten()))
+    }
+
+    /// Ways to apply a law to the topmost operator.
+    fn local_rewrites(&self) -> Vec<(Law, Expr)> {
+        let (is_and, operands) = match self {
+            Expr::Not(inner) => {
+                let rewritten = match &**inner {
+                    Expr::Not(inner) => (Law::DoubleNegation, (**inner).clone()),
+                    Expr::Const(val) => (Law::Complement, Expr::Const(!val)),
+                    Expr::And(operands) | Expr::Or(operands) => {
+                        let negated = operands.iter().cloned().map(Expr::not).collect();
+                        (Law::DeMorgan, Expr::join(matches!(**inner, Expr::Or(_)), negated))
+                    }
+                    Expr::Var(_) | Expr::Xor(..) => return Vec::new(),
+                };
+                return vec![rewritten];
+            }
+            Expr::And(operands) => (true, operands),
+            Expr::Or(operands) => (false, operands),
+            Expr::Const(_) | Expr::Var(_) | Expr::Xor(..) => return Vec::new(),
+        };
+        let mut rewrites = Vec::new();
+        let without = |index: usize| {
+            let mut operands = operands.clone();
+            operands.remove(index);
+            Expr::join(is_and, operands)
+        };
+        // Operands of the dual operator, or the operand itself.
+        let terms = |operand: &Expr| match operand.operands() {
+            Some((inner_is_and, terms)) if inner_is_and != is_and => terms.to_vec(),
+            _ => vec![operand.clone()],
+        };
+
+        if operands.iter().any(|operand| operand.operands().is_some_and(|(inner, _)| inner == is_and)) {
+            let flattened = operands.iter().flat_map(|operand| match operand.operands() {
+                Some((inner, terms)) if inner == is_and => terms.to_vec(),
+                _ => vec![operand.clone()],
+            });
+            rewrites.push((Law::Associativity, Expr::join(is_and, flattened.collect())));
+        }
+        if operands.contains(&Expr::Const(!is_and)) {
+            rewrites.push((Law::Domination, Expr::Const(!is_and)));
+        }
+        if let Some(index) = operands.iter().position(|operand| *operand == Expr::Const(is_and)) {
+            rewrites.push((Law::Identity, without(index)));
+        }
+        for (j, operand) in operands.iter().enumerate() {
+            if operands[..j].contains(operand) {
+                rewrites.push((Law::Idempotence, without(j)));
+            }
+            if operands.contains(&operand.clone().not()) {
+                rewrites.push((Law::Complement, Expr::Const(!is_and)));
+            }
+        }
+        for (i, absorbing) in operands.iter().enumerate() {
+            let absorbing = terms(absorbing);
+            for (j, absorbed) in operands.iter().enumerate() {
+                let absorbed = terms(absorbed);
+                let covered = absorbing.iter().all(|term| absorbed.contains(term));
+                if i != j && absorbed.len() > absorbing.len() && covered {
+                    rewrites.push((Law::Absorption, without(j)));
+                }
+            }
+        }
+        for (i, lhs) in operands.iter().enumerate() {
+            let lhs = terms(lhs);
+            for (j, rhs) in operands.iter().enumerate().skip(i + 1) {
+                let rhs = terms(rhs);
+                // The opposite literals, and the consensus term of the rest.
+                for (x, literal) in lhs.iter().enumerate() {
+                    let opposite = |term: &Expr| *term == literal.clone().not() || *literal == term.clone().not();
+                    let Some(y) = rhs.iter().position(opposite) else {
+                        continue;
+                    };
+                    let mut consensus = lhs.clone();
+                    consensus.remove(x);
+                    let rest = rhs.iter().enumerate().filter(|(k, _)| *k != y);
+                    consensus.extend(rest.map(|(_, term)| term.clone()));
+                    for (k, redundant) in operands.iter().enumerate() {
+                        let redundant = terms(redundant);
+                        if k != i && k != j && consensus.iter().all(|term| redundant.contains(term)) {
+                            rewrites.push((Law::Consensus, without(k)));
+                        }
+                    }
+                }
+                // Factoring out a common term, unless it's a whole operand, which is absorption.
+                if lhs.len() < 2 || rhs.len() < 2 {
+                    continue;
+                }
+                if let Some(common) = lhs.iter().find(|term| rhs.contains(term)) {
+                    let rest = |terms: &[Expr]| {
+                        let rest = terms.iter().filter(|term| *term != common).cloned().collect();
+                        Expr::join(!is_and, rest)
+                    };
+                    let factored = Expr::join(
+                        !is_and,
+                        vec![common.clone(), Expr::join(is_and, vec![rest(&lhs), rest(&rhs)])],
+                    );
+                    let mut operands = operands.clone();
+                    operands[i] = factored;
+                    operands.remove(j);
+                    rewrites.push((Law::Distributivity, Expr::join(is_and, operands)));
+                }
+            }
+        }
+        rewrites
+    }
+
+    /// Finds a law that turns one formula into the other in a single step, in either direction.
+    ///
+    /// Operands may be reordered along the way.
+    pub fn law_between(&self, other: &Expr) -> Option<Law> {
+        let exact = |from: &Expr, to: &Expr| {
+            from.rewrites().find(|(_, rewritten)| rewritten == to)
+        };
+        if let Some((law, _)) = exact(self, other).or_else(|| exact(other, self)) {
+            return Some(law);
+        }
+        let (lhs, rhs) = (self.sorted(), other.sorted());
+        if lhs == rhs {
+            return (self != other).then_some(Law::Commutativity);
+        }
+        let reordered = |from: &Expr, to: &Expr| {
+            from.rewrites().find(|(_, rewritten)| rewritten.sorted() == *to)
+        };
+        reordered(self, &rhs).or_else(|| reordered(other, &lhs)).map(|(law, _)| law)
+    }
+
+    /// The same formula with operands of every operator sorted by how they're written.
+    fn sorted(&self) -> Expr {
+        match self {
+            Expr::Const(_) | Expr::Var(_) => self.clone(),
+            Expr::Not(inner) => inner.sorted().not(),
+            Expr::Xor(lhs, rhs) => {
+                let mut operands = [lhs.sorted(), rhs.sorted()];
+                operands.sort_by_cached_key(Expr::to_string);
+                let [lhs, rhs] = operands;
+                Expr::Xor(Box::new(lhs), Box::new(rhs))
+            }
+            Expr::And(operands) | Expr::Or(operands) => {
+                let mut operands = operands.iter().map(Expr::sorted).collect::<Vec<_>>();
+                operands.sort_by_cached_key(Expr::to_string);
+                Expr::join(matches!(self, Expr::And(_)), operands)
+            }
+        }
+    }
+}
+
+impl Function {
+    /// Simplifies the formula one law at a time, returning every intermediate formula
+    /// along with the law that turns the previous formula into it.
+    pub fn simplify(&self) -> Vec<(Law, Expr)> {
+        let mut expr = self.to_expr();
+        let mut steps = Vec::new();
+        while steps.len() < MAX_STEPS {
+            let Some((law, next)) = expr.rewrites().next() else {
+                break;
+            };
+            steps.push((law, next.clone()));
+            expr = next;
+        }
+        steps
+    }
+}
+
+#[cfg(test)]
+mod rewrite_tests {
+    use super::Law;
+    use crate::function::Function;
+
+    fn simplified(formula: &str) -> (String, Vec<Law>) {
+        let func = Function::parse(formula).unwrap();
+        let steps = func.simplify();
+        for (_, expr) in &steps {
+            let step = Function::parse(&expr.to_string()).unwrap();
+            assert_eq!(None, func.compare(&step).counterexample(), "{formula} = {expr}");
+        }
+        let laws = steps.iter().map(|(law, _)| *law).collect();
+        let last = steps.last().map_or(func.to_string(), |(_, expr)| expr.to_string());
+        (last, laws)
+    }
+
+    #[test]
+    fn simplify_laws() {
+        assert_eq!(("a".to_string(), vec![Law::DoubleNegation]), simplified("!!a"));
+        assert_eq!(("0".to_string(), vec![Law::Complement]), simplified("a & b & !a"));
+        assert_eq!(("a".to_string(), vec![Law::Absorption]), simplified("a | a & b"));
+        assert_eq!(("a & b".to_string(), vec![Law::Idempotence]), simplified("a & b & a"));
+        assert_eq!(("a".to_string(), vec![Law::Identity]), simplified("a & 1"));
+        assert_eq!(("0".to_string(), vec![Law::Domination]), simplified("a & 0 & b"));
+        assert_eq!(("a & (b | c)".to_string(), vec![Law::Distributivity]), simplified("a & b | a & c"));
+        assert_eq!(("a & b | !a & c".to_string(), vec![Law::Consensus]), simplified("a & b | !a & c | b & c"));
+        assert_eq!(("a | b | c".to_string(), vec![Law::Associativity]), simplified("a | (b | c)"));
+    }
+
+    #[test]
+    fn simplify_derivation() {
+        let (result, laws) = simplified("!(!a | !b) | a & !b");
+        assert_eq!("a", result);
+        assert_eq!(Law::DeMorgan, laws[0]);
+        assert_eq!(("!a".to_string(), Vec::new()), simplified("!a"));
+        assert_eq!("1", simplified("!(a & !a)").0);
+    }
+
+    #[test]
+    fn laws_between() {
+        let law = |lhs: &str, rhs: &str| {
+            let [lhs, rhs] = [lhs, rhs].map(|formula| Function::parse(formula).unwrap().to_expr());
+            lhs.law_between(&rhs)
+        };
+        assert_eq!(Some(Law::DeMorgan), law("!(a & b)", "!a | !b"));
+        assert_eq!(Some(Law::DeMorgan), law("!b | !a", "!(b & a)"));
+        assert_eq!(Some(Law::Distributivity), law("a & (b | c)", "a & b | a & c"));
+        assert_eq!(Some(Law::Absorption), law("a & b | a", "a"));
+        assert_eq!(Some(Law::Commutativity), law("a | b & c", "c & b | a"));
+        assert_eq!(Some(Law::Complement), law("b & !b | a", "a | 0"));
+        assert_eq!(None, law("a", "a"));
+        assert_eq!(None, law("!(a | b)", "!a | !b"));
+    }
+}
@@ -0,0 +1,63 @@
+use super::Function;
+
+/// State of evaluation after a single postfix token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Index of the token, which is also the index of its node in the parse tree.
+    pub index: usize,
+    pub token: String,
+    /// Values on the stack, each with the index of the token that pushed it, from the bottom.
+    pub stack: Vec<(usize, bool)>,
+}
+
+impl Function {
+    /// Records how the function is evaluated on provided values, one postfix token at a time.
+    ///
+    /// Returns `None` if there are fewer values than variables.
+    pub fn trace(&self, vars: &[bool]) -> Option<Vec<Step>> {
+        let mut steps = Vec::with_capacity(self.postfix.len());
+        let mut origins = Vec::<usize>::new();
+        self.eval_observed(
+            |var| vars.get(self.var_index(var)?).copied(),
+            |index, stack| {
+                let token = &self.postfix[index];
+                origins.truncate(origins.len() - token.arity());
+                origins.push(index);
+                steps.push(Step {
+                    index,
+                    token: token.to_string(),
+                    stack: origins.iter().copied().zip(stack.iter().copied()).collect(),
+                });
+            },
+        )?;
+        Some(steps)
+    }
+}
+
+#[cfg(test)]
+mod trace_tests {
+    use crate::function::{Function, ParseTree};
+
+    #[test]
+    fn trace_stack() {
+        let func = Function::parse("!a | b & c").unwrap();
+        let steps = func.trace(&[true, true, false]).unwrap();
+        let tokens = steps.iter().map(|step| step.token.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["a", "!", "b", "c", "&", "|"], tokens);
+        assert_eq!(vec![(1, false), (2, true), (3, false)], steps[3].stack);
+        assert_eq!(vec![(5, false)], steps[5].stack);
+        assert_eq!(func.eval(&[true, true, false]), Some(steps[5].stack[0].1));
+        assert_eq!(None, func.trace(&[true]));
+    }
+
+    #[test]
+    fn trace_follows_tree() {
+        let tree = ParseTree::parse("(a | b) & !(a & c)").unwrap();
+        let values = [false, true, true];
+        for step in tree.function().trace(&values).unwrap() {
+            let (index, value) = *step.stack.last().unwrap();
+            assert_eq!(step.index, index);
+            assert_eq!(Some(value), tree.subexpression(index).eval(&values));
+        }
+    }
+}
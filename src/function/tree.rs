@@ -0,0 +1,140 @@
+use std::ops::Range;
+
+use super::{Function, ParseError, Variable};
+
+/// Parse tree of a formula, showing how operator precedence and brackets were applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTree {
+    source: String,
+    func: Function,
+    /// Nodes in postfix order, so the root is the last one.
+    nodes: Vec<Node>,
+}
+
+/// Operator or operand of a formula, along with the subexpression it's the root of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Operator symbol, variable name or constant.
+    pub label: String,
+    /// Indices of operands, from left to right.
+    pub children: Vec<usize>,
+    /// Bytes of the formula taken by the subexpression, without enclosing brackets.
+    pub span: Range<usize>,
+    /// Postfix tokens of the subexpression, which are contiguous.
+    tokens: Range<usize>,
+}
+
+impl ParseTree {
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let (func, spans) = Function::parse_spanned(s)?;
+        let mut nodes = Vec::<Node>::with_capacity(func.postfix.len());
+        let mut stack = Vec::<usize>::new();
+        for (index, (token, span)) in func.postfix.iter().zip(spans).enumerate() {
+            let children = stack.split_off(stack.len() - token.arity());
+            let label = token.to_string();
+            // Operator spans cover only the operator itself until operands are added.
+            let (span, first) = match (children.first(), children.last()) {
+                (Some(&first), Some(&last)) => {
+                    let start = span.start.min(nodes[first].span.start);
+                    let span = enclose_brackets(s, start..nodes[last].span.end);
+                    (span, nodes[first].tokens.start)
+                }
+                _ => (span, index),
+            };
+            nodes.push(Node { label, children, span, tokens: first..index + 1 });
+            stack.push(index);
+        }
+        Ok(ParseTree { source: s.to_string(), func, nodes })
+    }
+
+    /// Formula the tree is parsed from, which node spans refer to.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Function the formula defines.
+    pub fn function(&self) -> &Function {
+        &self.func
+    }
+
+    /// The same tree with its function over sorted `inputs`, like ones that don't-care rows add,
+    /// or `None` if they don't include all variables of the formula.
+    pub fn with_inputs(&self, inputs: &[Variable]) -> Option<Self> {
+        if !self.func.variables.iter().all(|var| inputs.binary_search(var).is_ok()) {
+            return None;
+        }
+        let func = Function { variables: inputs.to_vec(), postfix: self.func.postfix.clone() };
+        Some(ParseTree { func, ..self.clone() })
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Function defined by the subexpression of node `index`, over all variables of the formula.
+    pub fn subexpression(&self, index: usize) -> Function {
+        Function {
+            variables: self.func.variables.clone(),
+            postfix: self.func.postfix[self.nodes[index].tokens.clone()].to_vec(),
+        }
+    }
+}
+
+/// Extends the span to brackets that are opened or closed in it, but not both.
+///
+/// They are left out when an operand in brackets is at the edge of the span.
+fn enclose_brackets(s: &str, span: Range<usize>) -> Range<usize> {
+    let (mut depth, mut unopened) = (0, 0);
+    for ch in s[span.clone()].chars() {
+        match ch {
+            '(' => depth += 1,
+            ')' if depth == 0 => unopened += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    let (mut start, mut end) = (span.start, span.end);
+    for _ in 0..depth {
+        end += s[end..].find(')').expect("brackets should be balanced") + 1;
+    }
+    for _ in 0..unopened {
+        start = s[..start].rfind('(').expect("brackets should be balanced");
+    }
+    start..end
+}
+
+#[cfg(test)]
+mod tree_tests {
+    use super::ParseTree;
+
+    #[test]
+    fn tree_structure() {
+        let formula = "!x & (y | z) | !z";
+        let tree = ParseTree::parse(formula).unwrap();
+        let root = tree.nodes().last().unwrap();
+        assert_eq!("|", root.label);
+        assert_eq!(0..formula.len(), root.span);
+
+        let [lhs, rhs] = [0, 1].map(|i| &tree.nodes()[root.children[i]]);
+        assert_eq!(("&", "!x & (y | z)"), (lhs.label.as_str(), &formula[lhs.span.clone()]));
+        assert_eq!(("!", "!z"), (rhs.label.as_str(), &formula[rhs.span.clone()]));
+        // Brackets aren't part of the subexpression.
+        let bracketed = &tree.nodes()[lhs.children[1]];
+        assert_eq!("y | z", &formula[bracketed.span.clone()]);
+    }
+
+    #[test]
+    fn tree_subexpressions() {
+        let tree = ParseTree::parse("a & (b | c)").unwrap();
+        let or = tree.nodes().last().unwrap().children[1];
+        let sub = tree.subexpression(or);
+        assert_eq!("b | c", sub.to_string());
+        assert_eq!(tree.function().vars(), sub.vars());
+        assert_eq!(Some(true), sub.eval(&[false, true, false]));
+        assert_eq!(tree.function(), &tree.subexpression(tree.nodes().len() - 1));
+
+        let tree = ParseTree::parse("((a)) | b").unwrap();
+        let or = tree.nodes().last().unwrap();
+        assert_eq!(0..9, or.span);
+    }
+}
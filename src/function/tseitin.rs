@@ -0,0 +1,123 @@
+use crate::sat::{Cnf, Lit, Outcome, Solver, Var};
+
+use super::{parse::PostfixToken, Function, Variable};
+
+impl Function {
+    /// Encodes the function into CNF using Tseitin transformation.
+    ///
+    /// Variable `inputs[i]` is mapped to CNF variable `i`, so `cnf` should already have them allocated.
+    /// Returns a literal that is true iff the function is true; it is not asserted.
+    pub fn tseitin(&self, cnf: &mut Cnf, inputs: &[Variable]) -> Lit {
+        let mut stack = Vec::<Lit>::with_capacity(16);
+        for token in self.postfix.iter() {
+            let lit = match token {
+                PostfixToken::Not => !stack.pop().unwrap(),
+                PostfixToken::And => {
+                    let (a, b) = (stack.pop().unwrap(), stack.pop().unwrap());
+                    let out = cnf.new_var().positive();
+                    cnf.add_clause([!out, a]);
+                    cnf.add_clause([!out, b]);
+                    cnf.add_clause([out, !a, !b]);
+                    out
+                }
+                PostfixToken::Or => {
+                    let (a, b) = (stack.pop().unwrap(), stack.pop().unwrap());
+                    let out = cnf.new_var().positive();
+                    cnf.add_clause([out, !a]);
+                    cnf.add_clause([out, !b]);
+                    cnf.add_clause([!out, a, b]);
+                    out
+                }
+                PostfixToken::Xor => {
+                    let (a, b) = (stack.pop().unwrap(), stack.pop().unwrap());
+                    let out = cnf.new_var().positive();
+                    cnf.add_clause([!out, a, b]);
+                    cnf.add_clause([!out, !a, !b]);
+                    cnf.add_clause([out, !a, b]);
+                    cnf.add_clause([out, a, !b]);
+                    out
+                }
+                PostfixToken::Const(val) => {
+                    let out = cnf.new_var().positive();
+                    cnf.add_clause([out]);
+                    if *val { out } else { !out }
+                }
+                PostfixToken::Var(var) => {
+                    let index = inputs
+                        .iter()
+                        .position(|input| input == var)
+                        .expect("every variable of the function should be an input");
+                    Var(index as u32).positive()
+                }
+            };
+            stack.push(lit);
+        }
+        stack.pop().expect("function should not be empty")
+    }
+
+    /// Finds an assignment of [`Function::vars`] where function is true, if there is one.
+    pub fn satisfy(&self) -> Option<Vec<bool>> {
+        self.solve_for(true)
+    }
+
+    /// Finds an assignment of [`Function::vars`] where function is false, if there is one.
+    ///
+    /// Function is a tautology iff there is no such assignment.
+    pub fn falsify(&self) -> Option<Vec<bool>> {
+        self.solve_for(false)
+    }
+
+    fn solve_for(&self, value: bool) -> Option<Vec<bool>> {
+        let mut cnf = Cnf::with_vars(self.vars().len() as u32);
+        let root = self.tseitin(&mut cnf, self.vars());
+        cnf.add_clause([if value { root } else { !root }]);
+        match Solver::new(&cnf).solve() {
+            Outcome::Sat(mut model) => {
+                model.truncate(self.vars().len());
+                Some(model)
+            }
+            Outcome::Unsat => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tseitin_tests {
+    use super::Function;
+
+    #[test]
+    fn satisfy() {
+        let func = Function::parse("!x & (y | z) & !y").unwrap();
+        let model = func.satisfy().unwrap();
+        assert_eq!(vec![false, false, true], model);
+        assert_eq!(Some(true), func.eval(&model));
+
+        let func = Function::parse("x & !x | 0").unwrap();
+        assert_eq!(None, func.satisfy());
+    }
+
+    #[test]
+    fn falsify() {
+        let func = Function::parse("!(x & y) | x").unwrap();
+        assert_eq!(None, func.falsify());
+
+        let func = Function::parse("x | !y").unwrap();
+        assert_eq!(Some(vec![false, true]), func.falsify());
+    }
+
+    #[test]
+    fn many_variables() {
+        // Pairwise implications chain over all letters is satisfiable only by equal values.
+        let letters = ('a'..='z').collect::<Vec<_>>();
+        let chain = letters
+            .windows(2)
+            .map(|pair| format!("(!{} | {})", pair[0], pair[1]))
+            .collect::<Vec<_>>()
+            .join(" & ");
+        let func = Function::parse(&format!("{chain} & a & !z")).unwrap();
+        assert_eq!(None, func.satisfy());
+
+        let func = Function::parse(&format!("{chain} & a")).unwrap();
+        assert_eq!(Some(vec![true; 26]), func.satisfy());
+    }
+}
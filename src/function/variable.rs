@@ -0,0 +1,106 @@
+use std::{cmp::Ordering, fmt::Display, rc::Rc};
+
+/// Name of a variable.
+///
+/// Valid name starts with a lowercase latin letter,
+/// that may be followed by lowercase latin letters, digits and underscores.
+///
+/// Variables are ordered naturally, so `x2` goes before `x10`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Variable(Rc<str>);
+
+impl Variable {
+    /// Creates a variable if provided name is valid.
+    pub fn new(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        let valid = chars.next().is_some_and(Self::is_start)
+            && chars.all(Self::is_continuation);
+        valid.then(|| Variable(Rc::from(name)))
+    }
+
+    /// Checks if character may start a name.
+    pub fn is_start(ch: char) -> bool {
+        ch.is_ascii_lowercase()
+    }
+
+    /// Checks if character may occur in a name after the first one.
+    pub fn is_continuation(ch: char) -> bool {
+        ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_'
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Variable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialOrd for Variable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Variable {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (mut lhs, mut rhs) = (self.as_str(), other.as_str());
+        while !lhs.is_empty() && !rhs.is_empty() {
+            let (lhs_chunk, lhs_rest) = split_chunk(lhs);
+            let (rhs_chunk, rhs_rest) = split_chunk(rhs);
+            let is_number = |chunk: &str| chunk.starts_with(|ch: char| ch.is_ascii_digit());
+            let ordering = if is_number(lhs_chunk) && is_number(rhs_chunk) {
+                let (lhs_num, rhs_num) = (
+                    lhs_chunk.trim_start_matches('0'),
+                    rhs_chunk.trim_start_matches('0'),
+                );
+                lhs_num.len().cmp(&rhs_num.len()).then(lhs_num.cmp(rhs_num))
+            } else {
+                lhs_chunk.cmp(rhs_chunk)
+            };
+            if ordering.is_ne() {
+                return ordering;
+            }
+            (lhs, rhs) = (lhs_rest, rhs_rest);
+        }
+        lhs.len().cmp(&rhs.len()).then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+/// Splits off the longest prefix that consists only of digits or only of non-digits.
+fn split_chunk(s: &str) -> (&str, &str) {
+    let is_digit = s.starts_with(|ch: char| ch.is_ascii_digit());
+    let end = s
+        .find(|ch: char| ch.is_ascii_digit() != is_digit)
+        .unwrap_or(s.len());
+    s.split_at(end)
+}
+
+#[cfg(test)]
+mod variable_tests {
+    use super::Variable;
+
+    #[test]
+    fn variable_validation() {
+        assert!(Variable::new("x").is_some());
+        assert!(Variable::new("cout").is_some());
+        assert!(Variable::new("x_12").is_some());
+        assert!(Variable::new("").is_none());
+        assert!(Variable::new("1x").is_none());
+        assert!(Variable::new("_x").is_none());
+        assert!(Variable::new("Xy").is_none());
+        assert!(Variable::new("x-y").is_none());
+    }
+
+    #[test]
+    fn variable_order() {
+        let mut vars = ["x10", "y", "x2", "x", "x1_2", "x1", "ab", "x02"]
+            .map(|name| Variable::new(name).unwrap());
+        vars.sort();
+        let names = vars.iter().map(Variable::as_str).collect::<Vec<_>>();
+        assert_eq!(vec!["ab", "x", "x1", "x1_2", "x02", "x2", "x10", "y"], names);
+    }
+}
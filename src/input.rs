@@ -1,10 +1,43 @@
 pub mod text_input;
 pub mod graph_input;
+pub mod file_import;
 pub mod selector;
 
+use thiserror::Error;
+
+use crate::{
+    function::{AigerError, BlifError, DefinitionError, DerivationError, ParseError, PlaError},
+    sat::DimacsError,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum InputKind {
     #[default]
     Text = 0,
     Graph = 1,
+    /// Two text expressions that are checked for equivalence.
+    Compare = 2,
+    /// Chain of equal formulas, where every step is checked.
+    Derivation = 3,
+}
+
+/// An error in the function provided by the user.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InputError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Definition(#[from] DefinitionError),
+    #[error(transparent)]
+    Derivation(#[from] DerivationError),
+    #[error("invalid DIMACS file, {0}")]
+    Dimacs(#[from] DimacsError),
+    #[error("invalid BLIF file, {0}")]
+    Blif(#[from] BlifError),
+    #[error("invalid AIGER file, {0}")]
+    Aiger(#[from] AigerError),
+    #[error("invalid PLA file, {0}")]
+    Pla(#[from] PlaError),
+    #[error("format of `{0}` is not supported")]
+    UnsupportedFile(String),
 }
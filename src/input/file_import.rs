@@ -0,0 +1,81 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlInputElement};
+use yew::{function_component, html, AttrValue, Callback, Html, Properties};
+
+use super::InputError;
+use crate::{
+    files,
+    function::{Function, MultiFunction},
+    sat::Cnf,
+};
+
+/// A file picked by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedFile {
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+impl LoadedFile {
+    /// Extension of the file name in lowercase.
+    pub fn extension(&self) -> Option<String> {
+        let (_, extension) = self.name.rsplit_once('.')?;
+        Some(extension.to_ascii_lowercase())
+    }
+}
+
+/// A button that loads a file from user's device.
+#[function_component]
+pub fn FileImport(props: &Props) -> Html {
+    let onchange = {
+        let onload = props.onload.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e
+                .target()
+                .expect("Event should have a target when dispatched")
+                .unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            let name = file.name();
+            files::read(&file, onload.reform(move |contents| LoadedFile {
+                name: name.clone(),
+                contents,
+            }));
+            // Allows loading the same file again after it was edited.
+            input.set_value("");
+        })
+    };
+    html! {
+        <label class="file-import" role="button">
+            {&props.label}
+            <input type="file" accept={props.accept.clone()} {onchange}/>
+        </label>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub onload: Callback<LoadedFile>,
+    /// Comma-separated list of file extensions that are offered to the user.
+    pub accept: AttrValue,
+    pub label: AttrValue,
+}
+
+/// Extensions of files that [`import`] understands.
+pub const ACCEPTED: &str = ".cnf,.dimacs,.blif,.aag,.aig,.pla";
+
+/// Reads a function from a file, choosing the format by file extension.
+///
+/// Only PLA files may have several outputs.
+pub fn import(file: &LoadedFile) -> Result<MultiFunction, InputError> {
+    let text = String::from_utf8_lossy(&file.contents);
+    let func = match file.extension().as_deref() {
+        Some("cnf" | "dimacs") => Function::from_cnf(&Cnf::from_dimacs(&text)?),
+        Some("blif") => Function::from_blif(&text)?,
+        Some("aag" | "aig") => Function::from_aiger(&file.contents)?,
+        Some("pla") => return Ok(MultiFunction::from_pla(&text)?),
+        _ => return Err(InputError::UnsupportedFile(file.name.clone())),
+    };
+    Ok(func.into())
+}
@@ -3,19 +3,26 @@ use yew::{function_component, html, Properties, Callback};
 
 #[function_component]
 pub fn InputKindSelector(props: &Props) -> yew::Html {
+    let button = |kind: InputKind, icon: &'static str| html! {
+        <button
+            class={(props.selected == kind).then_some("selected")}
+            onclick = {props.onselect.reform(move |_| kind)}
+        >
+            <img class="icon" src={icon}/>
+        </button>
+    };
     html! {
         <menu id="input-kind-selector">
-            <button class="selected" onclick = {props.onselect.reform(|_| InputKind::Text)}>
-                <img class="icon" src="static/icons/text.svg"/>
-            </button>
-            <button onclick = {props.onselect.reform(|_| InputKind::Graph)}>
-                <img class="icon" src="static/icons/graph.svg"/>
-            </button>
+            {button(InputKind::Text, "static/icons/text.svg")}
+            {button(InputKind::Graph, "static/icons/graph.svg")}
+            {button(InputKind::Compare, "static/icons/compare.svg")}
+            {button(InputKind::Derivation, "static/icons/derivation.svg")}
         </menu>
     }
 }
 
 #[derive(Debug, Properties, PartialEq)]
 pub struct Props {
+    pub selected: InputKind,
     pub onselect: Callback<InputKind>,
 }
@@ -1,5 +1,8 @@
+use std::ops::Range;
+
+use crate::function::Variable;
 use web_sys::{Event, HtmlElement, HtmlInputElement};
-use yew::{html, Callback, Component, Context, Html, NodeRef, Properties};
+use yew::{html, AttrValue, Callback, Component, Context, Html, NodeRef, Properties};
 
 /// An input of arbitrary text expression.
 pub struct TextInput {
@@ -10,6 +13,23 @@ pub struct TextInput {
 #[derive(Debug, PartialEq, Properties)]
 pub struct Props {
     pub onchange: Callback<Event>,
+    /// Id of the inner `input` element; should be unique if there are several inputs.
+    #[prop_or(AttrValue::Static("formula-input-inner"))]
+    pub id: AttrValue,
+    #[prop_or(AttrValue::Static("Input your formula:"))]
+    pub label: AttrValue,
+    /// Replaces the text whenever it changes, e.g. when a formula is loaded from a file.
+    #[prop_or_default]
+    pub value: Option<AttrValue>,
+    /// Bytes of the text that are marked, e.g. a subexpression hovered elsewhere.
+    #[prop_or_default]
+    pub highlight: Option<Range<usize>>,
+    /// Whether the text is a chain of formulas separated by `=`.
+    #[prop_or_default]
+    pub chain: bool,
+    /// Whether the text may define named outputs, like `s = a ^ b; c = a & b`, or list minterms.
+    #[prop_or_default]
+    pub definitions: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -44,21 +64,35 @@ impl Component for TextInput {
         true
     }
 
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        match &ctx.props().value {
+            Some(value) if old_props.value.as_ref() != Some(value) => {
+                self.value = value.to_string();
+                if let Some(input) = self.input.cast::<HtmlInputElement>() {
+                    input.set_value(value);
+                }
+                true
+            }
+            _ => ctx.props() != old_props,
+        }
+    }
+
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let Props { id, label, highlight, chain, definitions, .. } = ctx.props();
         html! {
             <>
-                <label for="formula-input-inner">{"Input your formula:"}</label>
-                <div id="formula-input">
+                <label for={id.clone()}>{label}</label>
+                <div class="formula-input">
                     <input
                         ref = {self.input.clone()}
                         type="text"
-                        id="formula-input-inner"
+                        id={id.clone()}
                         enterkeyhint="done"
                         oninput={ctx.link().callback(|_| Msg::OnInput)}
                         onchange={ctx.link().callback(Msg::OnChange)}
                     />
                     <pre aria-hidden="true">
-                        {highlighting(self.value.as_str())}
+                        {highlighting(self.value.as_str(), highlight.as_ref(), *chain, *definitions)}
                     </pre>
                 </div>
             </>
@@ -72,7 +106,7 @@ impl Component for TextInput {
     }
 }
 
-fn highlighting(input: &str) -> Html {
+fn highlighting(input: &str, marked: Option<&Range<usize>>, chain: bool, definitions: bool) -> Html {
     enum Entry {
         Variable(char),
         Operator(char),
@@ -84,14 +118,25 @@ fn highlighting(input: &str) -> Html {
         Unknown(char),
     }
 
-    let mut buffer = Vec::<Entry>::with_capacity(input.len());
+    let mut buffer = Vec::<(bool, Entry)>::with_capacity(input.len());
     let mut depth = 0i32;
-    for ch in input.chars() {
-        buffer.push(match ch {
-            'a'..='z' | '0' | '1'
+    let mut in_name = false;
+    for (pos, ch) in input.char_indices() {
+        let is_marked = marked.is_some_and(|marked| marked.contains(&pos));
+        in_name = Variable::is_start(ch) || in_name && Variable::is_continuation(ch);
+        buffer.push((is_marked, match ch {
+            _ if in_name
                 => Entry::Variable(ch),
-            '&' | '|' | '!'
+            '0' | '1'
+                => Entry::Variable(ch),
+            '&' | '|' | '^' | '!'
+                => Entry::Operator(ch),
+            '=' if chain || definitions
+                => Entry::Operator(ch),
+            ';' | 'Σ' | '+' | ',' | '.' if definitions
                 => Entry::Operator(ch),
+            '2'..='9' if definitions
+                => Entry::Variable(ch),
             '(' => {
                 let entry = Entry::Bracket { depth, is_left: true };
                 depth += 1;
@@ -104,10 +149,10 @@ fn highlighting(input: &str) -> Html {
             _ if ch.is_whitespace()
                 => Entry::Whitespace(ch),
             _ => Entry::Unknown(ch),
-        })
+        }))
     }
     let highlighted = buffer.into_iter()
-        .map(|entry| match entry {
+        .map(|(is_marked, entry)| (is_marked, match entry {
             Entry::Variable(var)
                 => html!(<span class="variable">{var}</span>),
             Entry::Operator(op)
@@ -131,6 +176,10 @@ fn highlighting(input: &str) -> Html {
                 => html!(ch),
             Entry::Unknown(ch)
                 => html!(<span class="error">{ch}</span>),
+        }))
+        .map(|(is_marked, html)| match is_marked {
+            true => html!(<mark>{html}</mark>),
+            false => html,
         })
         .collect::<Html>();
     html!(<code>{highlighted}</code>)
@@ -1,23 +1,175 @@
+mod bdd;
 mod bool_iterator;
+mod files;
 mod input;
 mod function;
+mod panels;
+mod sat;
 mod svg_gen;
 mod table_gen;
 
-use crate::input::{text_input::TextInput, selector::InputKindSelector};
-use crate::function::Function;
-use crate::table_gen::TruthTable;
-use function::ParseError;
+use crate::input::{
+    file_import::{self, FileImport},
+    selector::InputKindSelector,
+    text_input::TextInput,
+    InputError, InputKind,
+};
+use crate::function::{
+    check_derivation, AigerFormat, CnfEncoding, DerivationError, Equality, Function, Hdl, HdlBody,
+    MultiFunction, ParseTree, Variable, Verdict, ENUMERATION_LIMIT, MINIMIZATION_LIMIT,
+};
+use crate::panels::{
+    circuit::CircuitPanel, conversion::ConversionPanel, diagram::DiagramPanel,
+    minimization::MinimizationPanel, models::ModelList, simplification::SimplificationPanel,
+    table::TablePanel, trace::TracePanel, tree::TreePanel,
+};
+use crate::svg_gen::TREE_LIMIT;
+use crate::table_gen::{ComparisonTable, TABLE_LIMIT};
 use wasm_bindgen::JsCast;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
+/// Function provided by the user, if any.
+type Input<T = Function> = Option<Result<T, InputError>>;
+
 #[function_component]
 fn App() -> Html {
-    let input = use_state(|| None);
-    let setter = input.setter();
+    let kind = use_state(InputKind::default);
+    let input = use_state(|| None::<Result<MultiFunction, InputError>>);
+    let lhs = use_state(|| None);
+    let other = use_state(|| None);
+    let loaded_text = use_state(|| None);
+    let hovered = use_state_eq(|| None);
+    // Source of the formula, for the parse tree to refer to.
+    let text = use_state(String::new);
+    let node = use_state_eq(|| None::<usize>);
+    // Don't-care rows added by the truth table are left out, while spans still point into the input.
+    let tree = use_memo(|text| ParseTree::parse(&MultiFunction::without_dont_cares(text)).ok(), (*text).clone());
+    let chain = use_state(String::new);
+    let derivation = use_memo(
+        |chain| (!chain.trim().is_empty()).then(|| check_derivation(chain)),
+        (*chain).clone(),
+    );
+
+    let onselect = {
+        let kind = kind.setter();
+        Callback::from(move |selected| kind.set(selected))
+    };
+
+    let onload = {
+        let input = input.setter();
+        let loaded_text = loaded_text.setter();
+        let text = text.setter();
+        let node = node.setter();
+        Callback::from(move |file| {
+            let outputs = file_import::import(&file);
+            if let Ok(outputs) = &outputs {
+                loaded_text.set(Some(AttrValue::from(outputs.to_string())));
+                text.set(outputs.to_string());
+            }
+            node.set(None);
+            input.set(Some(outputs));
+        })
+    };
+    let onchange = {
+        let parse = parse_on_change(input.setter(), MultiFunction::parse);
+        let text = text.setter();
+        let node = node.setter();
+        Callback::from(move |e: Event| {
+            text.set(e.target_unchecked_into::<HtmlInputElement>().value());
+            node.set(None);
+            parse.emit(e);
+        })
+    };
+    let highlight = node
+        .zip(tree.as_ref().as_ref())
+        .and_then(|(index, tree)| Some(tree.nodes().get(index)?.span.clone()));
+
+    let onchain = {
+        let chain = chain.setter();
+        Callback::from(move |e: Event| chain.set(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+    // The first wrong step is marked in the chain.
+    let wrong_step = match derivation.as_ref() {
+        Some(Ok(equalities)) => equalities
+            .iter()
+            .find(|equality| matches!(equality.verdict, Verdict::Differs(_)))
+            .map(|equality| equality.rhs.clone()),
+        _ => None,
+    };
+
+    // Clicking outputs of a truth table row toggles whether it's a don't-care, and rewrites the input.
+    let ontoggle = {
+        let input = input.clone();
+        let loaded_text = loaded_text.setter();
+        let text = text.setter();
+        Callback::from(move |minterm| {
+            if let Some(Ok(outputs)) = input.as_ref() {
+                let outputs = outputs.toggle_dont_care(minterm);
+                loaded_text.set(Some(AttrValue::from(outputs.to_string())));
+                text.set(outputs.to_string());
+                input.set(Some(Ok(outputs)));
+            }
+        })
+    };
+
+    let result = match *kind {
+        InputKind::Compare => comparison_display(lhs.clone(), other.clone()),
+        InputKind::Derivation => derivation_display(&chain, derivation.as_ref().as_ref()),
+        _ => result_display(input.clone(), hovered, ontoggle, tree.as_ref().as_ref(), node),
+    };
+
+    html! {
+        <>
+            <main>
+                <article>
+                    if *kind == InputKind::Compare {
+                        <TextInput
+                            onchange={parse_on_change(lhs.setter(), Function::parse)}
+                            id="formula-input-lhs"
+                            label="Input the first formula:"
+                        />
+                        <TextInput
+                            onchange={parse_on_change(other.setter(), Function::parse)}
+                            id="formula-input-rhs"
+                            label="Input the second formula:"
+                        />
+                    } else if *kind == InputKind::Derivation {
+                        <TextInput
+                            onchange={onchain}
+                            id="derivation-input"
+                            label="Input a derivation, like !(a & b) = !a | !b:"
+                            highlight={wrong_step}
+                            chain=true
+                        />
+                    } else {
+                        <TextInput
+                            {onchange}
+                            label={concat!(
+                                "Input your formula, definitions like s = a ^ b; c = a & b, ",
+                                "or minterms like Σm(1, 3) + d(4..7):",
+                            )}
+                            value={(*loaded_text).clone()}
+                            {highlight}
+                            definitions=true
+                        />
+                        <FileImport {onload} accept={file_import::ACCEPTED} label="Import file"/>
+                    }
+                    <InputKindSelector selected={*kind} {onselect}/>
+                </article>
+                {result}
+            </main>
+            <footer>{"Made by\u{00A0}"}<a href="https://github.com/ClayenKitten">{"ClayenKitten"}</a></footer>
+        </>
+    }
+}
 
-    let onchange = Callback::from(move |e: Event| {
+/// Creates a callback that parses input's value on change and stores it in the state.
+fn parse_on_change<T: 'static, E: Into<InputError> + 'static>(
+    setter: UseStateSetter<Input<T>>,
+    parse: fn(&str) -> Result<T, E>,
+) -> Callback<Event> {
+    Callback::from(move |e: Event| {
         let element: HtmlInputElement = e
             .target()
             .expect("Event should have a target when dispatched")
@@ -27,54 +179,286 @@ fn App() -> Html {
         if string.is_empty() || string.chars().all(|ch| ch.is_whitespace()) {
             setter.set(None);
         } else {
-            setter.set(Some(Function::parse(&string)));
+            setter.set(Some(parse(&string).map_err(Into::into)));
         }
+    })
+}
+
+/// Shows properties of the function, with truth table rows and diagram regions hovered together.
+///
+/// The truth table, minimization, diagrams, simplifications and circuits cover every output,
+/// and other sections the first one.
+/// Hovering a node of the parse tree adds a column with values of its subexpression to the truth table.
+/// Clicking outputs of a truth table row emits its minterm index to `ontoggle`.
+fn result_display(
+    formula: UseStateHandle<Input<MultiFunction>>,
+    hovered: UseStateHandle<Option<u64>>,
+    ontoggle: Callback<u64>,
+    tree: Option<&ParseTree>,
+    node: UseStateHandle<Option<usize>>,
+) -> Html {
+    let outputs = match formula.as_ref() {
+        Some(Ok(outputs)) => outputs,
+        Some(Err(err)) => return error(err),
+        None => return html!(),
+    };
+    let func = outputs.first();
+
+    let onhover = {
+        let hovered = hovered.setter();
+        Callback::from(move |minterm| hovered.set(minterm))
+    };
+    // Tree of an edited formula may be ahead of the function until the input is committed.
+    let tree = tree.and_then(|tree| tree.with_inputs(outputs.inputs())).filter(|tree| tree.function() == func);
+    let tree = tree.as_ref();
+    let subexpression = node
+        .zip(tree)
+        .filter(|(index, tree)| *index < tree.nodes().len())
+        .map(|(index, tree)| tree.subexpression(index));
+    // Don't-care rows are left out of the key, so that toggling them keeps the page and the order.
+    let definitions = outputs.outputs().iter().map(|(name, func)| format!("{name} = {func}; "));
+    let table = (func.vars().len() <= TABLE_LIMIT).then(|| html! {
+        <TablePanel
+            key={definitions.collect::<String>()}
+            outputs={outputs.clone()}
+            hovered={*hovered}
+            onhover={onhover.clone()}
+            {ontoggle}
+            {subexpression}
+        />
     });
 
+    let parse_tree = tree.filter(|tree| tree.nodes().len() <= TREE_LIMIT).map(|tree| {
+        let onhover = {
+            let node = node.setter();
+            Callback::from(move |index| node.set(index))
+        };
+        html! {
+            <>
+                <TreePanel tree={tree.clone()} hovered={*node} onhover={onhover.clone()}/>
+                <TracePanel key={func.to_string()} tree={tree.clone()} onnode={onhover}/>
+                <ConversionPanel formula={tree.source().to_string()}/>
+            </>
+        }
+    });
+
+    let minimization = (outputs.inputs().len() <= MINIMIZATION_LIMIT).then(|| html! {
+        <MinimizationPanel outputs={outputs.clone()}/>
+    });
+
+    // Every output has its own diagram, simplification and circuit, named unless it's the only one.
+    let named = outputs.outputs().len() > 1;
+    let heading = |name: &String| named.then(|| AttrValue::from(name.clone()));
+    let simplifications = outputs.outputs().iter().map(|(name, func)| html! {
+        <SimplificationPanel key={format!("{name} = {func}")} func={func.clone()} name={heading(name)}/>
+    });
+    let circuits = outputs.outputs().iter().map(|(name, func)| html! {
+        <CircuitPanel key={format!("{name} = {func}")} func={func.clone()} name={heading(name)}/>
+    });
+    let charts = outputs.outputs().iter().filter(|_| outputs.inputs().len() <= svg_gen::HYPERCUBE_LIMIT);
+    let charts = charts.map(|(name, func)| html! {
+        <DiagramPanel
+            key={format!("{name} = {func}")}
+            func={func.clone()}
+            dont_care={outputs.dont_care().cloned()}
+            name={heading(name)}
+            hovered={*hovered}
+            onhover={onhover.clone()}
+        />
+    });
+    let verdicts = outputs.outputs().iter().map(|(name, func)| satisfiability(named.then_some(name), func));
+
     html! {
-        <>
-            <main>
-                <article>
-                    <TextInput {onchange} />
-                    <InputKindSelector onselect={|_| () /* When selected input kind changes */}/>
-                </article>
-                {result_display(input)}
-            </main>
-            <footer>{"Made by\u{00A0}"}<a href="https://github.com/ClayenKitten">{"ClayenKitten"}</a></footer>
-        </>
+        <article id="result">
+            {for verdicts}
+            {table}
+            {for charts}
+            {minimization}
+            {parse_tree}
+            {for simplifications}
+            {for circuits}
+            <ModelList key={func.to_string()} func={func.clone()} />
+            {exports(func, outputs)}
+        </article>
     }
 }
 
-fn result_display(formula: UseStateHandle<Option<Result<Function, ParseError>>>) -> Html {
-    let func = match formula.as_ref() {
-        Some(Ok(func)) => func,
-        Some(Err(err)) => return error(err),
-        None => return html!(),
+fn comparison_display(
+    lhs: UseStateHandle<Input>,
+    rhs: UseStateHandle<Input>,
+) -> Html {
+    let (lhs, rhs) = match (lhs.as_ref(), rhs.as_ref()) {
+        (Some(Err(err)), _) | (_, Some(Err(err))) => return error(err),
+        (Some(Ok(lhs)), Some(Ok(rhs))) => (lhs, rhs),
+        _ => return html!(),
     };
-    
-    let table = TruthTable(
-        func.vars(),
-        |vals| func.eval(vals).unwrap()
-    );
 
-    let chart = if func.vars().len() <= 3 {
-        let svg = svg_gen::generate(
-            func.vars(),
-            |vals| func.eval(vals).unwrap()
-        );
-        Some(Html::from_html_unchecked(svg.to_string().into()))
-    } else {
-        None
+    let comparison = lhs.compare(rhs);
+    let verdict = match comparison.counterexample() {
+        None => "Formulas are equivalent.".to_string(),
+        Some(values) => format!("Formulas differ at {}.", assignment(comparison.vars(), values)),
     };
+    let vars = comparison.vars();
+    let table = (vars.len() <= ENUMERATION_LIMIT).then(|| ComparisonTable(
+        vars,
+        |vals| lhs.eval_on(vars, vals).unwrap(),
+        |vals| rhs.eval_on(vars, vals).unwrap(),
+    ));
 
     html! {
         <article id="result">
+            <p class="verdict">{verdict}</p>
             {table}
-            {chart}
         </article>
     }
 }
 
+/// Lists formulas of the derivation, each with the verdict on its equality to the previous one.
+fn derivation_display(
+    chain: &str,
+    derivation: Option<&Result<Vec<Equality>, DerivationError>>,
+) -> Html {
+    let equalities = match derivation {
+        Some(Ok(equalities)) => equalities,
+        Some(Err(err)) => return error(InputError::from(*err)),
+        None => return html!(),
+    };
+
+    let wrong = equalities.iter().filter(|equality| matches!(equality.verdict, Verdict::Differs(_)));
+    let verdict = match wrong.count() {
+        0 => "Every step is valid.".to_string(),
+        1 => "One step is wrong.".to_string(),
+        count => format!("{count} steps are wrong."),
+    };
+    let steps = equalities.iter().map(|equality| {
+        let (class, note) = match &equality.verdict {
+            Verdict::Law(law) => (None, format!("by {law}")),
+            Verdict::Equivalent => (None, "equivalent, but not by a single law".to_string()),
+            Verdict::Differs(comparison) => {
+                let values = comparison.counterexample().unwrap_or_default();
+                (Some("wrong"), format!("differs at {}", assignment(comparison.vars(), values)))
+            }
+        };
+        html! {
+            <li {class}>
+                <code>{format!("= {}", &chain[equality.rhs.clone()])}</code>
+                <span class="law">{note}</span>
+            </li>
+        }
+    });
+
+    html! {
+        <article id="result">
+            <p class="verdict">{verdict}</p>
+            <section class="simplification">
+                <ol class="derivation">
+                    <li><code>{&chain[equalities[0].lhs.clone()]}</code></li>
+                    {for steps}
+                </ol>
+            </section>
+        </article>
+    }
+}
+
+/// Tells whether the function is constant, prefixed with the name of the output if there's one.
+fn satisfiability(name: Option<&String>, func: &Function) -> Html {
+    let verdict = match (func.satisfy(), func.falsify()) {
+        (None, _) => "Unsatisfiable: function is always false.".to_string(),
+        (Some(_), None) => "Tautology: function is always true.".to_string(),
+        (Some(model), Some(_)) => format!("Satisfiable, for example at {}.", assignment(func.vars(), &model)),
+    };
+    let verdict = match name {
+        Some(name) => format!("{name}: {verdict}"),
+        None => verdict,
+    };
+    html!(<p class="verdict">{verdict}</p>)
+}
+
+/// Buttons that save the function to a file, with every output where the format allows several.
+///
+/// DIMACS has a single output, and saves the first one, named when there are several.
+fn exports(func: &Function, outputs: &MultiFunction) -> Html {
+    let label = |format: &str| match outputs.outputs() {
+        [(first, _), _, ..] => format!("{format}: {first}"),
+        _ => format.to_string(),
+    };
+    let dimacs = |encoding| {
+        let func = func.clone();
+        Callback::from(move |_| {
+            let contents = func.to_dimacs(encoding);
+            files::download("function.cnf", "text/plain", contents.as_bytes());
+        })
+    };
+    let hdl = |hdl: Hdl| {
+        let outputs = outputs.clone();
+        Callback::from(move |_| {
+            let contents = outputs.to_hdl(hdl, HdlBody::Assignment);
+            let name = format!("boolean_function.{}", hdl.extension());
+            files::download(&name, "text/plain", contents.as_bytes());
+        })
+    };
+    let testbench = |hdl: Hdl| {
+        let outputs = outputs.clone();
+        Callback::from(move |_| {
+            let contents = outputs.hdl_testbench(hdl);
+            let name = format!("boolean_function_tb.{}", hdl.extension());
+            files::download(&name, "text/plain", contents.as_bytes());
+        })
+    };
+    let blif = {
+        let outputs = outputs.clone();
+        Callback::from(move |_| {
+            files::download("function.blif", "text/plain", outputs.to_blif().as_bytes());
+        })
+    };
+    let pla = {
+        let outputs = outputs.clone();
+        Callback::from(move |_| {
+            files::download("function.pla", "text/plain", outputs.to_pla().as_bytes());
+        })
+    };
+    let aiger = |format| {
+        let outputs = outputs.clone();
+        Callback::from(move |_| {
+            let (name, mime) = match format {
+                AigerFormat::Ascii => ("function.aag", "text/plain"),
+                AigerFormat::Binary => ("function.aig", "application/octet-stream"),
+            };
+            files::download(name, mime, &outputs.to_aiger(format));
+        })
+    };
+    let enumerable = outputs.inputs().len() <= ENUMERATION_LIMIT;
+    html! {
+        <menu class="exports">
+            <li><button onclick={dimacs(CnfEncoding::Tseitin)}>{label("DIMACS (Tseitin)")}</button></li>
+            if func.vars().len() <= ENUMERATION_LIMIT {
+                <li><button onclick={dimacs(CnfEncoding::Canonical)}>{label("DIMACS (canonical)")}</button></li>
+            }
+            <li><button onclick={blif}>{"BLIF"}</button></li>
+            if enumerable {
+                <li><button onclick={pla}>{"PLA"}</button></li>
+            }
+            <li><button onclick={aiger(AigerFormat::Ascii)}>{"AIGER (ASCII)"}</button></li>
+            <li><button onclick={aiger(AigerFormat::Binary)}>{"AIGER (binary)"}</button></li>
+            <li><button onclick={hdl(Hdl::Verilog)}>{"Verilog"}</button></li>
+            <li><button onclick={hdl(Hdl::Vhdl)}>{"VHDL"}</button></li>
+            if enumerable {
+                <li><button onclick={testbench(Hdl::Verilog)}>{"Verilog testbench"}</button></li>
+                <li><button onclick={testbench(Hdl::Vhdl)}>{"VHDL testbench"}</button></li>
+            }
+        </menu>
+    }
+}
+
+/// Formats values of variables as a list of equalities.
+fn assignment(vars: &[Variable], values: &[bool]) -> String {
+    vars.iter()
+        .zip(values)
+        .map(|(var, val)| format!("{var}\u{00A0}=\u{00A0}{}", *val as u8))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn error(msg: impl ToString) -> Html {
     html! {
         <article class="danger">
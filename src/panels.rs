@@ -0,0 +1,11 @@
+//! Interactive sections of the result.
+
+pub mod circuit;
+pub mod conversion;
+pub mod diagram;
+pub mod minimization;
+pub mod models;
+pub mod simplification;
+pub mod table;
+pub mod trace;
+pub mod tree;
@@ -0,0 +1,87 @@
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, use_memo, use_state, AttrValue, Callback, Html, Properties, TargetCast};
+
+use crate::{
+    files,
+    function::{Function, GateSet, Hdl, HdlBody},
+    panels::diagram::prefers_dark,
+    svg_gen::{self, Style, SCHEMATIC_LIMIT},
+};
+
+const GATE_SETS: [(&str, GateSet); 3] = [
+    ("AND, OR and NOT", GateSet::AndOrNot),
+    ("NAND only", GateSet::Nand),
+    ("NOR only", GateSet::Nor),
+];
+
+/// Function as a circuit built from a chosen set of gates, written as a formula and drawn as a schematic.
+#[function_component]
+pub fn CircuitPanel(props: &Props) -> Html {
+    let set = use_state(|| 0);
+    let circuit = use_memo(|(func, set)| func.to_circuit(GATE_SETS[*set].1), (props.func.clone(), *set));
+
+    let onset = {
+        let set = set.setter();
+        Callback::from(move |e: web_sys::Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            set.set(value.parse().unwrap_or_default());
+        })
+    };
+    let style = if prefers_dark() { Style::DARK } else { Style::LIGHT };
+    let drawing = (circuit.gate_count() <= SCHEMATIC_LIMIT).then(|| svg_gen::schematic(&circuit, &style));
+    let filename = match &props.name {
+        Some(name) => format!("circuit_{name}"),
+        None => "circuit".to_string(),
+    };
+    let download = drawing.clone().map(|document| Callback::from(move |_| {
+        let svg = svg_gen::to_svg(&document);
+        files::download(&format!("{filename}.svg"), "image/svg+xml", svg.as_bytes());
+    }));
+    let drawing = drawing.map(|document| {
+        let inline = Html::from_html_unchecked(AttrValue::from(document.to_string()));
+        html! { <div class="drawing">{inline}</div> }
+    });
+    let netlist = |hdl: Hdl| {
+        let (func, set) = (props.func.clone(), GATE_SETS[*set].1);
+        Callback::from(move |_| {
+            let contents = func.to_hdl(hdl, HdlBody::Netlist(set));
+            let name = format!("boolean_function.{}", hdl.extension());
+            files::download(&name, "text/plain", contents.as_bytes());
+        })
+    };
+    let count = match circuit.gate_count() {
+        1 => "1 gate".to_string(),
+        count => format!("{count} gates"),
+    };
+
+    html! {
+        <section class="circuit">
+            if let Some(name) = &props.name {
+                <h3>{name}</h3>
+            }
+            <p><code>{circuit.to_string()}</code></p>
+            {drawing}
+            <fieldset>
+                <select onchange={onset}>
+                    {for GATE_SETS.iter().enumerate().map(|(index, (name, _))| html! {
+                        <option value={index.to_string()} selected={*set == index}>{*name}</option>
+                    })}
+                </select>
+                <span class="gate-count">{count}</span>
+                if let Some(onclick) = download {
+                    <button {onclick}>{"Download SVG"}</button>
+                }
+                <button onclick={netlist(Hdl::Verilog)}>{"Verilog netlist"}</button>
+                <button onclick={netlist(Hdl::Vhdl)}>{"VHDL netlist"}</button>
+            </fieldset>
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub func: Function,
+    /// Name of the output the function is, shown above the circuit and in names of downloaded files.
+    #[prop_or_default]
+    pub name: Option<AttrValue>,
+}
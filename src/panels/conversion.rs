@@ -0,0 +1,43 @@
+use yew::{function_component, html, use_memo, Html, Properties};
+
+use crate::function::Function;
+
+/// Steps of the shunting-yard algorithm turning the formula into postfix notation.
+#[function_component]
+pub fn ConversionPanel(props: &Props) -> Html {
+    let steps = use_memo(
+        |formula| Function::conversion_steps(formula).unwrap_or_default(),
+        props.formula.clone(),
+    );
+
+    let rows = steps.iter().enumerate().map(|(index, step)| html! {
+        <tr>
+            <td class="minterm">{index + 1}</td>
+            <td><code>{step.input.clone().unwrap_or_else(|| "end".to_string())}</code></td>
+            <td>{step.action}</td>
+            <td><code>{&step.stack}</code></td>
+            <td><code>{step.output.join(" ")}</code></td>
+        </tr>
+    });
+
+    html! {
+        <section class="conversion">
+            <table>
+                <tr>
+                    <th>{"#"}</th>
+                    <th>{"Token"}</th>
+                    <th>{"Action"}</th>
+                    <th>{"Operator stack"}</th>
+                    <th>{"Output"}</th>
+                </tr>
+                {for rows}
+            </table>
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    /// Formula as it's written by the user.
+    pub formula: String,
+}
@@ -0,0 +1,207 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlSelectElement, MouseEvent};
+use yew::{function_component, html, use_memo, use_state, AttrValue, Callback, Html, Properties, TargetCast};
+
+use crate::{
+    files,
+    function::Function,
+    svg_gen::{self, Fill, RegionLabels, Style, VENN_LIMIT},
+};
+
+/// Fills that may replace the one of the chosen style.
+const FILLS: [(&str, Fill); 3] = [
+    ("Hatch", Fill::Hatch),
+    ("Solid", Fill::Solid),
+    ("Hatch per region", Fill::RegionHatches),
+];
+
+const LABELS: [(&str, RegionLabels); 3] = [
+    ("No labels", RegionLabels::None),
+    ("Row numbers", RegionLabels::Minterms),
+    ("Product terms", RegionLabels::Terms),
+];
+
+/// Widths of exported PNG images, in pixels.
+const PNG_WIDTHS: [u32; 4] = [512, 1024, 2048, 4096];
+
+/// Venn diagram or hypercube of the function, with a choice of style and buttons to download it.
+///
+/// Hovering a region or a vertex reports its truth table row, and the hovered row's one is highlighted in turn.
+#[function_component]
+pub fn DiagramPanel(props: &Props) -> Html {
+    let has_venn = props.func.vars().len() <= VENN_LIMIT;
+    let cube = use_state(|| !has_venn);
+    let width = use_state(|| PNG_WIDTHS[1]);
+    let labels = use_state(|| 0);
+    // Follows the site's color scheme unless a preset is chosen.
+    let preset = use_state(|| None::<usize>);
+    let fill = use_state(|| None::<usize>);
+
+    let mut style = match *preset {
+        Some(index) => Style::PRESETS[index].1,
+        None if prefers_dark() => Style::DARK,
+        None => Style::LIGHT,
+    };
+    if let Some(index) = *fill {
+        style.fill = FILLS[index].1;
+    }
+    // Hovering re-renders the panel, which shouldn't lay out the drawing again.
+    let drawing = use_memo(
+        |(func, dont_care, style, region_labels, is_cube)| {
+            let value = |vals: &[bool]| {
+                match dont_care.as_ref().is_some_and(|dont_care| dont_care.eval(vals).unwrap()) {
+                    true => None,
+                    false => func.eval(vals),
+                }
+            };
+            let document = match is_cube {
+                true => svg_gen::hypercube(func.vars(), style, value),
+                false => svg_gen::generate(func.vars(), style, *region_labels, value),
+            };
+            let markup = AttrValue::from(document.to_string());
+            (document, markup)
+        },
+        (props.func.clone(), props.dont_care.clone(), style, LABELS[*labels].1, *cube),
+    );
+    let inline = Html::from_html_unchecked(drawing.1.clone());
+    // Regions are drawn by the generated markup, so they're highlighted with a stylesheet rule.
+    let highlight = props.hovered.map(|minterm| html! {
+        <style>{format!(".drawing [data-minterm=\"{minterm}\"] {{ filter: invert(25%); }}")}</style>
+    });
+
+    let filename = match &props.name {
+        Some(name) => format!("diagram_{name}"),
+        None => "diagram".to_string(),
+    };
+    let download_svg = {
+        let filename = filename.clone();
+        let drawing = drawing.clone();
+        Callback::from(move |_| {
+            let svg = svg_gen::to_svg(&drawing.0);
+            files::download(&format!("{filename}.svg"), "image/svg+xml", svg.as_bytes());
+        })
+    };
+    let download_png = {
+        let width = *width;
+        Callback::from(move |_| {
+            let png = svg_gen::to_png(&drawing.0, width);
+            files::download(&format!("{filename}.png"), "image/png", &png);
+        })
+    };
+    let onmouseover = {
+        let onhover = props.onhover.clone();
+        Callback::from(move |e: MouseEvent| {
+            let minterm = e
+                .target()
+                .and_then(|target| target.dyn_into::<Element>().ok())
+                .and_then(|element| element.closest("[data-minterm]").ok().flatten())
+                .and_then(|region| region.get_attribute("data-minterm"))
+                .and_then(|minterm| minterm.parse().ok());
+            onhover.emit(minterm);
+        })
+    };
+    let onmouseleave = props.onhover.reform(|_| None);
+    let onkind = {
+        let cube = cube.setter();
+        Callback::from(move |e: web_sys::Event| {
+            cube.set(e.target_unchecked_into::<HtmlSelectElement>().value() == "cube");
+        })
+    };
+    let onlabels = {
+        let labels = labels.setter();
+        Callback::from(move |e: web_sys::Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            labels.set(value.parse().unwrap_or_default());
+        })
+    };
+    let onpreset = {
+        let preset = preset.setter();
+        Callback::from(move |e: web_sys::Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            preset.set(value.parse().ok());
+        })
+    };
+    let onfill = {
+        let fill = fill.setter();
+        Callback::from(move |e: web_sys::Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            fill.set(value.parse().ok());
+        })
+    };
+    let onwidth = {
+        let width = width.setter();
+        Callback::from(move |e: web_sys::Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            if let Ok(value) = value.parse() {
+                width.set(value);
+            }
+        })
+    };
+
+    html! {
+        <section class="diagram">
+            if let Some(name) = &props.name {
+                <h3>{name}</h3>
+            }
+            {highlight}
+            <div class="drawing" {onmouseover} {onmouseleave}>{inline}</div>
+            <fieldset>
+                if has_venn {
+                    <select onchange={onkind}>
+                        <option value="venn" selected={!*cube}>{"Venn diagram"}</option>
+                        <option value="cube" selected={*cube}>{"Hypercube"}</option>
+                    </select>
+                }
+                if !*cube {
+                    <select onchange={onlabels}>
+                        {for LABELS.iter().enumerate().map(|(index, (name, _))| html! {
+                            <option value={index.to_string()} selected={*labels == index}>{*name}</option>
+                        })}
+                    </select>
+                }
+                <select onchange={onpreset}>
+                    <option value="auto" selected={preset.is_none()}>{"Automatic"}</option>
+                    {for Style::PRESETS.iter().enumerate().map(|(index, (name, _))| html! {
+                        <option value={index.to_string()} selected={*preset == Some(index)}>{*name}</option>
+                    })}
+                </select>
+                <select onchange={onfill}>
+                    <option value="style" selected={fill.is_none()}>{"Fill of the style"}</option>
+                    {for FILLS.iter().enumerate().map(|(index, (name, _))| html! {
+                        <option value={index.to_string()} selected={*fill == Some(index)}>{*name}</option>
+                    })}
+                </select>
+                <button onclick={download_svg}>{"Download SVG"}</button>
+                <select onchange={onwidth}>
+                    {for PNG_WIDTHS.into_iter().map(|option| html! {
+                        <option value={option.to_string()} selected={option == *width}>
+                            {format!("{option} px")}
+                        </option>
+                    })}
+                </select>
+                <button onclick={download_png}>{"Download PNG"}</button>
+            </fieldset>
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub func: Function,
+    /// Function of the same variables that is true where `func` doesn't matter.
+    #[prop_or_default]
+    pub dont_care: Option<Function>,
+    /// Name of the output the function is, shown above the drawing and in names of downloaded files.
+    #[prop_or_default]
+    pub name: Option<AttrValue>,
+    /// Minterm index of the hovered truth table row or region.
+    pub hovered: Option<u64>,
+    pub onhover: Callback<Option<u64>>,
+}
+
+/// Checks if the user prefers dark color scheme, like the site's stylesheet does.
+pub fn prefers_dark() -> bool {
+    web_sys::window()
+        .and_then(|window| window.match_media("(prefers-color-scheme: dark)").ok().flatten())
+        .is_some_and(|query| query.matches())
+}
@@ -0,0 +1,70 @@
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, use_memo, use_state, Callback, Html, Properties, TargetCast};
+
+use crate::{files, function::MultiFunction};
+
+/// Minimal sum of products of every output, found either for each output on its own
+/// or for all of them at once, sharing product terms.
+#[function_component]
+pub fn MinimizationPanel(props: &Props) -> Html {
+    let joint = use_state(|| false);
+    let cover = use_memo(|(outputs, joint)| outputs.minimize(*joint), (props.outputs.clone(), *joint));
+
+    let onselect = {
+        let joint = joint.setter();
+        Callback::from(move |e: web_sys::Event| {
+            joint.set(e.target_unchecked_into::<HtmlSelectElement>().value() == "joint");
+        })
+    };
+    let download = {
+        let cover = cover.clone();
+        Callback::from(move |_| files::download("minimized.pla", "text/plain", cover.to_pla().as_bytes()))
+    };
+    let formulas = cover.names().iter().enumerate().map(|(index, name)| html! {
+        <li><code>{format!("{name} = {}", cover.function(index))}</code></li>
+    });
+    let terms = cover.terms().iter().map(|(cube, outputs)| html! {
+        <tr>
+            {for cube.values().iter().map(|val| html!(
+                <td>{match val {Some(true) => "1", Some(false) => "0", None => "-"}}</td>)
+            )}
+            {for outputs.iter().map(|used| html!(<td>{if *used {"1"} else {"0"}}</td>))}
+        </tr>
+    });
+    let count = match cover.terms().len() {
+        1 => "1 product term".to_string(),
+        count => format!("{count} product terms"),
+    };
+    let literals = match cover.literals() {
+        1 => "1 literal".to_string(),
+        literals => format!("{literals} literals"),
+    };
+
+    html! {
+        <section class="minimization">
+            <ul>{for formulas}</ul>
+            <table>
+                <tr>
+                    {for cover.inputs().iter().map(|var| html!(<th>{var.to_string()}</th>))}
+                    {for cover.names().iter().map(|name| html!(<th>{name}</th>))}
+                </tr>
+                {for terms}
+            </table>
+            <fieldset>
+                if props.outputs.outputs().len() > 1 {
+                    <select onchange={onselect}>
+                        <option value="separate" selected={!*joint}>{"Each output on its own"}</option>
+                        <option value="joint" selected={*joint}>{"All outputs, sharing products"}</option>
+                    </select>
+                }
+                <span class="term-count">{format!("{count}, {literals}")}</span>
+                <button onclick={download}>{"Download PLA"}</button>
+            </fieldset>
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub outputs: MultiFunction,
+}
@@ -0,0 +1,104 @@
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, use_memo, use_state, Callback, Html, Properties, TargetCast};
+
+use crate::function::{Function, Variable};
+
+/// Number of models shown at once.
+const PAGE_SIZE: usize = 16;
+
+/// Satisfying assignments of the function, paginated and optionally projected onto some variables.
+#[function_component]
+pub fn ModelList(props: &Props) -> Html {
+    let projection = use_state(|| props.func.vars().to_vec());
+    let page = use_state(|| 0usize);
+    let models = use_memo(
+        |(func, projection)| func.models_over(projection),
+        (props.func.clone(), (*projection).clone()),
+    );
+
+    let count = models.count();
+    let pages = count.div_ceil(PAGE_SIZE as u128).max(1);
+    let rows = models
+        .iter()
+        .skip(page.saturating_mul(PAGE_SIZE))
+        .take(PAGE_SIZE)
+        .map(|values| html! {
+            <tr>
+                {for values.iter().map(|val| html!(<td>{*val as u8}</td>))}
+            </tr>
+        })
+        .collect::<Html>();
+
+    let toggle = |var: &Variable| {
+        let var = var.clone();
+        let func = props.func.clone();
+        let projection = projection.clone();
+        let page = page.setter();
+        Callback::from(move |e: web_sys::Event| {
+            let checked = e.target_unchecked_into::<HtmlInputElement>().checked();
+            let selected = func
+                .vars()
+                .iter()
+                .filter(|v| if **v == var { checked } else { projection.contains(v) })
+                .cloned()
+                .collect();
+            projection.set(selected);
+            page.set(0);
+        })
+    };
+    let turn = |delta: isize| {
+        let page = page.clone();
+        Callback::from(move |_| {
+            page.set(page.saturating_add_signed(delta));
+        })
+    };
+
+    html! {
+        <section class="models">
+            <h4>{format!("{} of {} assignments satisfy", shown_count(count), total(models.vars().len()))}</h4>
+            <fieldset>
+                <legend>{"Project onto:"}</legend>
+                {for props.func.vars().iter().map(|var| html! {
+                    <label>
+                        <input
+                            type="checkbox"
+                            checked={projection.contains(var)}
+                            onchange={toggle(var)}
+                        />
+                        {var.to_string()}
+                    </label>
+                })}
+            </fieldset>
+            <table>
+                <tr>{for models.vars().iter().map(|var| html!(<th>{var.to_string()}</th>))}</tr>
+                {rows}
+            </table>
+            <nav>
+                <button disabled={*page == 0} onclick={turn(-1)}>{"Previous"}</button>
+                <span>{format!("Page {} of {pages}", *page + 1)}</span>
+                <button disabled={(*page + 1) as u128 >= pages} onclick={turn(1)}>{"Next"}</button>
+            </nav>
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub func: Function,
+}
+
+/// Model count as text, where a saturated count only gives a lower bound.
+fn shown_count(count: u128) -> String {
+    match count {
+        u128::MAX => "≥ 2^127".to_string(),
+        count => count.to_string(),
+    }
+}
+
+/// Number of assignments of that many variables as text.
+fn total(num_vars: usize) -> String {
+    match u32::try_from(num_vars).ok().and_then(|n| 1u128.checked_shl(n)) {
+        Some(total) => total.to_string(),
+        None => format!("2^{num_vars}"),
+    }
+}
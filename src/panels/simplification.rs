@@ -0,0 +1,35 @@
+use yew::{function_component, html, use_memo, AttrValue, Html, Properties};
+
+use crate::function::Function;
+
+/// Simplification of the formula as a chain of equalities, each justified by a law.
+#[function_component]
+pub fn SimplificationPanel(props: &Props) -> Html {
+    let steps = use_memo(|func| func.simplify(), props.func.clone());
+
+    let steps = steps.iter().map(|(law, expr)| html! {
+        <li>
+            <code>{format!("= {expr}")}</code>
+            <span class="law">{law.name()}</span>
+        </li>
+    });
+    html! {
+        <section class="simplification">
+            if let Some(name) = &props.name {
+                <h3>{name}</h3>
+            }
+            <ol class="derivation">
+                <li><code>{props.func.to_string()}</code></li>
+                {for steps}
+            </ol>
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub func: Function,
+    /// Name of the output the function is, shown above the steps.
+    #[prop_or_default]
+    pub name: Option<AttrValue>,
+}
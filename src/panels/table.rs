@@ -0,0 +1,307 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{function_component, html, use_memo, use_state, Callback, Html, Properties, TargetCast};
+
+use crate::{
+    files,
+    function::{Function, MultiFunction, ENUMERATION_LIMIT},
+    table_gen::{
+        cube_rows, find_minterm, table_rows, CubeTable, Line, RowOrder, TableFormat, TableRows,
+        TruthTable,
+    },
+};
+
+/// Number of lines shown at once.
+const PAGE_SIZE: usize = 32;
+
+/// Truth table of the function's outputs, paginated, with a choice of row order and filtering.
+///
+/// Rows are ordered and filtered by the first output, and don't-care rows are kept by neither filter.
+/// Cubes and exports are only offered for functions of few variables, whose tables can be listed in full.
+/// Values of the subexpression, if one is provided, are shown next to the outputs.
+#[function_component]
+pub fn TablePanel(props: &Props) -> Html {
+    let kind = use_state(|| "binary".to_string());
+    let filter = use_state(|| None);
+    let compact = use_state(|| false);
+    let format = use_state(|| TableFormat::Csv);
+    let significance = use_state(|| {
+        let names = props.outputs.inputs().iter().map(|var| var.as_str());
+        names.collect::<Vec<_>>().join(" ")
+    });
+    let collapse = use_state(|| false);
+    let start = use_state(|| 0u64);
+    let query = use_state(String::new);
+    let found = use_state(|| None);
+
+    let func = props.outputs.first();
+    let vars = props.outputs.inputs();
+    let names = props.outputs.outputs().iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+    let custom = RowOrder::significance(vars, &significance);
+    let order = match kind.as_str() {
+        "gray" => RowOrder::Gray,
+        "outputs" => RowOrder::OutputsFirst,
+        "custom" => custom.clone().unwrap_or_default(),
+        _ => RowOrder::Binary,
+    };
+    let enumerable = vars.len() <= ENUMERATION_LIMIT;
+    let rows = use_memo(
+        |(func, dont_care, order, filter)| TableRows::new(func, dont_care.as_ref(), order.clone(), *filter),
+        (func.clone(), props.outputs.dont_care().cloned(), order, *filter),
+    );
+    // There may be too many cubes to list for more variables.
+    let cubes = use_memo(
+        |(outputs, filter, enumerable)| match enumerable {
+            true => {
+                let cubes = outputs.first().cubes_with_dont_care(outputs.inputs(), outputs.dont_care());
+                let cubes = cubes.into_iter();
+                cubes.filter(|(_, output)| filter.is_none_or(|filter| Some(filter) == *output)).collect::<Vec<_>>()
+            }
+            false => Vec::new(),
+        },
+        (props.outputs.clone(), *filter, enumerable),
+    );
+
+    let (table, end, total) = match *compact {
+        true => {
+            let start = (*start as usize).min(cubes.len());
+            let end = (start + PAGE_SIZE).min(cubes.len());
+            let table = CubeTable(vars, &names[0], &cubes[start..end]);
+            (table, end as u64, cubes.len() as u64)
+        }
+        false => {
+            let lines = rows.lines(*start, PAGE_SIZE, *collapse);
+            let end = match lines.last() {
+                Some(Line::Row(pos)) => pos + 1,
+                Some(Line::Run { end, .. }) => *end,
+                None => *start,
+            };
+            let table = TruthTable(
+                vars,
+                props.outputs.outputs(),
+                &rows,
+                &lines,
+                found.flatten(),
+                props.hovered,
+                &props.onhover,
+                &props.ontoggle,
+                props.subexpression.as_ref(),
+            );
+            (table, end, rows.num_rows())
+        }
+    };
+
+    let onselect = {
+        let kind = kind.setter();
+        let start = start.setter();
+        Callback::from(move |e: web_sys::Event| {
+            kind.set(e.target_unchecked_into::<HtmlSelectElement>().value());
+            start.set(0);
+        })
+    };
+    let onreorder = {
+        let significance = significance.setter();
+        let start = start.setter();
+        Callback::from(move |e: web_sys::InputEvent| {
+            significance.set(e.target_unchecked_into::<HtmlInputElement>().value());
+            start.set(0);
+        })
+    };
+    let onfilter = {
+        let filter = filter.setter();
+        let start = start.setter();
+        Callback::from(move |e: web_sys::Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            filter.set(match value.as_str() {
+                "ones" => Some(true),
+                "zeros" => Some(false),
+                _ => None,
+            });
+            start.set(0);
+        })
+    };
+    let onview = {
+        let compact = compact.setter();
+        let start = start.setter();
+        Callback::from(move |e: web_sys::Event| {
+            compact.set(e.target_unchecked_into::<HtmlSelectElement>().value() == "cubes");
+            start.set(0);
+        })
+    };
+    let oncollapse = {
+        let collapse = collapse.setter();
+        let start = start.clone();
+        let rows = rows.clone();
+        Callback::from(move |e: web_sys::Event| {
+            let checked = e.target_unchecked_into::<HtmlInputElement>().checked();
+            collapse.set(checked);
+            start.set(rows.line_start(*start, checked));
+        })
+    };
+    let onquery = {
+        let query = query.setter();
+        Callback::from(move |e: web_sys::InputEvent| {
+            query.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let onfind = {
+        let func = func.clone();
+        let rows = rows.clone();
+        let query = query.clone();
+        let found = found.setter();
+        let start = start.setter();
+        let collapse = *collapse;
+        Callback::from(move |e: web_sys::SubmitEvent| {
+            e.prevent_default();
+            let minterm = find_minterm(func.vars(), &query);
+            let pos = minterm.and_then(|minterm| rows.position(minterm));
+            if let Some(pos) = pos {
+                start.set(match collapse {
+                    true => rows.line_start(pos, true),
+                    false => pos - pos % PAGE_SIZE as u64,
+                });
+            }
+            found.set(Some(minterm.filter(|_| pos.is_some())));
+        })
+    };
+    let onformat = {
+        let format = format.setter();
+        Callback::from(move |e: web_sys::Event| {
+            let name = e.target_unchecked_into::<HtmlSelectElement>().value();
+            if let Some(selected) = TableFormat::ALL.into_iter().find(|format| format.name() == name) {
+                format.set(selected);
+            }
+        })
+    };
+    // Exports follow the shown table, but include every page.
+    let export = {
+        let outputs = props.outputs.clone();
+        let rows = rows.clone();
+        let cubes = cubes.clone();
+        let compact = *compact;
+        let format = *format;
+        let names = names.clone();
+        move || match compact {
+            true => format.write(outputs.inputs(), &names[..1], cube_rows(&cubes)),
+            false => format.write(outputs.inputs(), &names, table_rows(&rows, &outputs.outputs()[1..])),
+        }
+    };
+    let download = {
+        let export = export.clone();
+        let format = *format;
+        Callback::from(move |_| {
+            let filename = format!("table.{}", format.extension());
+            files::download(&filename, format.mime(), export().as_bytes());
+        })
+    };
+    let copy = Callback::from(move |_| files::copy(&export()));
+    let previous = {
+        let start = start.clone();
+        let rows = rows.clone();
+        let collapse = *collapse;
+        let compact = *compact;
+        Callback::from(move |_| match compact {
+            true => start.set(start.saturating_sub(PAGE_SIZE as u64)),
+            false => start.set(rows.lines_before(*start, PAGE_SIZE, collapse)),
+        })
+    };
+    let next = {
+        let start = start.setter();
+        Callback::from(move |_| start.set(end))
+    };
+
+    html! {
+        <section class="truth-table">
+            <fieldset>
+                if enumerable {
+                    <label>
+                        {"Show"}
+                        <select onchange={onview}>
+                            <option value="rows" selected={!*compact}>{"Every row"}</option>
+                            <option value="cubes" selected={*compact}>{"Cubes"}</option>
+                        </select>
+                    </label>
+                }
+                <label>
+                    {"Filter"}
+                    <select onchange={onfilter}>
+                        <option value="all" selected={filter.is_none()}>{"All rows"}</option>
+                        <option value="ones" selected={*filter == Some(true)}>{format!("{} = 1", names[0])}</option>
+                        <option value="zeros" selected={*filter == Some(false)}>{format!("{} = 0", names[0])}</option>
+                    </select>
+                </label>
+                if !*compact {
+                    <label>
+                        {"Row order"}
+                        <select onchange={onselect}>
+                            <option value="binary" selected={*kind == "binary"}>{"Binary"}</option>
+                            <option value="gray" selected={*kind == "gray"}>{"Gray code"}</option>
+                            <option value="outputs" selected={*kind == "outputs"}>{"True rows first"}</option>
+                            <option value="custom" selected={*kind == "custom"}>{"Custom significance"}</option>
+                        </select>
+                    </label>
+                    if *kind == "custom" {
+                        <label>
+                            {"Variables, most significant first"}
+                            <input
+                                type="text"
+                                value={(*significance).clone()}
+                                oninput={onreorder}
+                                aria-invalid={custom.is_none().then_some("true")}
+                            />
+                        </label>
+                    }
+                    <label>
+                        <input type="checkbox" checked={*collapse} onchange={oncollapse}/>
+                        {"Collapse runs of equal outputs"}
+                    </label>
+                }
+            </fieldset>
+            if !*compact {
+                <form onsubmit={onfind}>
+                    <input
+                        type="search"
+                        placeholder="Row number or assignment, like a = 1, b = 0"
+                        value={(*query).clone()}
+                        oninput={onquery}
+                        aria-invalid={matches!(*found, Some(None)).then_some("true")}
+                    />
+                    <button type="submit">{"Find"}</button>
+                </form>
+            }
+            {table}
+            <nav>
+                <button disabled={*start == 0} onclick={previous}>{"Previous"}</button>
+                <span>{format!("{} to {end} of {total}", (*start + 1).min(end))}</span>
+                <button disabled={end >= total} onclick={next}>{"Next"}</button>
+            </nav>
+            if enumerable {
+                <fieldset class="table-export">
+                    <label>
+                        {"Export as"}
+                        <select onchange={onformat}>
+                            {for TableFormat::ALL.into_iter().map(|option| html! {
+                                <option value={option.name()} selected={option == *format}>{option.name()}</option>
+                            })}
+                        </select>
+                    </label>
+                    <button onclick={download}>{"Download"}</button>
+                    <button onclick={copy}>{"Copy"}</button>
+                </fieldset>
+            }
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub outputs: MultiFunction,
+    /// Minterm index of the hovered truth table row or diagram region.
+    pub hovered: Option<u64>,
+    pub onhover: Callback<Option<u64>>,
+    /// Called with the minterm index of a row whose outputs are clicked, to make it a don't-care or not.
+    #[prop_or_default]
+    pub ontoggle: Callback<u64>,
+    /// Part of the formula whose values are shown in a separate column.
+    #[prop_or_default]
+    pub subexpression: Option<Function>,
+}
@@ -0,0 +1,89 @@
+use web_sys::HtmlInputElement;
+use yew::{classes, function_component, html, use_state, Callback, Html, Properties, TargetCast};
+
+use crate::{function::ParseTree, table_gen::find_assignment};
+
+/// Evaluation of the formula on a chosen row, one postfix token at a time.
+///
+/// Every step shows the stack, with each value labeled by the subexpression it's the value of,
+/// and reports the node of the token as hovered.
+#[function_component]
+pub fn TracePanel(props: &Props) -> Html {
+    let query = use_state(|| "0".to_string());
+    let step = use_state(|| 0usize);
+
+    let func = props.tree.function();
+    let steps = find_assignment(func.vars(), &query).map(|values| func.trace(&values).unwrap());
+
+    let onquery = {
+        let query = query.setter();
+        let step = step.setter();
+        Callback::from(move |e: web_sys::InputEvent| {
+            query.set(e.target_unchecked_into::<HtmlInputElement>().value());
+            step.set(0);
+        })
+    };
+    let last = steps.as_ref().map_or(0, |steps| steps.len() - 1);
+    let go = |target: usize| {
+        let step = step.setter();
+        let onnode = props.onnode.clone();
+        Callback::from(move |_| {
+            step.set(target);
+            onnode.emit(Some(target));
+        })
+    };
+
+    let Some(steps) = steps else {
+        return html! {
+            <section class="trace">
+                <input type="search" value={(*query).clone()} oninput={onquery} aria-invalid="true"/>
+            </section>
+        };
+    };
+    let current = &steps[(*step).min(last)];
+    let tokens = steps.iter().map(|token| {
+        let class = classes!(
+            (token.index == current.index).then_some("current"),
+            (token.index < current.index).then_some("done"),
+        );
+        html!(<li {class}>{&token.token}</li>)
+    });
+    let stack = current.stack.iter().rev().map(|(index, value)| html! {
+        <tr>
+            <td><code>{props.tree.subexpression(*index).to_string()}</code></td>
+            <td>{*value as u8}</td>
+        </tr>
+    });
+
+    html! {
+        <section class="trace">
+            <input
+                type="search"
+                placeholder="Row number or assignment, like a = 1, b = 0"
+                value={(*query).clone()}
+                oninput={onquery}
+            />
+            <ol class="postfix">{for tokens}</ol>
+            <nav>
+                <button disabled={current.index == 0} onclick={go(0)}>{"First"}</button>
+                <button disabled={current.index == 0} onclick={go(current.index.saturating_sub(1))}>
+                    {"Back"}
+                </button>
+                <span>{format!("Step {} of {}", current.index + 1, steps.len())}</span>
+                <button disabled={current.index == last} onclick={go(current.index + 1)}>{"Forward"}</button>
+                <button disabled={current.index == last} onclick={go(last)}>{"Last"}</button>
+            </nav>
+            <table>
+                <tr><th>{"Stack, top first"}</th><th>{"Value"}</th></tr>
+                {for stack}
+            </table>
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub tree: ParseTree,
+    /// Called with the node of the current token whenever a step is taken.
+    pub onnode: Callback<Option<usize>>,
+}
@@ -0,0 +1,52 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Element, MouseEvent};
+use yew::{function_component, html, AttrValue, Callback, Html, Properties};
+
+use crate::{
+    function::ParseTree,
+    svg_gen::{self, Style},
+};
+
+use super::diagram::prefers_dark;
+
+/// Parse tree of the formula, showing how operator precedence was applied.
+///
+/// Hovering a node reports its index, and the hovered node is highlighted.
+#[function_component]
+pub fn TreePanel(props: &Props) -> Html {
+    let style = if prefers_dark() { Style::DARK } else { Style::LIGHT };
+    let document = svg_gen::parse_tree(&props.tree, &style);
+    let inline = Html::from_html_unchecked(AttrValue::from(document.to_string()));
+    let highlight = props.hovered.map(|index| html! {
+        <style>{format!(".drawing [data-node=\"{index}\"] > rect {{ filter: invert(25%); }}")}</style>
+    });
+
+    let onmouseover = {
+        let onhover = props.onhover.clone();
+        Callback::from(move |e: MouseEvent| {
+            let index = e
+                .target()
+                .and_then(|target| target.dyn_into::<Element>().ok())
+                .and_then(|element| element.closest("[data-node]").ok().flatten())
+                .and_then(|node| node.get_attribute("data-node"))
+                .and_then(|index| index.parse().ok());
+            onhover.emit(index);
+        })
+    };
+    let onmouseleave = props.onhover.reform(|_| None);
+
+    html! {
+        <section class="parse-tree">
+            {highlight}
+            <div class="drawing" {onmouseover} {onmouseleave}>{inline}</div>
+        </section>
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    pub tree: ParseTree,
+    /// Index of the hovered node.
+    pub hovered: Option<usize>,
+    pub onhover: Callback<Option<usize>>,
+}
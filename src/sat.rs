@@ -0,0 +1,9 @@
+//! Satisfiability checking of formulas that are too large to enumerate.
+
+mod cnf;
+mod dimacs;
+mod solver;
+
+pub use cnf::{Cnf, Lit, Var};
+pub use dimacs::DimacsError;
+pub use solver::{Outcome, Solver};
@@ -0,0 +1,84 @@
+use std::ops::Not;
+
+/// A propositional variable, numbered from zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Var(pub u32);
+
+impl Var {
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub const fn positive(self) -> Lit {
+        Lit(self.0 << 1)
+    }
+}
+
+/// A variable or its negation.
+///
+/// Literals of the same variable are stored next to each other, so `code` may be used as an index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lit(u32);
+
+impl Lit {
+    pub const fn new(var: Var, negative: bool) -> Self {
+        Lit(var.0 << 1 | negative as u32)
+    }
+
+    pub const fn var(self) -> Var {
+        Var(self.0 >> 1)
+    }
+
+    pub const fn is_negative(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    pub const fn code(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Self::Output {
+        Lit(self.0 ^ 1)
+    }
+}
+
+/// A formula in conjunctive normal form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cnf {
+    num_vars: u32,
+    clauses: Vec<Vec<Lit>>,
+}
+
+impl Cnf {
+    /// Creates formula with `num_vars` variables and no clauses.
+    pub fn with_vars(num_vars: u32) -> Self {
+        Self { num_vars, clauses: Vec::new() }
+    }
+
+    pub fn num_vars(&self) -> u32 {
+        self.num_vars
+    }
+
+    pub fn clauses(&self) -> &[Vec<Lit>] {
+        self.clauses.as_slice()
+    }
+
+    /// Allocates a fresh variable.
+    pub fn new_var(&mut self) -> Var {
+        self.num_vars += 1;
+        Var(self.num_vars - 1)
+    }
+
+    /// Adds a clause, allocating any variables it mentions.
+    pub fn add_clause(&mut self, clause: impl Into<Vec<Lit>>) {
+        let clause = clause.into();
+        if let Some(max) = clause.iter().map(|lit| lit.var().0).max() {
+            self.num_vars = self.num_vars.max(max + 1);
+        }
+        self.clauses.push(clause);
+    }
+}
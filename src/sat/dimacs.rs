@@ -0,0 +1,182 @@
+use std::fmt::{Display, Write};
+
+use thiserror::Error;
+
+use super::{Cnf, Lit, Var};
+
+impl Cnf {
+    /// Writes formula in DIMACS CNF format, starting with provided comment lines.
+    pub fn to_dimacs(&self, comments: &[String]) -> String {
+        let mut output = String::new();
+        for comment in comments {
+            writeln!(output, "c {comment}").unwrap();
+        }
+        writeln!(output, "p cnf {} {}", self.num_vars(), self.clauses().len()).unwrap();
+        for clause in self.clauses() {
+            for lit in clause {
+                let number = lit.var().0 as i64 + 1;
+                write!(output, "{} ", if lit.is_negative() { -number } else { number }).unwrap();
+            }
+            output.push_str("0\n");
+        }
+        output
+    }
+
+    /// Reads formula in DIMACS CNF format.
+    pub fn from_dimacs(s: &str) -> Result<Self, DimacsError> {
+        use DimacsErrorKind::*;
+
+        let mut header = None;
+        let mut cnf = Cnf::default();
+        let mut clause = Vec::new();
+        let mut last_line = 0;
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            last_line = line_number;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            }
+            // Some benchmark sets end files with `%` and garbage after it.
+            if line.starts_with('%') {
+                break;
+            }
+            if line.starts_with('p') {
+                if header.is_some() {
+                    return Err(DuplicateHeader.at(line_number));
+                }
+                let fields = line.split_whitespace().collect::<Vec<_>>();
+                let (vars, clauses) = match fields.as_slice() {
+                    ["p", "cnf", vars, clauses] => (vars.parse(), clauses.parse()),
+                    _ => return Err(InvalidHeader.at(line_number)),
+                };
+                let (Ok(vars), Ok(clauses)) = (vars, clauses) else {
+                    return Err(InvalidHeader.at(line_number));
+                };
+                cnf = Cnf::with_vars(vars);
+                header = Some((vars, clauses));
+                continue;
+            }
+            let Some((vars, _)) = header else {
+                return Err(MissingHeader.at(line_number));
+            };
+            for word in line.split_whitespace() {
+                let number = word
+                    .parse::<i64>()
+                    .map_err(|_| InvalidLiteral(word.to_string()).at(line_number))?;
+                if number == 0 {
+                    cnf.add_clause(std::mem::take(&mut clause));
+                    continue;
+                }
+                if number.unsigned_abs() > vars as u64 {
+                    return Err(VariableOutOfRange(number).at(line_number));
+                }
+                let var = Var(number.unsigned_abs() as u32 - 1);
+                clause.push(Lit::new(var, number < 0));
+            }
+        }
+        let Some((_, clauses)) = header else {
+            return Err(MissingHeader.at(last_line.max(1)));
+        };
+        // Last clause is often left without terminating zero.
+        if !clause.is_empty() {
+            cnf.add_clause(clause);
+        }
+        if cnf.clauses().len() != clauses {
+            return Err(ClauseCount { expected: clauses, found: cnf.clauses().len() }.at(last_line));
+        }
+        Ok(cnf)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct DimacsError {
+    line: usize,
+    kind: DimacsErrorKind,
+}
+
+impl Display for DimacsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DimacsErrorKind {
+    #[error("expected problem line `p cnf <variables> <clauses>` before clauses")]
+    MissingHeader,
+    #[error("problem line should be `p cnf <variables> <clauses>`")]
+    InvalidHeader,
+    #[error("problem line occurs more than once")]
+    DuplicateHeader,
+    #[error("`{0}` is not a literal")]
+    InvalidLiteral(String),
+    #[error("literal `{0}` refers to a variable that isn't declared")]
+    VariableOutOfRange(i64),
+    #[error("expected {expected} clauses, found {found}")]
+    ClauseCount { expected: usize, found: usize },
+}
+
+impl DimacsErrorKind {
+    pub fn at(self, line: usize) -> DimacsError {
+        DimacsError { line, kind: self }
+    }
+}
+
+#[cfg(test)]
+mod dimacs_tests {
+    use super::DimacsErrorKind;
+    use crate::sat::{Cnf, Lit, Var};
+
+    #[test]
+    fn dimacs_write() {
+        let mut cnf = Cnf::with_vars(3);
+        cnf.add_clause([Lit::new(Var(0), false), Lit::new(Var(2), true)]);
+        cnf.add_clause([Lit::new(Var(1), false)]);
+        let expected = "c hello\np cnf 3 2\n1 -3 0\n2 0\n";
+        assert_eq!(expected, cnf.to_dimacs(&["hello".to_string()]));
+    }
+
+    #[test]
+    fn dimacs_read() {
+        let input = "c comment\np cnf 4 3\n1 -3 0 2\n0\n-4 1\n";
+        let cnf = Cnf::from_dimacs(input).unwrap();
+        assert_eq!(4, cnf.num_vars());
+        assert_eq!(
+            &[
+                vec![Lit::new(Var(0), false), Lit::new(Var(2), true)],
+                vec![Lit::new(Var(1), false)],
+                vec![Lit::new(Var(3), true), Lit::new(Var(0), false)],
+            ],
+            cnf.clauses()
+        );
+    }
+
+    #[test]
+    fn dimacs_roundtrip() {
+        let input = "p cnf 5 2\n1 -5 0\n-2 3 4 0\n";
+        assert_eq!(input, Cnf::from_dimacs(input).unwrap().to_dimacs(&[]));
+    }
+
+    #[test]
+    fn dimacs_errors() {
+        use DimacsErrorKind::*;
+
+        assert_eq!(Err(MissingHeader.at(1)), Cnf::from_dimacs("1 2 0\n"));
+        assert_eq!(Err(MissingHeader.at(1)), Cnf::from_dimacs(""));
+        assert_eq!(Err(InvalidHeader.at(2)), Cnf::from_dimacs("c\np cnf x 1\n"));
+        assert_eq!(Err(DuplicateHeader.at(2)), Cnf::from_dimacs("p cnf 1 1\np cnf 1 1\n"));
+        assert_eq!(
+            Err(InvalidLiteral("a".to_string()).at(2)),
+            Cnf::from_dimacs("p cnf 1 1\n1 a 0\n")
+        );
+        assert_eq!(
+            Err(VariableOutOfRange(-3).at(3)),
+            Cnf::from_dimacs("p cnf 2 2\n1 2 0\n-3 0\n")
+        );
+        assert_eq!(
+            Err(ClauseCount { expected: 2, found: 1 }.at(2)),
+            Cnf::from_dimacs("p cnf 2 2\n1 2 0\n")
+        );
+    }
+}
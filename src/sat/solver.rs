@@ -0,0 +1,513 @@
+use super::{Cnf, Lit, Var};
+
+/// Result of solving a formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Formula is satisfiable; contains value of every variable.
+    Sat(Vec<bool>),
+    Unsat,
+}
+
+/// A conflict-driven clause learning SAT solver.
+///
+/// Uses two watched literals for propagation, first-UIP clause learning with non-chronological
+/// backjumping, VSIDS branching heuristic with phase saving, and Luby restarts.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    /// Clauses watching each literal, indexed by [`Lit::code`].
+    watches: Vec<Vec<usize>>,
+    assigns: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    /// Start of each decision level in the trail.
+    trail_lim: Vec<usize>,
+    /// Position in the trail up to which literals were propagated.
+    queue_head: usize,
+    activity: Vec<f64>,
+    activity_inc: f64,
+    /// Last value assigned to each variable, used as a decision polarity.
+    phase: Vec<bool>,
+    heap: VarHeap,
+    /// Set if a conflict was found without any decisions made.
+    unsat: bool,
+}
+
+const ACTIVITY_DECAY: f64 = 0.95;
+const RESTART_BASE: u64 = 100;
+
+impl Solver {
+    pub fn new(cnf: &Cnf) -> Self {
+        let num_vars = cnf.num_vars() as usize;
+        let mut solver = Self {
+            clauses: Vec::with_capacity(cnf.clauses().len()),
+            watches: vec![Vec::new(); num_vars * 2],
+            assigns: vec![None; num_vars],
+            level: vec![0; num_vars],
+            reason: vec![None; num_vars],
+            trail: Vec::with_capacity(num_vars),
+            trail_lim: Vec::new(),
+            queue_head: 0,
+            activity: vec![0.; num_vars],
+            activity_inc: 1.,
+            phase: vec![false; num_vars],
+            heap: VarHeap::new(num_vars),
+            unsat: false,
+        };
+        for clause in cnf.clauses() {
+            solver.add_clause(clause);
+        }
+        solver
+    }
+
+    /// Adds an original clause at decision level zero.
+    fn add_clause(&mut self, clause: &[Lit]) {
+        if self.unsat {
+            return;
+        }
+        let mut clause = clause.to_vec();
+        clause.sort_unstable();
+        clause.dedup();
+        // Tautological clauses and clauses that are already satisfied are useless.
+        if clause.windows(2).any(|pair| pair[0] == !pair[1])
+            || clause.iter().any(|&lit| self.value(lit) == Some(true))
+        {
+            return;
+        }
+        clause.retain(|&lit| self.value(lit) != Some(false));
+        match clause.len() {
+            0 => self.unsat = true,
+            1 => {
+                self.enqueue(clause[0], None);
+                self.unsat = self.propagate().is_some();
+            }
+            _ => {
+                self.attach(clause);
+            }
+        }
+    }
+
+    /// Stores a clause of at least two literals, watching the first two.
+    fn attach(&mut self, clause: Vec<Lit>) -> usize {
+        let index = self.clauses.len();
+        self.watches[clause[0].code()].push(index);
+        self.watches[clause[1].code()].push(index);
+        self.clauses.push(clause);
+        index
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assigns[lit.var().index()].map(|val| val ^ lit.is_negative())
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var().index();
+        self.assigns[var] = Some(!lit.is_negative());
+        self.level[var] = self.decision_level();
+        self.reason[var] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Propagates all enqueued literals, returning a conflicting clause if there is one.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.queue_head < self.trail.len() {
+            let false_lit = !self.trail[self.queue_head];
+            self.queue_head += 1;
+
+            let mut watchers = std::mem::take(&mut self.watches[false_lit.code()]);
+            let mut kept = 0;
+            let mut conflict = None;
+            let mut i = 0;
+            while i < watchers.len() {
+                let index = watchers[i];
+                i += 1;
+                // Keep the false literal in the second position.
+                if self.clauses[index][0] == false_lit {
+                    self.clauses[index].swap(0, 1);
+                }
+                let first = self.clauses[index][0];
+                if self.value(first) == Some(true) {
+                    watchers[kept] = index;
+                    kept += 1;
+                    continue;
+                }
+                let replacement = (2..self.clauses[index].len())
+                    .find(|&k| self.value(self.clauses[index][k]) != Some(false));
+                if let Some(k) = replacement {
+                    self.clauses[index].swap(1, k);
+                    self.watches[self.clauses[index][1].code()].push(index);
+                    continue;
+                }
+                watchers[kept] = index;
+                kept += 1;
+                if self.value(first) == Some(false) {
+                    conflict = Some(index);
+                    while i < watchers.len() {
+                        watchers[kept] = watchers[i];
+                        kept += 1;
+                        i += 1;
+                    }
+                } else {
+                    self.enqueue(first, Some(index));
+                }
+            }
+            watchers.truncate(kept);
+            self.watches[false_lit.code()] = watchers;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Derives a first-UIP clause from the conflict, returning it with the level to backjump to.
+    ///
+    /// Asserting literal of the learnt clause is the first one,
+    /// and the literal with the highest level among the rest is the second.
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let mut learnt = vec![Lit::new(Var(0), false)];
+        let mut seen = vec![false; self.assigns.len()];
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        let mut clause = conflict;
+        let mut implied = None;
+        loop {
+            let skip = usize::from(implied.is_some());
+            for k in skip..self.clauses[clause].len() {
+                let lit = self.clauses[clause][k];
+                let var = lit.var().index();
+                if seen[var] || self.level[var] == 0 {
+                    continue;
+                }
+                seen[var] = true;
+                self.bump(lit.var());
+                if self.level[var] == self.decision_level() {
+                    pending += 1;
+                } else {
+                    learnt.push(lit);
+                }
+            }
+            let lit = loop {
+                index -= 1;
+                if seen[self.trail[index].var().index()] {
+                    break self.trail[index];
+                }
+            };
+            seen[lit.var().index()] = false;
+            pending -= 1;
+            if pending == 0 {
+                learnt[0] = !lit;
+                break;
+            }
+            implied = Some(lit);
+            clause = self.reason[lit.var().index()].expect("implied literal should have a reason");
+        }
+
+        let backjump = match learnt.len() {
+            1 => 0,
+            _ => {
+                let max = (1..learnt.len())
+                    .max_by_key(|&k| self.level[learnt[k].var().index()])
+                    .unwrap();
+                learnt.swap(1, max);
+                self.level[learnt[1].var().index()]
+            }
+        };
+        (learnt, backjump)
+    }
+
+    /// Undoes all assignments above provided decision level.
+    fn backtrack(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+        for lit in self.trail.drain(self.trail_lim[level]..) {
+            let var = lit.var();
+            self.assigns[var.index()] = None;
+            self.reason[var.index()] = None;
+            self.phase[var.index()] = !lit.is_negative();
+            self.heap.insert(var, &self.activity);
+        }
+        self.trail_lim.truncate(level);
+        self.queue_head = self.trail.len();
+    }
+
+    fn bump(&mut self, var: Var) {
+        self.activity[var.index()] += self.activity_inc;
+        if self.activity[var.index()] > 1e100 {
+            self.activity.iter_mut().for_each(|activity| *activity *= 1e-100);
+            self.activity_inc *= 1e-100;
+        }
+        self.heap.increased(var, &self.activity);
+    }
+
+    /// Picks the most active unassigned variable.
+    fn pick_branch(&mut self) -> Option<Lit> {
+        while let Some(var) = self.heap.pop(&self.activity) {
+            if self.assigns[var.index()].is_none() {
+                return Some(Lit::new(var, !self.phase[var.index()]));
+            }
+        }
+        None
+    }
+
+    pub fn solve(&mut self) -> Outcome {
+        if self.unsat {
+            return Outcome::Unsat;
+        }
+        let mut conflicts = 0u64;
+        let mut restart = 1u32;
+        let mut limit = luby(restart) * RESTART_BASE;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.decision_level() == 0 {
+                    self.unsat = true;
+                    return Outcome::Unsat;
+                }
+                conflicts += 1;
+                let (learnt, backjump) = self.analyze(conflict);
+                self.backtrack(backjump);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let index = self.attach(learnt);
+                    self.enqueue(asserting, Some(index));
+                }
+                self.activity_inc /= ACTIVITY_DECAY;
+            } else if conflicts >= limit {
+                conflicts = 0;
+                restart += 1;
+                limit = luby(restart) * RESTART_BASE;
+                self.backtrack(0);
+            } else {
+                match self.pick_branch() {
+                    Some(lit) => {
+                        self.trail_lim.push(self.trail.len());
+                        self.enqueue(lit, None);
+                    }
+                    None => {
+                        let model = self.assigns.iter().map(|val| val.unwrap_or(false)).collect();
+                        self.backtrack(0);
+                        return Outcome::Sat(model);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Computes `i`-th element of Luby sequence: 1, 1, 2, 1, 1, 2, 4, 1, ...
+fn luby(mut i: u32) -> u64 {
+    loop {
+        let k = 32 - i.leading_zeros();
+        if i == (1 << k) - 1 {
+            return 1 << (k - 1);
+        }
+        i -= (1 << (k - 1)) - 1;
+    }
+}
+
+/// Binary max-heap of variables ordered by activity.
+#[derive(Debug, Clone)]
+struct VarHeap {
+    heap: Vec<Var>,
+    /// Position of each variable in the heap.
+    positions: Vec<Option<usize>>,
+}
+
+impl VarHeap {
+    fn new(num_vars: usize) -> Self {
+        Self {
+            heap: (0..num_vars as u32).map(Var).collect(),
+            positions: (0..num_vars).map(Some).collect(),
+        }
+    }
+
+    fn insert(&mut self, var: Var, activity: &[f64]) {
+        if self.positions[var.index()].is_some() {
+            return;
+        }
+        self.heap.push(var);
+        self.positions[var.index()] = Some(self.heap.len() - 1);
+        self.sift_up(self.heap.len() - 1, activity);
+    }
+
+    fn increased(&mut self, var: Var, activity: &[f64]) {
+        if let Some(pos) = self.positions[var.index()] {
+            self.sift_up(pos, activity);
+        }
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<Var> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop().unwrap();
+        self.positions[top.index()] = None;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last.index()] = Some(0);
+            self.sift_down(0, activity);
+        }
+        Some(top)
+    }
+
+    fn sift_up(&mut self, mut pos: usize, activity: &[f64]) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if activity[self.heap[parent].index()] >= activity[self.heap[pos].index()] {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut pos: usize, activity: &[f64]) {
+        loop {
+            let mut largest = pos;
+            for child in [2 * pos + 1, 2 * pos + 2] {
+                if child < self.heap.len()
+                    && activity[self.heap[child].index()] > activity[self.heap[largest].index()]
+                {
+                    largest = child;
+                }
+            }
+            if largest == pos {
+                break;
+            }
+            self.swap(pos, largest);
+            pos = largest;
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a].index()] = Some(a);
+        self.positions[self.heap[b].index()] = Some(b);
+    }
+}
+
+#[cfg(test)]
+mod solver_tests {
+    use super::{luby, Outcome, Solver};
+    use crate::sat::{Cnf, Lit, Var};
+
+    fn lit(n: i32) -> Lit {
+        Lit::new(Var(n.unsigned_abs() - 1), n < 0)
+    }
+
+    fn cnf(clauses: &[&[i32]]) -> Cnf {
+        let mut cnf = Cnf::default();
+        for clause in clauses {
+            cnf.add_clause(clause.iter().map(|&n| lit(n)).collect::<Vec<_>>());
+        }
+        cnf
+    }
+
+    fn satisfies(cnf: &Cnf, model: &[bool]) -> bool {
+        cnf.clauses().iter().all(|clause| {
+            clause.iter().any(|lit| model[lit.var().index()] != lit.is_negative())
+        })
+    }
+
+    #[test]
+    fn luby_sequence() {
+        let seq = (1..=15).map(luby).collect::<Vec<_>>();
+        assert_eq!(vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8], seq);
+    }
+
+    #[test]
+    fn solve_empty() {
+        assert_eq!(Outcome::Sat(vec![]), Solver::new(&Cnf::default()).solve());
+        assert_eq!(Outcome::Unsat, Solver::new(&cnf(&[&[]])).solve());
+    }
+
+    #[test]
+    fn solve_units() {
+        let formula = cnf(&[&[1], &[-2], &[-1, 2, 3]]);
+        assert_eq!(Outcome::Sat(vec![true, false, true]), Solver::new(&formula).solve());
+
+        let formula = cnf(&[&[1], &[-1]]);
+        assert_eq!(Outcome::Unsat, Solver::new(&formula).solve());
+    }
+
+    #[test]
+    fn solve_sat() {
+        let formula = cnf(&[&[1, 2], &[-1, 3], &[-2, -3], &[2, 3, -4], &[4, 1]]);
+        match Solver::new(&formula).solve() {
+            Outcome::Sat(model) => assert!(satisfies(&formula, &model)),
+            Outcome::Unsat => panic!("formula is satisfiable"),
+        }
+    }
+
+    #[test]
+    fn solve_unsat() {
+        // Every assignment of three variables is forbidden by one of the clauses.
+        let formula = cnf(&[
+            &[1, 2, 3], &[1, 2, -3], &[1, -2, 3], &[1, -2, -3],
+            &[-1, 2, 3], &[-1, 2, -3], &[-1, -2, 3], &[-1, -2, -3],
+        ]);
+        assert_eq!(Outcome::Unsat, Solver::new(&formula).solve());
+    }
+
+    /// Encodes placing `n + 1` pigeons into `n` holes, which is unsatisfiable and requires learning.
+    fn pigeonhole(n: i32) -> Cnf {
+        let var = |pigeon: i32, hole: i32| pigeon * n + hole + 1;
+        let mut clauses = Vec::new();
+        for pigeon in 0..=n {
+            clauses.push((0..n).map(|hole| var(pigeon, hole)).collect::<Vec<_>>());
+        }
+        for hole in 0..n {
+            for a in 0..=n {
+                for b in a + 1..=n {
+                    clauses.push(vec![-var(a, hole), -var(b, hole)]);
+                }
+            }
+        }
+        cnf(&clauses.iter().map(Vec::as_slice).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn solve_pigeonhole() {
+        for n in 1..=6 {
+            assert_eq!(Outcome::Unsat, Solver::new(&pigeonhole(n)).solve());
+        }
+    }
+
+    #[test]
+    fn solve_random() {
+        // Small linear congruential generator, so that test is deterministic.
+        let mut state = 0x2545_f491_u64;
+        let mut next = |bound: u64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+        for _ in 0..200 {
+            let vars = 8;
+            let clauses = (0..34)
+                .map(|_| {
+                    (0..3)
+                        .map(|_| {
+                            let var = next(vars) as i32 + 1;
+                            if next(2) == 0 { var } else { -var }
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let formula = cnf(&clauses.iter().map(Vec::as_slice).collect::<Vec<_>>());
+            let brute_force = (0..1u32 << vars).any(|bits| {
+                let model = (0..vars).map(|i| bits >> i & 1 == 1).collect::<Vec<_>>();
+                satisfies(&formula, &model)
+            });
+            match Solver::new(&formula).solve() {
+                Outcome::Sat(model) => assert!(satisfies(&formula, &model)),
+                Outcome::Unsat => assert!(!brute_force),
+            }
+        }
+    }
+}
@@ -1,138 +1,260 @@
+mod export;
+mod hypercube;
+mod schematic;
+mod style;
+mod tree;
+
 use std::f64::consts::FRAC_PI_2;
 
 use svg::{
-    node::element::{path::Data, Circle, Group, Path, Pattern, Rectangle, Text},
+    node::element::{path::Data, Circle, Group, Path, Rectangle, Text, Title},
     Document, Node,
 };
 
-pub fn generate<F>(vars: &[char], func: F) -> svg::Document
+use crate::function::Variable;
+
+pub use export::{to_png, to_svg};
+pub use hypercube::{hypercube, HYPERCUBE_LIMIT};
+pub use schematic::{schematic, SCHEMATIC_LIMIT};
+pub use style::{Fill, Style};
+pub use tree::{parse_tree, TREE_LIMIT};
+
+/// Largest number of variables Venn diagram is drawn for.
+pub const VENN_LIMIT: usize = 3;
+
+/// Text shown inside every region of a diagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegionLabels {
+    #[default]
+    None,
+    /// Index of the truth table row, like `5`.
+    Minterms,
+    /// Product of literals that is true only in the region, like `a·¬b·c`.
+    Terms,
+}
+
+/// Venn diagram of a function of up to three variables.
+///
+/// Every region has `data-minterm` attribute with the index of its truth table row
+/// and a tooltip with the index and the product term.
+/// Regions where the function is `None`, as it doesn't matter there, are filled with [`Style::DONT_CARE`].
+pub fn generate<F>(vars: &[Variable], style: &Style, labels: RegionLabels, func: F) -> svg::Document
 where
-    F: Fn(&[bool]) -> bool,
+    F: Fn(&[bool]) -> Option<bool>,
 {
+    let n = vars.len();
+    // Larger functions only get background, which is the row where every variable is false.
+    let num_regions = if n <= VENN_LIMIT { 1 << n } else { 1 };
+    let regions = (0..num_regions)
+        .map(|minterm| {
+            let values = (0..n).map(|i| minterm >> (n - 1 - i) & 1 == 1).collect::<Vec<_>>();
+            Region {
+                minterm,
+                fill: func(&values).map_or(Style::DONT_CARE.to_string(), |filled| style.paint(minterm, filled)),
+                term: term(vars, &values),
+            }
+        })
+        .collect::<Vec<_>>();
+    let dont_cares = regions.iter().any(|region| region.fill == Style::DONT_CARE);
+
     let document = Document::new()
         .set("viewBox", (0, 0, 100, 100))
-        .add(fill_pattern())
-        .add(background(func(&vec![false; vars.len()])));
-    match vars.len() {
+        .add(style.patterns(n, dont_cares))
+        .add(background(style, &regions[0]));
+    let document = match n {
         0 => document,
-        1 => {
-            let char = *vars.iter().next().unwrap();
-            document.add(single(char, func(&[true])))
-        }
-        2 => {
-            let vars: [char; 2] = vars.to_vec().try_into().unwrap();
-            document.add(double(
-                vars,
-                [
-                    func(&[true, false]),
-                    func(&[true, true]),
-                    func(&[false, true]),
-                ],
-            ))
-        }
-        3 => {
-            let vars: [char; 3] = vars.to_vec().try_into().unwrap();
-            document.add(triple(
-                vars,
-                [
-                    func(&[true, false, false]),
-                    func(&[false, true, false]),
-                    func(&[false, false, true]),
-                    func(&[true, true, false]),
-                    func(&[true, false, true]),
-                    func(&[false, true, true]),
-                    func(&[true, true, true]),
-                ],
-            ))
-        }
-        _ => document,
+        1 => document.add(single(style, &vars[0], &regions)),
+        2 => document.add(double(style, [&vars[0], &vars[1]], &regions)),
+        3 => document.add(triple(style, [&vars[0], &vars[1], &vars[2]], &regions)),
+        _ => return document,
+    };
+    match labels {
+        RegionLabels::None => document,
+        _ => document.add(region_labels(style, labels, vars, &regions)),
     }
 }
 
-/// A hatching pattern that is used to fill circles and their intersections.
-fn fill_pattern() -> svg::node::element::Pattern {
-    Pattern::new()
-        .set("id", "hatch")
-        .set("patternUnits", "userSpaceOnUse")
-        .set("width", 4)
-        .set("height", 4)
-        .add(
-            Rectangle::new()
-                .set("width", 4)
-                .set("height", 4)
-                .set("fill", "white"),
-        )
-        .add(
-            Path::new()
-                .set("d", "M-1,1 l2,-2\nM0,4 l4,-4\nM3,5 l2,-2")
-                .set("style", "stroke:gray; stroke-width:1"),
-        )
+/// A region of the diagram, identified by minterm index with the first variable being the most significant.
+struct Region {
+    minterm: usize,
+    fill: String,
+    term: String,
+}
+
+impl Region {
+    /// Fills the shape and makes it refer to the truth table row.
+    fn apply<T: Node>(&self, mut shape: T) -> T {
+        shape.assign("fill", self.fill.clone());
+        shape.assign("data-minterm", self.minterm);
+        shape.append(Title::new().add(svg::node::Text::new(format!("{}: {}", self.minterm, self.term))));
+        shape
+    }
 }
 
-fn background(filled: bool) -> impl Node {
-    Rectangle::new()
-        .set("width", "100%")
-        .set("height", "100%")
-        .set("stroke", "black")
-        .set("stroke-width", 1)
-        .set("fill", if filled { "url(#hatch)" } else { "white" })
+/// Product of literals that is true only for provided values, like `a·¬b`.
+fn term(vars: &[Variable], values: &[bool]) -> String {
+    if vars.is_empty() {
+        return "1".to_string();
+    }
+    let literals = vars.iter().zip(values).map(|(var, val)| match val {
+        true => var.to_string(),
+        false => format!("\u{00AC}{var}"),
+    });
+    literals.collect::<Vec<_>>().join("\u{00B7}")
+}
+
+fn background(style: &Style, region: &Region) -> impl Node {
+    region.apply(
+        Rectangle::new()
+            .set("width", "100%")
+            .set("height", "100%")
+            .set("stroke", style.stroke)
+            .set("stroke-width", style.stroke_width),
+    )
 }
 
 const RADIUS: f64 = 25.;
 
-fn single(var: char, fill: bool) -> impl Node {
+/// Centers of circles and positions of variable names, relative to the center of the diagram.
+fn layout(num_vars: usize) -> (Vec<Pos>, Vec<Pos>) {
+    match num_vars {
+        1 => (vec![Pos::ZERO], vec![Pos::ZERO]),
+        2 => {
+            let centers = vec![Pos { x: -RADIUS / 2., y: 0. }, Pos { x: RADIUS / 2., y: 0. }];
+            let names = vec![
+                Pos { x: -RADIUS * (15. / 16.), y: 0. },
+                Pos { x: RADIUS * (15. / 16.), y: 0. },
+            ];
+            (centers, names)
+        }
+        3 => {
+            let dy = RADIUS * f64::sqrt(3.) / 6.;
+            let side_text_offset = f64::sqrt((RADIUS / 2.).powi(2) / 2.);
+            let c1 = Pos { x: 0., y: -2. * dy };
+            let c2 = Pos { x: -RADIUS / 2., y: dy };
+            let c3 = Pos { x: RADIUS / 2., y: dy };
+            let names = vec![
+                Pos { x: c1.x, y: c1.y - RADIUS / 2. },
+                Pos { x: c2.x - side_text_offset, y: c2.y + side_text_offset },
+                Pos { x: c3.x + side_text_offset, y: c3.y + side_text_offset },
+            ];
+            (vec![c1, c2, c3], names)
+        }
+        _ => (Vec::new(), Vec::new()),
+    }
+}
+
+fn single(style: &Style, var: &Variable, regions: &[Region]) -> impl Node {
     Group::new()
         .set("transform", "translate(50, 50)")
-        .add(circle(Pos::ZERO, fill))
-        .add(text(0., 0., var))
+        .add(regions[1].apply(circle(style, Pos::ZERO)))
+        .add(text(style, Pos::ZERO, var.as_str()))
 }
 
-fn double(vars: [char; 2], fill: [bool; 3]) -> impl Node {
-    let c1 = Pos { x: -RADIUS / 2., y: 0. };
-    let c2 = Pos { x: RADIUS / 2., y: 0. };
+fn double(style: &Style, vars: [&Variable; 2], regions: &[Region]) -> impl Node {
+    let (centers, names) = layout(2);
+    let (c1, c2) = (centers[0], centers[1]);
 
     Group::new()
         .set("transform", "translate(50, 50)")
-        .add(circle(c1, fill[0]))
-        .add(circle(c2, fill[2]))
-        .add(intersection2(c1, c2, fill[1]))
-        .add(text(-RADIUS * (15. / 16.), 0., vars[0]))
-        .add(text(RADIUS * (15. / 16.), 0., vars[1]))
+        .add(regions[0b10].apply(circle(style, c1)))
+        .add(regions[0b01].apply(circle(style, c2)))
+        .add(regions[0b11].apply(intersection2(style, c1, c2)))
+        .add(text(style, names[0], vars[0].as_str()))
+        .add(text(style, names[1], vars[1].as_str()))
 }
 
-fn triple(vars: [char; 3], fill: [bool; 7]) -> impl Node {
-    let dy = RADIUS * f64::sqrt(3.) / 6.;
-    let side_text_offset = f64::sqrt((RADIUS / 2.).powi(2) / 2.);
-
-    let c1 = Pos { x: 0., y: -2. * dy };
-    let c2 = Pos { x: -RADIUS / 2., y: dy };
-    let c3 = Pos { x: RADIUS / 2., y: dy };
+fn triple(style: &Style, vars: [&Variable; 3], regions: &[Region]) -> impl Node {
+    let (centers, names) = layout(3);
+    let (c1, c2, c3) = (centers[0], centers[1], centers[2]);
 
     Group::new()
         .set("transform", "translate(50, 50)")
-        .add(circle(c1, fill[0]))
-        .add(circle(c2, fill[1]))
-        .add(circle(c3, fill[2]))
-        .add(intersection2(c1, c2, fill[3]))
-        .add(intersection2(c1, c3, fill[4]))
-        .add(intersection2(c2, c3, fill[5]))
-        .add(intersection3(fill[6]))
-        .add(text(c1.x, c1.y - RADIUS / 2., vars[0]))
-        .add(text(c2.x - side_text_offset, c2.y + side_text_offset, vars[1]))
-        .add(text(c3.x + side_text_offset, c3.y + side_text_offset, vars[2]))
-}
-
-fn circle(center: Pos, fill: bool) -> Circle {
+        .add(regions[0b100].apply(circle(style, c1)))
+        .add(regions[0b010].apply(circle(style, c2)))
+        .add(regions[0b001].apply(circle(style, c3)))
+        .add(regions[0b110].apply(intersection2(style, c1, c2)))
+        .add(regions[0b101].apply(intersection2(style, c1, c3)))
+        .add(regions[0b011].apply(intersection2(style, c2, c3)))
+        .add(regions[0b111].apply(intersection3(style)))
+        .add(text(style, names[0], vars[0].as_str()))
+        .add(text(style, names[1], vars[1].as_str()))
+        .add(text(style, names[2], vars[2].as_str()))
+}
+
+/// Label of every region, placed where it's furthest from region's boundary and variable names.
+fn region_labels(style: &Style, labels: RegionLabels, vars: &[Variable], regions: &[Region]) -> impl Node {
+    let (centers, names) = layout(vars.len());
+    let obstacles = names
+        .iter()
+        .zip(vars)
+        .map(|(pos, var)| (*pos, label_width(var.as_str()) / 2.))
+        .collect::<Vec<_>>();
+    let group = Group::new()
+        .set("transform", "translate(50, 50)")
+        .set("pointer-events", "none");
+    regions.iter().fold(group, |group, region| {
+        let inside = (0..vars.len())
+            .map(|i| region.minterm >> (vars.len() - 1 - i) & 1 == 1)
+            .collect::<Vec<_>>();
+        let pos = label_point(&centers, &inside, &obstacles);
+        let label = match labels {
+            RegionLabels::Terms => region.term.clone(),
+            _ => region.minterm.to_string(),
+        };
+        group.add(
+            Text::new()
+                .set("x", pos.x)
+                .set("y", pos.y)
+                .set("text-anchor", "middle")
+                .set("dominant-baseline", "middle")
+                .set("fill", style.text)
+                .set("font-size", "4")
+                .set("font-family", style.font_family)
+                .add(svg::node::Text::new(label)),
+        )
+    })
+}
+
+/// Point of the region that is furthest from its boundary, the frame and obstacles.
+///
+/// The region is inside of circles for which `inside` is true and outside of the others.
+/// Obstacles are given by their center and radius.
+fn label_point(centers: &[Pos], inside: &[bool], obstacles: &[(Pos, f64)]) -> Pos {
+    const STEP: f64 = 0.5;
+    let mut best = (f64::NEG_INFINITY, Pos::ZERO);
+    for i in -99..=99 {
+        for j in -99..=99 {
+            let pos = Pos { x: i as f64 * STEP, y: j as f64 * STEP };
+            let in_region = centers
+                .iter()
+                .zip(inside)
+                .all(|(center, inside)| (pos.distance(*center) < RADIUS) == *inside);
+            if !in_region {
+                continue;
+            }
+            let edges = centers.iter().map(|center| (pos.distance(*center) - RADIUS).abs());
+            let names = obstacles.iter().map(|(center, radius)| pos.distance(*center) - radius);
+            let frame = 50. - pos.x.abs().max(pos.y.abs());
+            let clearance = edges.chain(names).fold(frame, f64::min);
+            if clearance > best.0 {
+                best = (clearance, pos);
+            }
+        }
+    }
+    best.1
+}
+
+fn circle(style: &Style, center: Pos) -> Circle {
     Circle::new()
         .set("cx", center.x)
         .set("cy", center.y)
         .set("r", RADIUS)
-        .set("stroke", "black")
-        .set("stroke-width", 1)
-        .set("fill", if fill { "url(#hatch)" } else { "white" })
+        .set("stroke", style.stroke)
+        .set("stroke-width", style.stroke_width)
 }
 
-fn intersection2(circle1: Pos, circle2: Pos, fill: bool) -> Path {
+fn intersection2(style: &Style, circle1: Pos, circle2: Pos) -> Path {
     let distance = Pos::distance(circle1, circle2);
     let center = Pos::center(circle1, circle2);
     let angle = (circle1.y - circle2.y).atan2(circle1.x - circle2.x) + FRAC_PI_2;
@@ -153,12 +275,11 @@ fn intersection2(circle1: Pos, circle2: Pos, fill: bool) -> Path {
                 .elliptical_arc_by((RADIUS, RADIUS, 0, 0, 0, dx, dy))
                 .elliptical_arc_to((RADIUS, RADIUS, 0, 0, 0, start.x, start.y)),
         )
-        .set("fill", if fill { "url(#hatch)" } else { "white" })
-        .set("stroke", "black")
-        .set("stroke-width", 1)
+        .set("stroke", style.stroke)
+        .set("stroke-width", style.stroke_width)
 }
 
-fn intersection3(fill: bool) -> Path {
+fn intersection3(style: &Style) -> Path {
     let pos1 = Pos {
         x: 0.,
         y: -f64::sqrt((RADIUS / 2.).powi(2) + (3f64.sqrt() / 6. * RADIUS).powi(2)),
@@ -180,24 +301,33 @@ fn intersection3(fill: bool) -> Path {
                 .elliptical_arc_to((RADIUS, RADIUS, 0, 0, 1, pos3.x, pos3.y))
                 .elliptical_arc_to((RADIUS, RADIUS, 0, 0, 1, pos1.x, pos1.y)),
         )
-        .set("fill", if fill { "url(#hatch)" } else { "white" })
-        .set("stroke", "black")
-        .set("stroke-width", 1)
+        .set("stroke", style.stroke)
+        .set("stroke-width", style.stroke_width)
 }
 
-fn text(x: f64, y: f64, s: impl Into<String>) -> impl Node {
+/// Width of the box around a variable name.
+fn label_width(s: &str) -> f64 {
+    // Monospace glyph is about 0.6 of the font size wide.
+    8. + 4.2 * (s.chars().count().max(1) - 1) as f64
+}
+
+fn text(style: &Style, pos: Pos, s: impl Into<String>) -> impl Node {
+    let s = s.into();
+    let width = label_width(&s);
+    let Pos { x, y } = pos;
     Group::new()
+        .set("pointer-events", "none")
         .add(
             Rectangle::new()
-                .set("x", x - 4.)
+                .set("x", x - width / 2.)
                 .set("y", y - 4.)
                 .set("rx", 1)
                 .set("ry", 1)
-                .set("width", 8)
+                .set("width", width)
                 .set("height", 8)
-                .set("fill", "white")
-                .set("stroke", "black")
-                .set("stroke-width", 0.25),
+                .set("fill", style.label_background)
+                .set("stroke", style.stroke)
+                .set("stroke-width", style.stroke_width / 4.),
         )
         .add(
             Text::new()
@@ -205,9 +335,9 @@ fn text(x: f64, y: f64, s: impl Into<String>) -> impl Node {
                 .set("y", y)
                 .set("text-anchor", "middle")
                 .set("dominant-baseline", "middle")
-                .set("fill", "black")
+                .set("fill", style.text)
                 .set("font-size", "7")
-                .set("font-family", "\"andale mono\", monospace")
+                .set("font-family", style.font_family)
                 .add(svg::node::Text::new(s)),
         )
 }
@@ -234,3 +364,35 @@ impl Pos {
         }
     }
 }
+
+#[cfg(test)]
+mod svg_gen_tests {
+    use super::{generate, label_point, layout, Pos, RegionLabels, Style, RADIUS};
+    use crate::function::Function;
+
+    #[test]
+    fn regions_refer_to_rows() {
+        let func = Function::parse("a & !b").unwrap();
+        let svg = generate(func.vars(), &Style::LIGHT, RegionLabels::Terms, |vals| func.eval(vals));
+        let svg = svg.to_string();
+        for minterm in 0..4 {
+            assert!(svg.contains(&format!("data-minterm=\"{minterm}\"")));
+        }
+        assert!(svg.contains("2: a\u{00B7}\u{00AC}b"));
+    }
+
+    #[test]
+    fn label_points_inside_regions() {
+        let (centers, _) = layout(3);
+        for minterm in 1..8 {
+            let inside = [minterm & 4 != 0, minterm & 2 != 0, minterm & 1 != 0];
+            let pos = label_point(&centers, &inside, &[]);
+            for (center, inside) in centers.iter().zip(inside) {
+                assert_eq!(inside, pos.distance(*center) < RADIUS - 1., "region {minterm}");
+            }
+        }
+        let pos = label_point(&centers, &[false; 3], &[]);
+        assert!(centers.iter().all(|center| pos.distance(*center) > RADIUS + 1.));
+        assert_ne!(Pos::ZERO, pos);
+    }
+}
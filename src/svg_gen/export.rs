@@ -0,0 +1,65 @@
+use resvg::{
+    tiny_skia::{Pixmap, Transform},
+    usvg::{Options, Tree, WriteOptions},
+};
+use svg::Document;
+
+/// Font that exported diagrams use regardless of fonts installed on the system.
+const FONT: &[u8] = include_bytes!("../../static/fonts/DejaVuSansMono.ttf");
+const FONT_FAMILY: &str = "DejaVu Sans Mono";
+
+fn tree(document: &Document) -> Tree {
+    let mut options = Options {
+        font_family: FONT_FAMILY.to_string(),
+        ..Options::default()
+    };
+    let fonts = options.fontdb_mut();
+    fonts.load_font_data(FONT.to_vec());
+    fonts.set_monospace_family(FONT_FAMILY);
+    Tree::from_str(&document.to_string(), &options).expect("generated diagram should be valid SVG")
+}
+
+/// Writes the diagram as a standalone SVG file.
+///
+/// Styles are resolved into attributes and text is converted to outlines,
+/// so the file looks the same without the site's stylesheet and fonts.
+pub fn to_svg(document: &Document) -> String {
+    tree(document).to_string(&WriteOptions::default())
+}
+
+/// Rasterizes the diagram into a PNG image, `width` pixels wide.
+pub fn to_png(document: &Document, width: u32) -> Vec<u8> {
+    let tree = tree(document);
+    let scale = width as f32 / tree.size().width();
+    let height = (tree.size().height() * scale).ceil() as u32;
+    let mut pixmap = Pixmap::new(width, height).expect("image should have positive size");
+    resvg::render(&tree, Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    pixmap.encode_png().expect("PNG encoding shouldn't fail")
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::{to_png, to_svg};
+    use crate::{
+        function::Function,
+        svg_gen::{generate, RegionLabels, Style},
+    };
+
+    #[test]
+    fn standalone_svg() {
+        let func = Function::parse("a & b | !c").unwrap();
+        let svg = to_svg(&generate(func.vars(), &Style::LIGHT, RegionLabels::None, |vals| func.eval(vals)));
+        assert!(svg.contains("<pattern"));
+        assert!(!svg.contains("<text"));
+    }
+
+    #[test]
+    fn png_export() {
+        let func = Function::parse("a | b").unwrap();
+        let png = to_png(&generate(func.vars(), &Style::LIGHT, RegionLabels::None, |vals| func.eval(vals)), 256);
+        assert_eq!(b"\x89PNG", &png[..4]);
+        // Width and height are the first fields of the header chunk.
+        assert_eq!(256u32.to_be_bytes(), png[16..20]);
+        assert_eq!(256u32.to_be_bytes(), png[20..24]);
+    }
+}
@@ -0,0 +1,187 @@
+use svg::{
+    node::element::{Circle, Group, Line, Rectangle, Text, Title},
+    Document,
+};
+
+use crate::{bool_iterator::BoolIterator, function::Variable};
+
+use super::{Pos, Style};
+
+/// Largest number of variables the hypercube is drawn for.
+pub const HYPERCUBE_LIMIT: usize = 8;
+
+/// Length of an edge in projections, and distance between layers of the layered layout.
+const EDGE: f64 = 40.;
+const VERTEX_RADIUS: f64 = 3.;
+const MARGIN: f64 = 10.;
+
+/// Draws the function as a hypercube graph, where assignments are connected if they differ in one variable.
+///
+/// Vertices are filled where the function is true, and edges between such vertices are emphasized,
+/// so that implicants appear as subcubes.
+/// Vertices where the function is `None`, as it doesn't matter there, are filled with [`Style::DONT_CARE`].
+/// Every vertex has `data-minterm` attribute with the index of its truth table row.
+pub fn hypercube<F>(vars: &[Variable], style: &Style, func: F) -> Document
+where
+    F: Fn(&[bool]) -> Option<bool>,
+{
+    let n = vars.len();
+    assert!(n <= HYPERCUBE_LIMIT, "hypercube is only drawn for few variables");
+    let positions = positions(n);
+    // There are no rows without variables, but there is a single vertex.
+    let mut outputs = vec![if n == 0 { func(&[]) } else { Some(false) }; 1 << n];
+    let mut values = Vec::with_capacity(n);
+    for row in BoolIterator::with_width(n).expect("hypercube is only drawn for few variables") {
+        values.clear();
+        values.extend(row.values().rev());
+        let minterm = values.iter().fold(0, |minterm, &val| minterm << 1 | val as usize);
+        outputs[minterm] = func(&values);
+    }
+
+    let mut edges = Group::new();
+    for minterm in 0..1 << n {
+        for bit in (0..n).filter(|bit| minterm >> bit & 1 == 0) {
+            let neighbour = minterm | 1 << bit;
+            let implied = outputs[minterm] == Some(true) && outputs[neighbour] == Some(true);
+            let (from, to) = (positions[minterm], positions[neighbour]);
+            edges = edges.add(
+                Line::new()
+                    .set("x1", from.x)
+                    .set("y1", from.y)
+                    .set("x2", to.x)
+                    .set("y2", to.y)
+                    .set("stroke", if implied { style.fill_color } else { style.stroke })
+                    .set("stroke-width", style.stroke_width * if implied { 1.5 } else { 0.3 }),
+            );
+        }
+    }
+
+    let mut vertices = Group::new();
+    for (minterm, pos) in positions.iter().enumerate() {
+        let bits = (0..n).map(|i| if minterm >> (n - 1 - i) & 1 == 1 { '1' } else { '0' });
+        let bits = bits.collect::<String>();
+        let (output, fill) = match outputs[minterm] {
+            Some(output) => ((output as u8).to_string(), style.paint(minterm, output)),
+            None => ("X".to_string(), Style::DONT_CARE.to_string()),
+        };
+        let title = format!("{minterm}: {bits}, F = {output}");
+        vertices = vertices.add(
+            Circle::new()
+                .set("cx", pos.x)
+                .set("cy", pos.y)
+                .set("r", VERTEX_RADIUS)
+                .set("fill", fill)
+                .set("stroke", style.stroke)
+                .set("stroke-width", style.stroke_width / 2.)
+                .set("data-minterm", minterm)
+                .add(Title::new().add(svg::node::Text::new(title))),
+        );
+        // Layered layout is too dense for labels, but tooltips remain.
+        if n <= 4 {
+            vertices = vertices.add(
+                Text::new()
+                    .set("x", pos.x)
+                    .set("y", pos.y + VERTEX_RADIUS + 3.5)
+                    .set("text-anchor", "middle")
+                    .set("fill", style.text)
+                    .set("font-size", "4")
+                    .set("font-family", style.font_family)
+                    .set("pointer-events", "none")
+                    .add(svg::node::Text::new(bits)),
+            );
+        }
+    }
+
+    let (min, max) = positions.iter().fold((positions[0], positions[0]), |(min, max), pos| {
+        let min = Pos { x: min.x.min(pos.x), y: min.y.min(pos.y) };
+        let max = Pos { x: max.x.max(pos.x), y: max.y.max(pos.y) };
+        (min, max)
+    });
+    let (width, height) = (max.x - min.x + 2. * MARGIN, max.y - min.y + 2. * MARGIN);
+    Document::new()
+        .set("viewBox", (min.x - MARGIN, min.y - MARGIN, width, height))
+        .add(style.patterns(n, outputs.contains(&None)))
+        .add(
+            Rectangle::new()
+                .set("x", min.x - MARGIN)
+                .set("y", min.y - MARGIN)
+                .set("width", width)
+                .set("height", height)
+                .set("fill", style.background),
+        )
+        .add(edges)
+        .add(vertices)
+}
+
+/// Position of every vertex, indexed by minterm.
+///
+/// Up to three variables, it's a projection of a square or a cube, and four give a cube within a cube.
+/// More variables are laid out in layers by the number of true variables.
+fn positions(num_vars: usize) -> Vec<Pos> {
+    // Directions of the last, the second to last and the third to last variable.
+    const AXES: [Pos; 3] = [
+        Pos { x: EDGE, y: 0. },
+        Pos { x: 0., y: EDGE },
+        Pos { x: EDGE / 2., y: -EDGE * 0.4 },
+    ];
+    let project = |minterm: usize, n: usize| {
+        (0..n).fold(Pos::ZERO, |pos, bit| match minterm >> bit & 1 {
+            1 => Pos { x: pos.x + AXES[bit].x, y: pos.y + AXES[bit].y },
+            _ => pos,
+        })
+    };
+    match num_vars {
+        0..=3 => (0..1 << num_vars).map(|minterm| project(minterm, num_vars)).collect(),
+        4 => {
+            let center = project(0b111, 3).center(Pos::ZERO);
+            (0..16)
+                .map(|minterm| {
+                    let scale = if minterm & 0b1000 == 0 { 0.5 } else { 1.25 };
+                    let pos = project(minterm, 3);
+                    Pos { x: (pos.x - center.x) * scale, y: (pos.y - center.y) * scale }
+                })
+                .collect()
+        }
+        n => {
+            let mut layers = vec![Vec::new(); n + 1];
+            for minterm in 0..1usize << n {
+                layers[minterm.count_ones() as usize].push(minterm);
+            }
+            let mut positions = vec![Pos::ZERO; 1 << n];
+            for (weight, layer) in layers.iter().enumerate() {
+                for (i, &minterm) in layer.iter().enumerate() {
+                    let x = (i as f64 - (layer.len() - 1) as f64 / 2.) * 4. * VERTEX_RADIUS;
+                    positions[minterm] = Pos { x, y: (n - weight) as f64 * EDGE };
+                }
+            }
+            positions
+        }
+    }
+}
+
+#[cfg(test)]
+mod hypercube_tests {
+    use super::{hypercube, positions, HYPERCUBE_LIMIT};
+    use crate::{function::Function, svg_gen::Style};
+
+    #[test]
+    fn vertices_are_distinct() {
+        for n in 0..=HYPERCUBE_LIMIT {
+            let positions = positions(n);
+            assert_eq!(1 << n, positions.len());
+            for (i, a) in positions.iter().enumerate() {
+                assert!(positions[..i].iter().all(|b| a.distance(*b) > 1.), "{n} variables");
+            }
+        }
+    }
+
+    #[test]
+    fn hypercube_edges() {
+        let func = Function::parse("a & b | c & d").unwrap();
+        let svg = hypercube(func.vars(), &Style::LIGHT, |vals| func.eval(vals)).to_string();
+        assert_eq!(16, svg.matches("data-minterm").count());
+        assert_eq!(32, svg.matches("<line").count());
+        // Subcubes `11--` and `--11` share a vertex, and have 4 edges each.
+        assert_eq!(8, svg.matches("stroke=\"gray\"").count());
+    }
+}
@@ -0,0 +1,359 @@
+use svg::{
+    node::element::{path::Data, Circle, Group, Path, Rectangle, Title},
+    Document,
+};
+
+use crate::function::{Circuit, Gate};
+
+use super::{label_width, text, Pos, Style};
+
+/// Largest number of gates the schematic is drawn for.
+pub const SCHEMATIC_LIMIT: usize = 100;
+
+/// Vertical distance between inputs of a gate.
+const PITCH: f64 = 10.;
+/// Vertical gap between gates of a column.
+const GAP: f64 = 4.;
+const GATE_WIDTH: f64 = 20.;
+const BUBBLE_RADIUS: f64 = 2.;
+/// Deepest point of the concave back of OR gates.
+const BACK_DEPTH: f64 = 3.;
+/// Horizontal distance between vertical wires in a channel.
+const TRACK: f64 = 4.;
+/// Width of a channel between columns, not counting its tracks.
+const CHANNEL: f64 = 8.;
+const MARGIN: f64 = 6.;
+
+/// What takes a place in a column: a gate, or a wire passing the column to reach later gates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Gate(usize),
+    Wire(usize),
+}
+
+impl Slot {
+    /// Gate whose output the slot carries.
+    fn source(self) -> usize {
+        match self {
+            Slot::Gate(gate) | Slot::Wire(gate) => gate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Placed {
+    slot: Slot,
+    top: f64,
+    height: f64,
+}
+
+impl Placed {
+    fn center(&self) -> f64 {
+        self.top + self.height / 2.
+    }
+}
+
+/// Wire from a slot to inputs in the next column, as vertical offsets of its ends
+/// and distances of the inputs from the left edge of the column.
+struct Net {
+    from: f64,
+    to: Vec<(f64, f64)>,
+}
+
+/// Draws the circuit as a schematic with ANSI gate symbols, inputs on the left and the output on the right.
+///
+/// Gates are placed in columns by their distance from the inputs, and wires are routed
+/// on separate tracks between columns. A gate used by several others fans out,
+/// with a dot wherever its wire branches. Every gate has `data-gate` attribute with its index in the circuit.
+pub fn schematic(circuit: &Circuit, style: &Style) -> Document {
+    assert!(circuit.gate_count() <= SCHEMATIC_LIMIT, "schematic is only drawn for small circuits");
+    let gates = circuit.gates();
+    let columns = columns(circuit);
+
+    // Wires are routed one channel at a time, from every slot to inputs of the next column.
+    let channels = columns.windows(2).map(|pair| {
+        let [previous, column] = pair else { unreachable!() };
+        let nets = previous.iter().filter_map(|source| {
+            let mut to = Vec::new();
+            for placed in column {
+                match placed.slot {
+                    Slot::Gate(gate) => {
+                        for (port, y) in ports(circuit, gate, placed, previous) {
+                            if port == source.slot.source() {
+                                to.push((y, input_offset(&gates[gate], y - placed.top, placed.height)));
+                            }
+                        }
+                    }
+                    Slot::Wire(gate) if gate == source.slot.source() => to.push((placed.center(), 0.)),
+                    Slot::Wire(_) => {}
+                }
+            }
+            (!to.is_empty()).then(|| Net { from: source.center(), to })
+        });
+        nets.collect::<Vec<_>>()
+    });
+    let channels = channels.collect::<Vec<_>>();
+    let is_straight = |net: &Net| net.to.iter().all(|(y, _)| *y == net.from);
+
+    let inputs = columns[0].iter().map(|placed| match gates[placed.slot.source()] {
+        Gate::Input(var) => circuit.inputs()[var].to_string(),
+        Gate::Const(val) => (val as u8).to_string(),
+        _ => unreachable!("only inputs and constants have no operands"),
+    });
+    let inputs = inputs.collect::<Vec<_>>();
+    let mut right = inputs.iter().map(|name| label_width(name)).fold(0., f64::max);
+    let mut lefts = vec![0.];
+    let mut rights = vec![right];
+    for nets in &channels {
+        let tracks = nets.iter().filter(|net| !is_straight(net)).count();
+        let left = right + CHANNEL + tracks as f64 * TRACK;
+        right = left + GATE_WIDTH;
+        lefts.push(left);
+        rights.push(right);
+    }
+
+    let mut wires = Data::new();
+    let mut dots = Group::new();
+    for (index, nets) in channels.iter().enumerate() {
+        let (start, left) = (rights[index], lefts[index + 1]);
+        let mut track = start + CHANNEL / 2.;
+        for net in nets {
+            if is_straight(net) {
+                for (y, offset) in &net.to {
+                    wires = wires.move_to((start, *y)).horizontal_line_to(left + offset);
+                }
+                continue;
+            }
+            let ys = net.to.iter().map(|(y, _)| *y).chain([net.from]);
+            let (top, bottom) = ys.clone().fold((f64::MAX, f64::MIN), |(top, bottom), y| (top.min(y), bottom.max(y)));
+            wires = wires
+                .move_to((start, net.from))
+                .horizontal_line_to(track)
+                .move_to((track, top))
+                .vertical_line_to(bottom);
+            for (y, offset) in &net.to {
+                wires = wires.move_to((track, *y)).horizontal_line_to(left + offset);
+            }
+            for y in ys.filter(|y| top < *y && *y < bottom) {
+                dots = dots.add(dot(style, Pos { x: track, y }));
+            }
+            track += TRACK;
+        }
+    }
+
+    let mut symbols = Group::new();
+    let mut labels = Group::new();
+    for (name, placed) in inputs.iter().zip(&columns[0]) {
+        let x = rights[0] - label_width(name) / 2.;
+        labels = labels.add(text(style, Pos { x, y: placed.center() }, name.as_str()));
+    }
+    for (column, left) in columns.iter().zip(&lefts).skip(1) {
+        for placed in column {
+            match placed.slot {
+                Slot::Gate(gate) => symbols = symbols.add(symbol(circuit, gate, style, *left, placed)),
+                Slot::Wire(_) => {
+                    wires = wires.move_to((*left, placed.center())).horizontal_line_to(left + GATE_WIDTH);
+                }
+            }
+        }
+    }
+    let output = columns.last().unwrap().iter().find(|placed| placed.slot.source() == circuit.output());
+    let y = output.expect("output reaches the last column").center();
+    wires = wires.move_to((right, y)).horizontal_line_to(right + CHANNEL);
+    let name_width = label_width("F");
+    labels = labels.add(text(style, Pos { x: right + CHANNEL + name_width / 2., y }, "F"));
+
+    let top = columns.iter().flatten().map(|placed| placed.top).fold(0., f64::min) - MARGIN;
+    let bottom = columns.iter().flatten().map(|placed| placed.top + placed.height).fold(0., f64::max) + MARGIN;
+    let (width, height) = (right + CHANNEL + name_width + 2. * MARGIN, bottom - top);
+    Document::new()
+        .set("viewBox", (-MARGIN, top, width, height))
+        .add(
+            Rectangle::new()
+                .set("x", -MARGIN)
+                .set("y", top)
+                .set("width", width)
+                .set("height", height)
+                .set("fill", style.background),
+        )
+        .add(
+            Path::new()
+                .set("d", wires)
+                .set("fill", "none")
+                .set("stroke", style.stroke)
+                .set("stroke-width", style.stroke_width / 2.),
+        )
+        .add(dots)
+        .add(symbols)
+        .add(labels)
+}
+
+/// Slots of every column, with inputs and constants in the first one and the output in the last one.
+///
+/// Gates are placed right after the last of their operands, and wires that skip columns get a slot
+/// in every column they pass. Slots are ordered by the average position of what they're connected to
+/// in the previous column, and columns are centered vertically.
+fn columns(circuit: &Circuit) -> Vec<Vec<Placed>> {
+    let gates = circuit.gates();
+    let mut levels = vec![0; gates.len()];
+    for (index, gate) in gates.iter().enumerate() {
+        levels[index] = gate.operands().iter().map(|&operand| levels[operand] + 1).max().unwrap_or(0);
+    }
+    let depth = levels.iter().copied().max().unwrap_or(0);
+    // Column of the last gate using the output of every gate, with the output itself used past the last column.
+    let mut last_use = levels.clone();
+    for (index, gate) in gates.iter().enumerate() {
+        for &operand in gate.operands() {
+            last_use[operand] = last_use[operand].max(levels[index]);
+        }
+    }
+    last_use[circuit.output()] = depth + 1;
+
+    let mut columns = Vec::<Vec<Placed>>::with_capacity(depth + 1);
+    for level in 0..=depth {
+        let placed = (0..gates.len()).filter(|&gate| levels[gate] == level).map(Slot::Gate);
+        let passing = (0..gates.len()).filter(|&gate| levels[gate] < level && level < last_use[gate]);
+        let mut slots = placed.chain(passing.map(Slot::Wire)).collect::<Vec<_>>();
+        if let Some(previous) = columns.last() {
+            let row = |gate: usize| previous.iter().position(|placed| placed.slot.source() == gate).unwrap() as f64;
+            let key = |slot: &Slot| match *slot {
+                Slot::Gate(gate) => {
+                    let operands = gates[gate].operands();
+                    operands.iter().map(|&operand| row(operand)).sum::<f64>() / operands.len() as f64
+                }
+                Slot::Wire(gate) => row(gate),
+            };
+            slots.sort_by(|lhs, rhs| key(lhs).total_cmp(&key(rhs)));
+        }
+
+        let heights = slots.iter().map(|slot| match *slot {
+            Slot::Gate(gate) => gates[gate].operands().len().max(1) as f64 * PITCH,
+            Slot::Wire(_) => PITCH,
+        });
+        let heights = heights.collect::<Vec<_>>();
+        let total = heights.iter().sum::<f64>() + GAP * slots.len().saturating_sub(1) as f64;
+        let mut top = -total / 2.;
+        let mut column = Vec::with_capacity(slots.len());
+        for (slot, height) in slots.into_iter().zip(heights) {
+            column.push(Placed { slot, top, height });
+            top += height + GAP;
+        }
+        columns.push(column);
+    }
+    columns
+}
+
+/// Operands of the gate, each with the vertical position of its input.
+///
+/// Operands are ordered by where they come from, so that wires to a gate don't cross.
+fn ports(circuit: &Circuit, gate: usize, placed: &Placed, previous: &[Placed]) -> Vec<(usize, f64)> {
+    let from = |operand: usize| previous.iter().find(|source| source.slot.source() == operand).unwrap().center();
+    let mut operands = circuit.gates()[gate].operands().to_vec();
+    operands.sort_by(|lhs, rhs| from(*lhs).total_cmp(&from(*rhs)));
+    let operands = operands.into_iter().enumerate();
+    operands.map(|(i, operand)| (operand, placed.top + (i as f64 + 0.5) * PITCH)).collect()
+}
+
+/// Distance from the left edge of the column to where an input at the height meets the gate's body.
+fn input_offset(gate: &Gate, y: f64, height: f64) -> f64 {
+    match gate {
+        Gate::Or(_) | Gate::Nor(_) => {
+            let t = y / height;
+            4. * BACK_DEPTH * t * (1. - t)
+        }
+        _ => 0.,
+    }
+}
+
+fn symbol(circuit: &Circuit, gate: usize, style: &Style, left: f64, placed: &Placed) -> Group {
+    let (top, height) = (placed.top, placed.height);
+    let middle = top + height / 2.;
+    let kind = &circuit.gates()[gate];
+    let bubble = matches!(kind, Gate::Not(_) | Gate::Nand(_) | Gate::Nor(_));
+    let width = if bubble { GATE_WIDTH - 2. * BUBBLE_RADIUS } else { GATE_WIDTH };
+    let right = left + width;
+    let outline = match kind {
+        Gate::Not(_) => Data::new().move_to((left, top)).line_to((right, middle)).line_to((left, top + height)),
+        Gate::And(_) | Gate::Nand(_) => Data::new()
+            .move_to((left, top))
+            .horizontal_line_to(left + width / 2.)
+            .elliptical_arc_to((width / 2., height / 2., 0, 0, 1, left + width / 2., top + height))
+            .horizontal_line_to(left),
+        Gate::Or(_) | Gate::Nor(_) => Data::new()
+            .move_to((left, top))
+            .quadratic_curve_to((left + width * 0.6, top, right, middle))
+            .quadratic_curve_to((left + width * 0.6, top + height, left, top + height))
+            .quadratic_curve_to((left + 2. * BACK_DEPTH, middle, left, top)),
+        Gate::Input(_) | Gate::Const(_) => unreachable!("inputs are drawn as labels"),
+    };
+    let mut group = Group::new()
+        .set("data-gate", gate)
+        .add(Title::new().add(svg::node::Text::new(circuit.formula(gate))))
+        .add(
+            Path::new()
+                .set("d", outline.close())
+                .set("fill", style.label_background)
+                .set("stroke", style.stroke)
+                .set("stroke-width", style.stroke_width / 2.),
+        );
+    if bubble {
+        group = group.add(
+            Circle::new()
+                .set("cx", right + BUBBLE_RADIUS)
+                .set("cy", middle)
+                .set("r", BUBBLE_RADIUS)
+                .set("fill", style.label_background)
+                .set("stroke", style.stroke)
+                .set("stroke-width", style.stroke_width / 2.),
+        );
+    }
+    group
+}
+
+/// Junction of a wire that branches.
+fn dot(style: &Style, pos: Pos) -> Circle {
+    Circle::new()
+        .set("cx", pos.x)
+        .set("cy", pos.y)
+        .set("r", style.stroke_width)
+        .set("fill", style.stroke)
+}
+
+#[cfg(test)]
+mod schematic_tests {
+    use super::{columns, schematic, Slot, GAP};
+    use crate::{
+        function::{Function, GateSet},
+        svg_gen::Style,
+    };
+
+    #[test]
+    fn slots_dont_overlap() {
+        let func = Function::parse("!(a & b | !c) & (a & b | d) | a & (b | !d)").unwrap();
+        for set in [GateSet::AndOrNot, GateSet::Nand, GateSet::Nor] {
+            let circuit = func.to_circuit(set);
+            let columns = columns(&circuit);
+            for column in &columns {
+                for pair in column.windows(2) {
+                    assert!(pair[0].top + pair[0].height + GAP <= pair[1].top + 1e-9);
+                }
+            }
+            for gate in 0..circuit.gates().len() {
+                let placed = columns.iter().flatten().filter(|placed| placed.slot == Slot::Gate(gate));
+                assert_eq!(1, placed.count());
+            }
+        }
+    }
+
+    #[test]
+    fn shared_gates_fan_out() {
+        let circuit = Function::parse("(a & b | c) & (a & b | d)").unwrap().to_circuit(GateSet::Nand);
+        let svg = schematic(&circuit, &Style::LIGHT).to_string();
+        assert_eq!(circuit.gate_count(), svg.matches("data-gate").count());
+        assert_eq!(1, svg.matches("<title>a \u{2191} b</title>").count());
+        assert!(svg.contains("<circle"));
+
+        let circuit = Function::parse("a").unwrap().to_circuit(GateSet::Nand);
+        assert_eq!(0, schematic(&circuit, &Style::LIGHT).to_string().matches("data-gate").count());
+    }
+}
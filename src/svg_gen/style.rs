@@ -0,0 +1,183 @@
+use svg::node::element::{Circle, Definitions, Path, Pattern, Rectangle};
+
+/// Colors, lines and fills of a diagram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// Fill of regions where the function is false.
+    pub background: &'static str,
+    pub stroke: &'static str,
+    pub stroke_width: f64,
+    pub text: &'static str,
+    /// Fill of boxes around variable names.
+    pub label_background: &'static str,
+    pub font_family: &'static str,
+    /// Color of hatch lines or solid fill of regions where the function is true.
+    pub fill_color: &'static str,
+    pub fill: Fill,
+}
+
+/// How regions where the function is true are filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fill {
+    /// The same diagonal hatch in every region.
+    Hatch,
+    /// Solid color in every region.
+    Solid,
+    /// Hatch at a different angle in every region, so that they can be told apart without color.
+    RegionHatches,
+    /// A different color from the palette in every region.
+    RegionColors(&'static [&'static str]),
+}
+
+/// Colors that stay distinguishable with any kind of color blindness, by Okabe and Ito.
+const OKABE_ITO: [&str; 7] = ["#E69F00", "#56B4E9", "#009E73", "#F0E442", "#0072B2", "#D55E00", "#CC79A7"];
+
+impl Style {
+    pub const LIGHT: Style = Style {
+        background: "white",
+        stroke: "black",
+        stroke_width: 1.,
+        text: "black",
+        label_background: "white",
+        font_family: "\"andale mono\", monospace",
+        fill_color: "gray",
+        fill: Fill::Hatch,
+    };
+
+    pub const DARK: Style = Style {
+        background: "#11191f",
+        stroke: "#c2c7d0",
+        text: "#e6e9ee",
+        label_background: "#11191f",
+        fill_color: "#6f8797",
+        ..Style::LIGHT
+    };
+
+    /// Black and white, with regions told apart by hatch angle.
+    pub const PRINT: Style = Style {
+        fill_color: "black",
+        stroke_width: 0.75,
+        fill: Fill::RegionHatches,
+        ..Style::LIGHT
+    };
+
+    pub const COLOR_BLIND: Style = Style {
+        fill: Fill::RegionColors(&OKABE_ITO),
+        ..Style::LIGHT
+    };
+
+    pub const PRESETS: [(&'static str, Style); 4] = [
+        ("Light", Style::LIGHT),
+        ("Dark", Style::DARK),
+        ("Print", Style::PRINT),
+        ("Color-blind safe", Style::COLOR_BLIND),
+    ];
+
+    /// Value of the `fill` attribute of regions where the function doesn't matter, which are dotted.
+    pub const DONT_CARE: &'static str = "url(#dont-care)";
+
+    /// Value of the `fill` attribute of the region with provided minterm index.
+    pub fn paint(&self, minterm: usize, filled: bool) -> String {
+        if !filled {
+            return self.background.to_string();
+        }
+        match self.fill {
+            Fill::Hatch => "url(#hatch)".to_string(),
+            Fill::Solid => self.fill_color.to_string(),
+            Fill::RegionHatches => format!("url(#hatch-{minterm})"),
+            Fill::RegionColors(palette) => palette[minterm % palette.len()].to_string(),
+        }
+    }
+
+    /// Hatching patterns that regions of a diagram of `num_vars` variables refer to,
+    /// with the one of [`Style::DONT_CARE`] if there are `dont_cares`.
+    pub fn patterns(&self, num_vars: usize, dont_cares: bool) -> Definitions {
+        let mut definitions = Definitions::new();
+        if dont_cares {
+            definitions = definitions.add(self.dots());
+        }
+        match self.fill {
+            Fill::Hatch => definitions.add(self.hatch("hatch", None)),
+            Fill::RegionHatches => {
+                let regions = 1 << num_vars;
+                (0..regions).fold(definitions, |definitions, minterm| {
+                    let angle = 180. * minterm as f64 / regions as f64;
+                    definitions.add(self.hatch(&format!("hatch-{minterm}"), Some(angle)))
+                })
+            }
+            Fill::Solid | Fill::RegionColors(_) => definitions,
+        }
+    }
+
+    /// Dots, which stay distinct from hatches and solid fills.
+    fn dots(&self) -> Pattern {
+        Pattern::new()
+            .set("id", "dont-care")
+            .set("patternUnits", "userSpaceOnUse")
+            .set("width", 3)
+            .set("height", 3)
+            .add(Rectangle::new().set("width", 3).set("height", 3).set("fill", self.background))
+            .add(Circle::new().set("cx", 1.5).set("cy", 1.5).set("r", 0.6).set("fill", self.fill_color))
+    }
+
+    /// Diagonal hatch, or parallel lines at provided angle.
+    fn hatch(&self, id: &str, angle: Option<f64>) -> Pattern {
+        let pattern = Pattern::new()
+            .set("id", id)
+            .set("patternUnits", "userSpaceOnUse")
+            .set("width", 4)
+            .set("height", 4)
+            .add(
+                Rectangle::new()
+                    .set("width", 4)
+                    .set("height", 4)
+                    .set("fill", self.background),
+            );
+        let style = format!("stroke:{}; stroke-width:1", self.fill_color);
+        match angle {
+            None => pattern.add(
+                Path::new()
+                    .set("d", "M-1,1 l2,-2\nM0,4 l4,-4\nM3,5 l2,-2")
+                    .set("style", style),
+            ),
+            Some(angle) => pattern
+                .set("patternTransform", format!("rotate({angle})"))
+                .add(Path::new().set("d", "M0,2 h4").set("style", style)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod style_tests {
+    use super::Style;
+    use crate::{
+        function::Function,
+        svg_gen::{generate, RegionLabels},
+    };
+
+    #[test]
+    fn region_fills() {
+        assert_eq!("white", Style::LIGHT.paint(3, false));
+        assert_eq!("url(#hatch)", Style::LIGHT.paint(3, true));
+        assert_eq!("url(#hatch-3)", Style::PRINT.paint(3, true));
+        assert_eq!("#F0E442", Style::COLOR_BLIND.paint(3, true));
+    }
+
+    #[test]
+    fn print_patterns() {
+        let func = Function::parse("a & b").unwrap();
+        let svg = generate(func.vars(), &Style::PRINT, RegionLabels::None, |vals| func.eval(vals)).to_string();
+        assert_eq!(4, svg.matches("<pattern").count());
+        assert!(svg.contains("fill=\"url(#hatch-3)\""));
+        assert!(!svg.contains("url(#hatch-0)"));
+    }
+
+    #[test]
+    fn dont_care_fill() {
+        let func = Function::parse("a & b").unwrap();
+        let svg = generate(func.vars(), &Style::LIGHT, RegionLabels::None, |vals| func.eval(vals).filter(|_| vals[0]));
+        let svg = svg.to_string();
+        assert!(svg.contains("id=\"dont-care\""));
+        assert_eq!(2, svg.matches(&format!("fill=\"{}\"", Style::DONT_CARE)).count());
+    }
+}
@@ -0,0 +1,203 @@
+use svg::{
+    node::element::{Group, Line, Rectangle, Text, Title},
+    Document,
+};
+
+use crate::function::ParseTree;
+
+use super::{label_width, Pos, Style};
+
+/// Largest number of nodes the parse tree is drawn for.
+pub const TREE_LIMIT: usize = 255;
+
+/// Vertical distance between levels of the tree.
+const LEVEL: f64 = 20.;
+/// Smallest horizontal gap between boxes of neighbouring subtrees.
+const GAP: f64 = 4.;
+const HEIGHT: f64 = 10.;
+const MARGIN: f64 = 6.;
+
+/// Draws the parse tree, with operators as internal nodes and variables and constants as leaves.
+///
+/// Every node has `data-node` attribute with its index in the tree.
+pub fn parse_tree(tree: &ParseTree, style: &Style) -> Document {
+    let nodes = tree.nodes();
+    assert!(nodes.len() <= TREE_LIMIT, "parse tree is only drawn for short formulas");
+    let positions = positions(tree);
+
+    let mut edges = Group::new();
+    for (node, from) in nodes.iter().zip(&positions) {
+        for to in node.children.iter().map(|&child| positions[child]) {
+            edges = edges.add(
+                Line::new()
+                    .set("x1", from.x)
+                    .set("y1", from.y)
+                    .set("x2", to.x)
+                    .set("y2", to.y)
+                    .set("stroke", style.stroke)
+                    .set("stroke-width", style.stroke_width / 2.),
+            );
+        }
+    }
+
+    let mut boxes = Group::new();
+    for (index, (node, pos)) in nodes.iter().zip(&positions).enumerate() {
+        let width = label_width(&node.label);
+        // Operators get round boxes to set them apart from operands.
+        let radius = if node.children.is_empty() { 1. } else { HEIGHT / 2. };
+        let title = tree.subexpression(index).to_string();
+        boxes = boxes.add(
+            Group::new()
+                .set("data-node", index)
+                .add(Title::new().add(svg::node::Text::new(title)))
+                .add(
+                    Rectangle::new()
+                        .set("x", pos.x - width / 2.)
+                        .set("y", pos.y - HEIGHT / 2.)
+                        .set("rx", radius)
+                        .set("ry", radius)
+                        .set("width", width)
+                        .set("height", HEIGHT)
+                        .set("fill", style.label_background)
+                        .set("stroke", style.stroke)
+                        .set("stroke-width", style.stroke_width / 2.),
+                )
+                .add(
+                    Text::new()
+                        .set("x", pos.x)
+                        .set("y", pos.y)
+                        .set("text-anchor", "middle")
+                        .set("dominant-baseline", "middle")
+                        .set("fill", style.text)
+                        .set("font-size", "7")
+                        .set("font-family", style.font_family)
+                        .set("pointer-events", "none")
+                        .add(svg::node::Text::new(node.label.as_str())),
+                ),
+        );
+    }
+
+    let (mut min, mut max) = (Pos::ZERO, Pos::ZERO);
+    for (node, pos) in nodes.iter().zip(&positions) {
+        let half = label_width(&node.label) / 2.;
+        min = Pos { x: min.x.min(pos.x - half), y: min.y.min(pos.y - HEIGHT / 2.) };
+        max = Pos { x: max.x.max(pos.x + half), y: max.y.max(pos.y + HEIGHT / 2.) };
+    }
+    let (width, height) = (max.x - min.x + 2. * MARGIN, max.y - min.y + 2. * MARGIN);
+    Document::new()
+        .set("viewBox", (min.x - MARGIN, min.y - MARGIN, width, height))
+        .add(
+            Rectangle::new()
+                .set("x", min.x - MARGIN)
+                .set("y", min.y - MARGIN)
+                .set("width", width)
+                .set("height", height)
+                .set("fill", style.background),
+        )
+        .add(edges)
+        .add(boxes)
+}
+
+/// Left and right edges of a subtree at every level, relative to its root.
+struct Contour {
+    left: Vec<f64>,
+    right: Vec<f64>,
+}
+
+/// Center of every node, with the root at the origin.
+///
+/// Follows Reingold and Tilford: subtrees are laid out independently,
+/// then pushed apart until their contours are `GAP` apart at every level,
+/// and every parent is centered above its leftmost and rightmost children.
+fn positions(tree: &ParseTree) -> Vec<Pos> {
+    let nodes = tree.nodes();
+    // Offset of every node from its parent.
+    let mut offsets = vec![0.; nodes.len()];
+    let mut contours = Vec::<Option<Contour>>::with_capacity(nodes.len());
+    // Children come before parents in postfix order.
+    for node in nodes {
+        let half = label_width(&node.label) / 2.;
+        let mut merged = Contour { left: Vec::new(), right: Vec::new() };
+        let mut shifts = Vec::with_capacity(node.children.len());
+        for &child in &node.children {
+            let contour = contours[child].take().expect("every node has a single parent");
+            let shift = match shifts.is_empty() {
+                true => 0.,
+                false => merged
+                    .right
+                    .iter()
+                    .zip(&contour.left)
+                    .map(|(right, left)| right - left + GAP)
+                    .fold(f64::MIN, f64::max),
+            };
+            for (level, (left, right)) in contour.left.iter().zip(&contour.right).enumerate() {
+                match merged.left.get_mut(level) {
+                    Some(merged_left) => {
+                        *merged_left = merged_left.min(left + shift);
+                        merged.right[level] = merged.right[level].max(right + shift);
+                    }
+                    None => {
+                        merged.left.push(left + shift);
+                        merged.right.push(right + shift);
+                    }
+                }
+            }
+            shifts.push((child, shift));
+        }
+        let middle = match (shifts.first(), shifts.last()) {
+            (Some((_, first)), Some((_, last))) => (first + last) / 2.,
+            _ => 0.,
+        };
+        for (child, shift) in shifts {
+            offsets[child] = shift - middle;
+        }
+        let mut contour = Contour { left: vec![-half], right: vec![half] };
+        contour.left.extend(merged.left.iter().map(|x| x - middle));
+        contour.right.extend(merged.right.iter().map(|x| x - middle));
+        contours.push(Some(contour));
+    }
+
+    let mut positions = vec![Pos::ZERO; nodes.len()];
+    for (index, node) in nodes.iter().enumerate().rev() {
+        for &child in &node.children {
+            let parent = positions[index];
+            positions[child] = Pos { x: parent.x + offsets[child], y: parent.y + LEVEL };
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tree_tests {
+    use super::{parse_tree, positions, HEIGHT};
+    use crate::{function::ParseTree, svg_gen::{label_width, Style}};
+
+    #[test]
+    fn boxes_dont_overlap() {
+        let tree = ParseTree::parse("!(a & b | !c) & (long_name | !!d & (e | f & g)) | x1 & x2").unwrap();
+        let positions = positions(&tree);
+        let nodes = tree.nodes();
+        for i in 0..nodes.len() {
+            for j in 0..i {
+                let (a, b) = (positions[i], positions[j]);
+                let apart = (a.x - b.x).abs() >= (label_width(&nodes[i].label) + label_width(&nodes[j].label)) / 2.;
+                assert!(apart || (a.y - b.y).abs() >= HEIGHT, "{} and {}", nodes[i].label, nodes[j].label);
+            }
+        }
+    }
+
+    #[test]
+    fn parents_are_centered() {
+        let tree = ParseTree::parse("a & b | !c").unwrap();
+        let positions = positions(&tree);
+        let root = tree.nodes().last().unwrap();
+        let [lhs, rhs] = [root.children[0], root.children[1]].map(|child| positions[child]);
+        let root = positions.last().unwrap();
+        assert_eq!(root.x, (lhs.x + rhs.x) / 2.);
+        assert!(lhs.y > root.y);
+
+        let svg = parse_tree(&tree, &Style::LIGHT).to_string();
+        assert_eq!(tree.nodes().len(), svg.matches("data-node").count());
+        assert!(svg.contains("<title>!c</title>"));
+    }
+}
@@ -1,21 +1,161 @@
-use yew::{html, Html};
+mod export;
+mod order;
+mod rows;
 
-use crate::bool_iterator::BoolIterator;
+use yew::{classes, html, Callback, Html};
 
+use crate::{
+    bool_iterator::BoolIterator,
+    function::{Cube, Function, Variable},
+};
+
+pub use export::{cube_rows, table_rows, TableFormat};
+pub use order::RowOrder;
+pub use rows::{find_assignment, find_minterm, Line, TableRows, TABLE_LIMIT};
+
+/// Provided lines of a truth table, with each row labeled by its minterm index.
+///
+/// There's a column for every named output, the first of which `rows` are built from.
+/// Other outputs are functions of the same `vars`, and are left empty in collapsed runs.
+/// The row with `highlighted` minterm index, if any, is highlighted, and so is the `hovered` one.
+/// Hovering a row emits its minterm index, and clicking its outputs emits it to `ontoggle`.
+/// The `subexpression` column, if any, goes before the outputs, and is left empty in collapsed runs.
+/// Every output of don't-care rows of `rows` is `X`.
+#[allow(non_snake_case, clippy::too_many_arguments)]
+pub fn TruthTable(
+    vars: &[Variable],
+    outputs: &[(String, Function)],
+    rows: &TableRows,
+    lines: &[Line],
+    highlighted: Option<u64>,
+    hovered: Option<u64>,
+    onhover: &Callback<Option<u64>>,
+    ontoggle: &Callback<u64>,
+    subexpression: Option<&Function>,
+) -> Html {
+    let output = |value: Option<bool>, onclick: Option<Callback<web_sys::MouseEvent>>| html! {
+        <td
+            class={classes!("output", value.is_none().then_some("dont-care"))}
+            title={onclick.is_some().then_some("Click to toggle don't-care")}
+            {onclick}
+        >
+            {match value {Some(true) => "1", Some(false) => "0", None => "X"}}
+        </td>
+    };
+    let lines = lines
+        .iter()
+        .map(|line| match *line {
+            Line::Row(pos) => {
+                let minterm = rows.minterm(pos);
+                let class = classes!(
+                    (highlighted == Some(minterm)).then_some("highlighted"),
+                    (hovered == Some(minterm)).then_some("hovered"),
+                );
+                let values = rows.values(minterm);
+                let sub = subexpression.map(|sub| sub.eval(&values).unwrap());
+                let value = rows.output(minterm);
+                let onclick = || Some(ontoggle.reform(move |_| minterm));
+                html! {
+                    <tr {class} onmouseenter={onhover.reform(move |_| Some(minterm))}>
+                        <td class="minterm">{minterm}</td>
+                        {for values.iter().map(|val| html!(
+                            <td>{if *val {"1"} else {"0"}}</td>)
+                        )}
+                        if let Some(sub) = sub {
+                            <td class="subexpression">{if sub {"1"} else {"0"}}</td>
+                        }
+                        {output(value, onclick())}
+                        {for outputs[1..].iter().map(|(_, func)| output(value.and(func.eval(&values)), onclick()))}
+                    </tr>
+                }
+            }
+            Line::Run { start, end } => html! {
+                <tr class="run">
+                    <td class="minterm">{"\u{22EE}"}</td>
+                    <td colspan={vars.len().to_string()}>{format!("{} rows", end - start)}</td>
+                    if subexpression.is_some() {
+                        <td class="subexpression"></td>
+                    }
+                    {output(rows.output(rows.minterm(start)), None)}
+                    {for outputs[1..].iter().map(|_| html!(<td></td>))}
+                </tr>
+            },
+        })
+        .collect::<Html>();
+    html! {
+        <table id="truth-table" onmouseleave={onhover.reform(|_| None)}>
+            <tr>
+                <th class="minterm">{"#"}</th>
+                {
+                    for vars.iter()
+                        .map(|h| html! {
+                            <th>{h.to_string()}</th>
+                        })
+                }
+                if let Some(sub) = subexpression {
+                    <th class="subexpression">{sub.to_string()}</th>
+                }
+                {for outputs.iter().map(|(name, _)| html!(<th>{name}</th>))}
+            </tr>
+            {lines}
+        </table>
+    }
+}
+
+/// Truth table compressed into cubes, with `-` for variables the output doesn't depend on,
+/// and `X` as the output of don't-care cubes.
+#[allow(non_snake_case)]
+pub fn CubeTable(vars: &[Variable], name: &str, cubes: &[(Cube, Option<bool>)]) -> Html {
+    let rows = cubes
+        .iter()
+        .map(|(cube, output)| html! {
+            <tr>
+                {for cube.values().iter().map(|val| html!(
+                    <td>{match val {Some(true) => "1", Some(false) => "0", None => "-"}}</td>)
+                )}
+                <td class={classes!("output", output.is_none().then_some("dont-care"))}>
+                    {match output {Some(true) => "1", Some(false) => "0", None => "X"}}
+                </td>
+            </tr>
+        })
+        .collect::<Html>();
+    html! {
+        <table id="truth-table">
+            <tr>
+                {
+                    for vars.iter()
+                        .map(|h| html! {
+                            <th>{h.to_string()}</th>
+                        })
+                }
+                <th>{name}</th>
+            </tr>
+            {rows}
+        </table>
+    }
+}
+
+/// Truth table of two functions side by side, with rows where they differ highlighted.
 #[allow(non_snake_case)]
-pub fn TruthTable<F>(vars: &[char], func: F) -> Html
+pub fn ComparisonTable<F, G>(vars: &[Variable], lhs: F, rhs: G) -> Html
 where
     F: Fn(&[bool]) -> bool,
+    G: Fn(&[bool]) -> bool,
 {
-    let rows = BoolIterator::new(vars.len() as u8)
-        .map(|mut values| {
-            values.reverse();
+    let mut values = Vec::with_capacity(vars.len());
+    let rows = BoolIterator::with_width(vars.len())
+        .expect("comparison table is only made for few variables")
+        .map(|row| {
+            values.clear();
+            values.extend(row.values().rev());
+            let (lhs, rhs) = (lhs(&values), rhs(&values));
             html! {
-                <tr>
+                <tr class={(lhs != rhs).then_some("differs")}>
                     {for values.iter().map(|val| html!(
                         <td>{if *val {"1"} else {"0"}}</td>)
                     )}
-                    <td>{if func(&values) {"1"} else {"0"}}</td>
+                    <td>{if lhs {"1"} else {"0"}}</td>
+                    <td>{if rhs {"1"} else {"0"}}</td>
                 </tr>
             }
         })
@@ -26,10 +166,11 @@ where
                 {
                     for vars.iter()
                         .map(|h| html! {
-                            <th>{h}</th>
+                            <th>{h.to_string()}</th>
                         })
                 }
                 <th>{"F"}</th>
+                <th>{"G"}</th>
             </tr>
             {rows}
         </table>
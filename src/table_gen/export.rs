@@ -0,0 +1,270 @@
+use std::fmt::Write;
+
+use crate::function::{Cube, Function, Variable};
+
+use super::TableRows;
+
+/// A text format the truth table can be saved in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    /// GitHub flavored Markdown.
+    Markdown,
+    /// LaTeX `tabular` environment.
+    Latex,
+    /// Object with variable names and rows.
+    Json,
+}
+
+/// A row to be exported, which is a cube if some values are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportRow {
+    /// Minterm index, if it's shown.
+    pub minterm: Option<u64>,
+    pub values: Vec<Option<bool>>,
+    /// Value of every output, or `None` on don't-care rows.
+    pub outputs: Vec<Option<bool>>,
+}
+
+impl TableFormat {
+    pub const ALL: [TableFormat; 4] = [Self::Csv, Self::Markdown, Self::Latex, Self::Json];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Markdown => "Markdown",
+            Self::Latex => "LaTeX",
+            Self::Json => "JSON",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Markdown => "md",
+            Self::Latex => "tex",
+            Self::Json => "json",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Markdown => "text/markdown",
+            Self::Latex => "application/x-tex",
+            Self::Json => "application/json",
+        }
+    }
+
+    /// Writes the table with columns of named outputs, labeling rows by minterm index if the first row has one.
+    ///
+    /// JSON rows have an `output` if there's one, and an array of `outputs` otherwise.
+    /// Outputs of don't-care rows are `X`, or `null` in JSON.
+    pub fn write(self, vars: &[Variable], names: &[String], rows: impl IntoIterator<Item = ExportRow>) -> String {
+        let mut rows = rows.into_iter().peekable();
+        let labeled = rows.peek().is_some_and(|row| row.minterm.is_some());
+        let mut output = String::new();
+        match self {
+            Self::Csv => {
+                let mut header = vars.iter().map(Variable::to_string).collect::<Vec<_>>();
+                header.extend(names.iter().cloned());
+                if labeled {
+                    header.insert(0, "#".to_string());
+                }
+                writeln!(output, "{}", header.join(",")).unwrap();
+                for row in rows {
+                    writeln!(output, "{}", cells(&row, labeled).join(",")).unwrap();
+                }
+            }
+            Self::Markdown => {
+                let mut header = vars.iter().map(|var| format!("`{var}`")).collect::<Vec<_>>();
+                let mut rule = vec![":-:"; vars.len() + names.len()];
+                header.extend(names.iter().cloned());
+                if labeled {
+                    header.insert(0, "#".to_string());
+                    rule.insert(0, "-:");
+                }
+                writeln!(output, "| {} |", header.join(" | ")).unwrap();
+                writeln!(output, "|{}|", rule.join("|")).unwrap();
+                for row in rows {
+                    writeln!(output, "| {} |", cells(&row, labeled).join(" | ")).unwrap();
+                }
+            }
+            Self::Latex => {
+                let columns = "c".repeat(vars.len());
+                let mut header = vars
+                    .iter()
+                    .map(|var| format!("${}$", var.as_str().replace('_', "\\_")))
+                    .collect::<Vec<_>>();
+                header.extend(names.iter().map(|name| format!("${}$", name.replace('_', "\\_"))));
+                let outputs = "c".repeat(names.len());
+                if labeled {
+                    header.insert(0, "\\#".to_string());
+                    writeln!(output, "\\begin{{tabular}}{{r|{columns}|{outputs}}}").unwrap();
+                } else {
+                    writeln!(output, "\\begin{{tabular}}{{{columns}|{outputs}}}").unwrap();
+                }
+                writeln!(output, "  {} \\\\", header.join(" & ")).unwrap();
+                writeln!(output, "  \\hline").unwrap();
+                for row in rows {
+                    writeln!(output, "  {} \\\\", cells(&row, labeled).join(" & ")).unwrap();
+                }
+                writeln!(output, "\\end{{tabular}}").unwrap();
+            }
+            Self::Json => {
+                let vars = vars.iter().map(|var| format!("\"{var}\"")).collect::<Vec<_>>();
+                writeln!(output, "{{").unwrap();
+                writeln!(output, "  \"variables\": [{}],", vars.join(", ")).unwrap();
+                if names.len() > 1 {
+                    let names = names.iter().map(|name| format!("\"{name}\"")).collect::<Vec<_>>();
+                    writeln!(output, "  \"outputs\": [{}],", names.join(", ")).unwrap();
+                }
+                write!(output, "  \"rows\": [").unwrap();
+                for (i, row) in rows.enumerate() {
+                    let values = row
+                        .values
+                        .iter()
+                        .map(|val| val.map_or("null", |val| if val { "1" } else { "0" }))
+                        .collect::<Vec<_>>();
+                    output.push_str(if i == 0 { "\n" } else { ",\n" });
+                    output.push_str("    {");
+                    if let Some(minterm) = row.minterm {
+                        write!(output, "\"minterm\": {minterm}, ").unwrap();
+                    }
+                    write!(output, "\"values\": [{}], ", values.join(", ")).unwrap();
+                    let outputs = row
+                        .outputs
+                        .iter()
+                        .map(|val| val.map_or("null", |val| if val { "1" } else { "0" }))
+                        .collect::<Vec<_>>();
+                    match outputs.as_slice() {
+                        [value] => write!(output, "\"output\": {value}}}").unwrap(),
+                        _ => write!(output, "\"outputs\": [{}]}}", outputs.join(", ")).unwrap(),
+                    }
+                }
+                writeln!(output, "\n  ]").unwrap();
+                writeln!(output, "}}").unwrap();
+            }
+        }
+        output
+    }
+}
+
+/// Cells of the row, with `-` for absent values and `X` for outputs that don't matter.
+fn cells(row: &ExportRow, labeled: bool) -> Vec<String> {
+    let values = row.values.iter().map(|val| match val {
+        Some(true) => "1".to_string(),
+        Some(false) => "0".to_string(),
+        None => "-".to_string(),
+    });
+    let label = row.minterm.filter(|_| labeled).map(|minterm| minterm.to_string());
+    let outputs = row.outputs.iter().map(|val| val.map_or("X".to_string(), |val| (val as u8).to_string()));
+    label.into_iter().chain(values).chain(outputs).collect()
+}
+
+/// All rows of the table in its order, with values of the first output and then of `others`.
+///
+/// Every output is `None` on don't-care rows of the table.
+pub fn table_rows<'a>(rows: &'a TableRows, others: &'a [(String, Function)]) -> impl Iterator<Item = ExportRow> + 'a {
+    (0..rows.num_rows()).map(|pos| {
+        let minterm = rows.minterm(pos);
+        let values = rows.values(minterm);
+        let output = rows.output(minterm);
+        let others = others.iter().map(|(_, func)| output.and(func.eval(&values)));
+        ExportRow {
+            minterm: Some(minterm),
+            outputs: [output].into_iter().chain(others).collect(),
+            values: values.into_iter().map(Some).collect(),
+        }
+    })
+}
+
+/// Rows of the table compressed into cubes.
+pub fn cube_rows(cubes: &[(Cube, Option<bool>)]) -> impl Iterator<Item = ExportRow> + '_ {
+    cubes.iter().map(|(cube, output)| ExportRow {
+        minterm: None,
+        values: cube.values().to_vec(),
+        outputs: vec![*output],
+    })
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::{cube_rows, table_rows, TableFormat};
+    use crate::{
+        function::{Function, MultiFunction},
+        table_gen::{RowOrder, TableRows},
+    };
+
+    fn table(format: TableFormat) -> String {
+        let func = Function::parse("a & !x_1").unwrap();
+        let rows = TableRows::new(&func, None, RowOrder::Gray, Some(false));
+        format.write(func.vars(), &["F".to_string()], table_rows(&rows, &[]))
+    }
+
+    #[test]
+    fn export_csv() {
+        assert_eq!("#,a,x_1,F\n0,0,0,0\n1,0,1,0\n3,1,1,0\n", table(TableFormat::Csv));
+    }
+
+    #[test]
+    fn export_markdown() {
+        let expected = "| # | `a` | `x_1` | F |\n|-:|:-:|:-:|:-:|\n\
+            | 0 | 0 | 0 | 0 |\n| 1 | 0 | 1 | 0 |\n| 3 | 1 | 1 | 0 |\n";
+        assert_eq!(expected, table(TableFormat::Markdown));
+    }
+
+    #[test]
+    fn export_latex() {
+        let expected = "\\begin{tabular}{r|cc|c}\n  \\# & $a$ & $x\\_1$ & $F$ \\\\\n  \\hline\n  \
+            0 & 0 & 0 & 0 \\\\\n  1 & 0 & 1 & 0 \\\\\n  3 & 1 & 1 & 0 \\\\\n\\end{tabular}\n";
+        assert_eq!(expected, table(TableFormat::Latex));
+    }
+
+    #[test]
+    fn export_json() {
+        let expected = "{\n  \"variables\": [\"a\", \"x_1\"],\n  \"rows\": [\n    \
+            {\"minterm\": 0, \"values\": [0, 0], \"output\": 0},\n    \
+            {\"minterm\": 1, \"values\": [0, 1], \"output\": 0},\n    \
+            {\"minterm\": 3, \"values\": [1, 1], \"output\": 0}\n  ]\n}\n";
+        assert_eq!(expected, table(TableFormat::Json));
+    }
+
+    #[test]
+    fn export_cubes() {
+        let func = Function::parse("a & !b").unwrap();
+        let cubes = func.cubes_with_dont_care(func.vars(), Function::parse("!a & b").ok().as_ref());
+        let csv = TableFormat::Csv.write(func.vars(), &["F".to_string()], cube_rows(&cubes));
+        assert_eq!("a,b,F\n0,0,0\n0,1,X\n1,0,1\n1,1,0\n", csv);
+        let json = TableFormat::Json.write(func.vars(), &["F".to_string()], cube_rows(&cubes[1..2]));
+        assert!(json.contains("{\"values\": [0, 1], \"output\": null}"));
+        let cubes = func.cubes_with_dont_care(func.vars(), None);
+        let json = TableFormat::Json.write(func.vars(), &["F".to_string()], cube_rows(&cubes[..1]));
+        assert!(json.contains("{\"values\": [0, null], \"output\": 0}"));
+    }
+
+    #[test]
+    fn export_outputs() {
+        let outputs = [("s", "a & !b | !a & b"), ("c", "a & b")];
+        let outputs = outputs.map(|(name, formula)| (name.to_string(), Function::parse(formula).unwrap()));
+        let multi = MultiFunction::new(outputs.to_vec());
+        let rows = TableRows::new(multi.first(), None, RowOrder::Binary, None);
+        let names = multi.outputs().iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+        let csv = TableFormat::Csv.write(multi.inputs(), &names, table_rows(&rows, &multi.outputs()[1..]));
+        assert_eq!("#,a,b,s,c\n0,0,0,0,0\n1,0,1,1,0\n2,1,0,1,0\n3,1,1,0,1\n", csv);
+        let json = TableFormat::Json.write(multi.inputs(), &names, table_rows(&rows, &multi.outputs()[1..]));
+        assert!(json.contains("\"outputs\": [\"s\", \"c\"],"));
+        assert!(json.contains("{\"minterm\": 3, \"values\": [1, 1], \"outputs\": [0, 1]}"));
+    }
+
+    #[test]
+    fn export_dont_cares() {
+        let multi = MultiFunction::parse("f = a; g = !a; d(a & b)").unwrap();
+        let rows = TableRows::new(multi.first(), multi.dont_care(), RowOrder::Binary, None);
+        let names = multi.outputs().iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+        let csv = TableFormat::Csv.write(multi.inputs(), &names, table_rows(&rows, &multi.outputs()[1..]));
+        assert_eq!("#,a,b,f,g\n0,0,0,0,1\n1,0,1,0,1\n2,1,0,1,0\n3,1,1,X,X\n", csv);
+        let json = TableFormat::Json.write(multi.inputs(), &names, table_rows(&rows, &multi.outputs()[1..]));
+        assert!(json.contains("\"outputs\": [null, null]"));
+    }
+}
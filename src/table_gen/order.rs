@@ -0,0 +1,124 @@
+use crate::function::Variable;
+
+/// Order in which rows of a truth table are listed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RowOrder {
+    /// Counting in binary, with the first variable being the most significant.
+    #[default]
+    Binary,
+    /// Reflected Gray code, so that neighbouring rows differ in a single variable.
+    Gray,
+    /// Rows where function is true, followed by rows where it's false, both in binary order.
+    OutputsFirst,
+    /// Counting in binary, with variables listed by their index from the most significant.
+    Significance(Vec<usize>),
+}
+
+impl RowOrder {
+    /// Reads variable names separated by commas or spaces, from the most significant.
+    ///
+    /// Every variable must be listed exactly once.
+    pub fn significance(vars: &[Variable], names: &str) -> Option<RowOrder> {
+        let mut order = Vec::with_capacity(vars.len());
+        for name in names.split([',', ' ']).filter(|name| !name.is_empty()) {
+            let index = vars.iter().position(|var| var.as_str() == name)?;
+            if order.contains(&index) {
+                return None;
+            }
+            order.push(index);
+        }
+        (order.len() == vars.len()).then_some(RowOrder::Significance(order))
+    }
+}
+
+/// Reflected Gray code of a number.
+pub fn gray(n: u64) -> u64 {
+    n ^ (n >> 1)
+}
+
+/// Number which has provided reflected Gray code.
+pub fn gray_inverse(code: u64) -> u64 {
+    let mut n = code;
+    let mut shift = 1;
+    while shift < u64::BITS {
+        n ^= n >> shift;
+        shift *= 2;
+    }
+    n
+}
+
+/// Moves bit of `n` at significance position `i` to bit of variable `order[i]`.
+pub fn permute(order: &[usize], n: u64) -> u64 {
+    let width = order.len();
+    order.iter().enumerate().fold(0, |minterm, (pos, &var)| {
+        minterm | (n >> (width - 1 - pos) & 1) << (width - 1 - var)
+    })
+}
+
+/// Inverse of [`permute`].
+pub fn permute_inverse(order: &[usize], minterm: u64) -> u64 {
+    let width = order.len();
+    order.iter().enumerate().fold(0, |n, (pos, &var)| {
+        n | (minterm >> (width - 1 - var) & 1) << (width - 1 - pos)
+    })
+}
+
+/// Writes values of variables in the minterm to the buffer.
+///
+/// The first variable is the most significant bit of the minterm index,
+/// and variables above its 64 bits are false.
+pub fn minterm_values(minterm: u64, width: u8, buffer: &mut Vec<bool>) {
+    buffer.clear();
+    buffer.extend((0..width).rev().map(|i| minterm.checked_shr(i.into()).unwrap_or(0) & 1 == 1));
+}
+
+/// Minterm index of values, with the first variable being the most significant bit.
+pub fn minterm_index(values: &[bool]) -> u64 {
+    values.iter().fold(0, |minterm, &val| minterm << 1 | val as u64)
+}
+
+#[cfg(test)]
+mod order_tests {
+    use super::{gray, gray_inverse, minterm_index, minterm_values, permute, permute_inverse, RowOrder};
+    use crate::function::Variable;
+
+    #[test]
+    fn gray_code() {
+        let codes = (0..8).map(gray).collect::<Vec<_>>();
+        assert_eq!(vec![0, 1, 3, 2, 6, 7, 5, 4], codes);
+        for n in [0, 5, 1 << 40, u64::MAX] {
+            assert_eq!(n, gray_inverse(gray(n)));
+        }
+    }
+
+    #[test]
+    fn significance_permutation() {
+        // The last variable is the most significant, then the first one.
+        let order = [2, 0, 1];
+        let minterms = (0..8).map(|n| permute(&order, n)).collect::<Vec<_>>();
+        assert_eq!(vec![0, 2, 4, 6, 1, 3, 5, 7], minterms);
+        for n in 0..8 {
+            assert_eq!(n, permute_inverse(&order, permute(&order, n)));
+        }
+    }
+
+    #[test]
+    fn significance_from_names() {
+        let vars = ["a", "b", "cin"].map(|name| Variable::new(name).unwrap());
+        assert_eq!(
+            Some(RowOrder::Significance(vec![2, 0, 1])),
+            RowOrder::significance(&vars, "cin, a b")
+        );
+        assert_eq!(None, RowOrder::significance(&vars, "cin a"));
+        assert_eq!(None, RowOrder::significance(&vars, "cin a a"));
+        assert_eq!(None, RowOrder::significance(&vars, "cin a d"));
+    }
+
+    #[test]
+    fn values_of_minterm() {
+        let mut buffer = Vec::new();
+        minterm_values(6, 3, &mut buffer);
+        assert_eq!(vec![true, true, false], buffer);
+        assert_eq!(6, minterm_index(&buffer));
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{
+    bdd::{Bdd, NodeRef},
+    function::{Function, Variable},
+};
+
+use super::order::{gray, gray_inverse, minterm_index, minterm_values, permute, permute_inverse, RowOrder};
+
+/// Runs of at least that many rows with the same output may be collapsed into a single line.
+pub const MIN_RUN: u64 = 4;
+
+/// Largest number of variables the rows are listed for, so that row positions fit into `u64`.
+pub const TABLE_LIMIT: usize = 63;
+
+/// Rows of a function's truth table in some order, computed on demand by their position.
+///
+/// Rows are identified by a key, which is a minterm index with its bits rearranged so that
+/// the table lists rows by increasing key, possibly grouped by output.
+#[derive(Debug, Clone)]
+pub struct TableRows {
+    width: u8,
+    order: RowOrder,
+    /// Diagrams over bits of the key, with the most significant bit being variable `0`.
+    bdd: Bdd,
+    ones: NodeRef,
+    /// Rows where the output doesn't matter, which are neither ones nor zeros.
+    dont_care: NodeRef,
+    /// Rows that are listed one after another, with their number.
+    groups: Vec<(NodeRef, u64)>,
+    /// Rows with an output other than zero, one and don't-care, in that order.
+    others: [NodeRef; 3],
+    counted: Vec<bool>,
+}
+
+/// A line of the displayed table, referring to rows by their position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    Row(u64),
+    /// Rows from `start` to `end` exclusive, all with the same output, or all don't-cares.
+    Run { start: u64, end: u64 },
+}
+
+impl TableRows {
+    /// Orders rows of the function, keeping only those where it equals `filter`, if any.
+    ///
+    /// Rows where `dont_care` is true, if it's provided, are neither ones nor zeros:
+    /// they're listed last when true rows go first, and aren't kept by any filter.
+    /// The function should have at most [`TABLE_LIMIT`] variables, and `dont_care` should have the same ones.
+    pub fn new(
+        func: &Function,
+        dont_care: Option<&Function>,
+        order: RowOrder,
+        filter: Option<bool>,
+    ) -> Self {
+        let width = func.vars().len();
+        let mut bdd = Bdd::new(width);
+        let mut nodes = (0..width).map(|i| bdd.var(i)).collect::<Vec<_>>();
+        match &order {
+            RowOrder::Binary | RowOrder::OutputsFirst => {}
+            RowOrder::Gray => {
+                for i in (1..width).rev() {
+                    nodes[i] = bdd.xor(nodes[i - 1], nodes[i]);
+                }
+            }
+            RowOrder::Significance(order) => {
+                for (pos, &var) in order.iter().enumerate() {
+                    nodes[var] = bdd.var(pos);
+                }
+            }
+        }
+        let dont_care = match dont_care {
+            Some(dont_care) => dont_care.compose_bdd(&mut bdd, &nodes),
+            None => Bdd::FALSE,
+        };
+        let care = bdd.not(dont_care);
+        let output = func.compose_bdd(&mut bdd, &nodes);
+        let ones = bdd.and(output, care);
+        let zeros = bdd.not(output);
+        let zeros = bdd.and(zeros, care);
+        let selected = match (filter, &order) {
+            (Some(true), _) => vec![ones],
+            (Some(false), _) => vec![zeros],
+            (None, RowOrder::OutputsFirst) if dont_care == Bdd::FALSE => vec![ones, zeros],
+            (None, RowOrder::OutputsFirst) => vec![ones, zeros, dont_care],
+            (None, _) => vec![Bdd::TRUE],
+        };
+        let others = [zeros, ones, dont_care].map(|node| bdd.not(node));
+        let counted = vec![true; width];
+        let groups = selected
+            .into_iter()
+            .map(|node| (node, bdd.count(node, &counted) as u64))
+            .collect();
+        Self { width: width as u8, order, bdd, ones, dont_care, groups, others, counted }
+    }
+
+    /// Number of rows.
+    pub fn num_rows(&self) -> u64 {
+        self.groups.iter().map(|(_, count)| count).sum()
+    }
+
+    fn key_to_minterm(&self, key: u64) -> u64 {
+        match &self.order {
+            RowOrder::Binary | RowOrder::OutputsFirst => key,
+            RowOrder::Gray => gray(key),
+            RowOrder::Significance(order) => permute(order, key),
+        }
+    }
+
+    fn minterm_to_key(&self, minterm: u64) -> u64 {
+        match &self.order {
+            RowOrder::Binary | RowOrder::OutputsFirst => minterm,
+            RowOrder::Gray => gray_inverse(minterm),
+            RowOrder::Significance(order) => permute_inverse(order, minterm),
+        }
+    }
+
+    /// Minterm index of the row at provided position.
+    pub fn minterm(&self, pos: u64) -> u64 {
+        let mut n = pos;
+        for &(node, count) in self.groups.iter() {
+            if n >= count {
+                n -= count;
+                continue;
+            }
+            let key = match node == Bdd::TRUE {
+                true => n,
+                false => minterm_index(&self.bdd.select(node, &self.counted, n as u128)),
+            };
+            return self.key_to_minterm(key);
+        }
+        panic!("position should be less than number of rows")
+    }
+
+    /// Position of the row with provided minterm index, unless it's filtered out.
+    pub fn position(&self, minterm: u64) -> Option<u64> {
+        let key = self.values(self.minterm_to_key(minterm));
+        let mut offset = 0;
+        for &(node, count) in self.groups.iter() {
+            if self.bdd.eval(node, &key) {
+                return Some(offset + self.bdd.rank(node, &self.counted, &key) as u64);
+            }
+            offset += count;
+        }
+        None
+    }
+
+    /// Values of variables in the minterm.
+    pub fn values(&self, minterm: u64) -> Vec<bool> {
+        let mut values = Vec::with_capacity(self.width as usize);
+        minterm_values(minterm, self.width, &mut values);
+        values
+    }
+
+    /// Value of the function at the minterm, or `None` if it's a don't-care.
+    pub fn output(&self, minterm: u64) -> Option<bool> {
+        let key = self.values(self.minterm_to_key(minterm));
+        match self.bdd.eval(self.dont_care, &key) {
+            true => None,
+            false => Some(self.bdd.eval(self.ones, &key)),
+        }
+    }
+
+    /// Bounds of the longest run of rows with the same output that contains the position.
+    fn run(&self, pos: u64) -> (u64, u64) {
+        let mut offset = 0;
+        for &(node, count) in self.groups.iter() {
+            if pos >= offset + count {
+                offset += count;
+                continue;
+            }
+            if node != Bdd::TRUE {
+                // Rows of a group share the output, which differs from that of other groups.
+                return (offset, offset + count);
+            }
+            // Every row is listed, so positions are keys, and the run is bounded by keys with other outputs.
+            let values = self.values(pos);
+            let others = match self.output(self.key_to_minterm(pos)) {
+                Some(false) => self.others[0],
+                Some(true) => self.others[1],
+                None => self.others[2],
+            };
+            let before = self.bdd.rank(others, &self.counted, &values);
+            let start = match before {
+                0 => 0,
+                before => minterm_index(&self.bdd.select(others, &self.counted, before - 1)) + 1,
+            };
+            let end = match before < self.bdd.count(others, &self.counted) {
+                true => minterm_index(&self.bdd.select(others, &self.counted, before)),
+                false => count,
+            };
+            return (start, end);
+        }
+        panic!("position should be less than number of rows")
+    }
+
+    /// Position where the line containing provided row starts.
+    pub fn line_start(&self, pos: u64, collapse: bool) -> u64 {
+        match collapse {
+            true => match self.run(pos) {
+                (start, end) if end - start >= MIN_RUN => start,
+                _ => pos,
+            },
+            false => pos,
+        }
+    }
+
+    /// Up to `count` lines starting at provided position.
+    pub fn lines(&self, start: u64, count: usize, collapse: bool) -> Vec<Line> {
+        let mut lines = Vec::with_capacity(count);
+        let mut pos = start;
+        while lines.len() < count && pos < self.num_rows() {
+            if collapse {
+                let (run_start, end) = self.run(pos);
+                if end - run_start >= MIN_RUN {
+                    lines.push(Line::Run { start: pos, end });
+                    pos = end;
+                    continue;
+                }
+            }
+            lines.push(Line::Row(pos));
+            pos += 1;
+        }
+        lines
+    }
+
+    /// Position where `count` lines that end right before provided position start.
+    pub fn lines_before(&self, end: u64, count: usize, collapse: bool) -> u64 {
+        let mut pos = end;
+        for _ in 0..count {
+            if pos == 0 {
+                break;
+            }
+            pos = self.line_start(pos - 1, collapse);
+        }
+        pos
+    }
+}
+
+/// Reads a minterm index, like `5`, or an assignment of every variable, like `a = 1, b = 0`.
+pub fn find_minterm(vars: &[Variable], query: &str) -> Option<u64> {
+    let values = find_assignment(vars, query)?;
+    // Bits above the index are only allowed if they're all zero.
+    let (high, low) = values.split_at(values.len().saturating_sub(u64::BITS as usize));
+    high.iter().all(|val| !val).then(|| minterm_index(low))
+}
+
+/// Reads values of variables from a minterm index or an assignment, like [`find_minterm`],
+/// for any number of variables.
+pub fn find_assignment(vars: &[Variable], query: &str) -> Option<Vec<bool>> {
+    let query = query.split_whitespace().collect::<String>();
+    if query.chars().all(|ch| ch.is_ascii_digit()) {
+        let minterm = query.parse::<u64>().ok()?;
+        let fits = minterm.checked_shr(vars.len() as u32).unwrap_or(0) == 0;
+        let bit = |i: usize| u32::try_from(i).ok().and_then(|i| minterm.checked_shr(i)).unwrap_or(0) & 1 == 1;
+        return fits.then(|| (0..vars.len()).rev().map(bit).collect());
+    }
+    let mut values = vec![None; vars.len()];
+    for pair in query.split(',') {
+        let (name, value) = pair.split_once('=')?;
+        let index = vars.iter().position(|var| var.as_str() == name)?;
+        let value = match value {
+            "0" => false,
+            "1" => true,
+            _ => return None,
+        };
+        if values[index].replace(value).is_some() {
+            return None;
+        }
+    }
+    values.into_iter().collect()
+}
+
+#[cfg(test)]
+mod rows_tests {
+    use super::{find_assignment, find_minterm, Line, TableRows, TABLE_LIMIT};
+    use crate::{function::Function, table_gen::RowOrder};
+
+    fn minterms(rows: &TableRows) -> Vec<u64> {
+        (0..rows.num_rows()).map(|pos| rows.minterm(pos)).collect()
+    }
+
+    #[test]
+    fn rows_in_order() {
+        let func = Function::parse("a & !b | c").unwrap();
+        let rows = TableRows::new(&func, None, RowOrder::Binary, None);
+        assert_eq!((0..8).collect::<Vec<_>>(), minterms(&rows));
+        let rows = TableRows::new(&func, None, RowOrder::Gray, None);
+        assert_eq!(vec![0, 1, 3, 2, 6, 7, 5, 4], minterms(&rows));
+        let rows = TableRows::new(&func, None, RowOrder::OutputsFirst, None);
+        assert_eq!(vec![1, 3, 4, 5, 7, 0, 2, 6], minterms(&rows));
+        let rows = TableRows::new(&func, None, RowOrder::Significance(vec![2, 0, 1]), None);
+        assert_eq!(vec![0, 2, 4, 6, 1, 3, 5, 7], minterms(&rows));
+    }
+
+    #[test]
+    fn rows_positions() {
+        let func = Function::parse("a & !b | c").unwrap();
+        let orders = [RowOrder::Gray, RowOrder::OutputsFirst, RowOrder::Significance(vec![1, 2, 0])];
+        for order in orders {
+            for filter in [None, Some(true), Some(false)] {
+                let rows = TableRows::new(&func, None, order.clone(), filter);
+                for pos in 0..rows.num_rows() {
+                    assert_eq!(Some(pos), rows.position(rows.minterm(pos)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn filtered_rows() {
+        let func = Function::parse("a & !b | c").unwrap();
+        let rows = TableRows::new(&func, None, RowOrder::Gray, Some(true));
+        assert_eq!(vec![1, 3, 7, 5, 4], minterms(&rows));
+        assert!((0..rows.num_rows()).all(|pos| rows.output(rows.minterm(pos)) == Some(true)));
+        assert_eq!(None, rows.position(2));
+        let rows = TableRows::new(&func, None, RowOrder::Significance(vec![2, 0, 1]), Some(false));
+        assert_eq!(vec![0, 2, 6], minterms(&rows));
+    }
+
+    #[test]
+    fn rows_of_constant() {
+        let rows = TableRows::new(&Function::parse("1").unwrap(), None, RowOrder::Binary, None);
+        assert_eq!(1, rows.num_rows());
+        assert_eq!(Some(true), rows.output(0));
+    }
+
+    #[test]
+    fn dont_care_rows() {
+        let func = Function::parse("a & b").unwrap();
+        let dont_care = Function::parse("a & !b").unwrap();
+        let rows = TableRows::new(&func, Some(&dont_care), RowOrder::OutputsFirst, None);
+        assert_eq!(vec![3, 0, 1, 2], minterms(&rows));
+        let outputs = (0..4).map(|minterm| rows.output(minterm)).collect::<Vec<_>>();
+        assert_eq!(vec![Some(false), Some(false), None, Some(true)], outputs);
+        let rows = TableRows::new(&func, Some(&dont_care), RowOrder::Binary, Some(false));
+        assert_eq!(vec![0, 1], minterms(&rows));
+    }
+
+    #[test]
+    fn collapsed_lines() {
+        // False for the first six rows, then true for two.
+        let func = Function::parse("a & b & (c | !c)").unwrap();
+        let rows = TableRows::new(&func, None, RowOrder::Binary, None);
+        assert_eq!(
+            vec![Line::Run { start: 0, end: 6 }, Line::Row(6), Line::Row(7)],
+            rows.lines(0, 10, true)
+        );
+        assert_eq!(vec![Line::Run { start: 3, end: 6 }, Line::Row(6)], rows.lines(3, 2, true));
+        assert_eq!(vec![Line::Row(3), Line::Row(4)], rows.lines(3, 2, false));
+        assert_eq!(0, rows.lines_before(7, 2, true));
+        assert_eq!(5, rows.lines_before(7, 2, false));
+        assert_eq!(0, rows.line_start(4, true));
+        assert_eq!(7, rows.line_start(7, true));
+    }
+
+    #[test]
+    fn runs_of_outputs() {
+        let func = Function::parse("a & !b | c & d").unwrap();
+        let dont_care = Function::parse("!a & b & !c").unwrap();
+        let custom = RowOrder::Significance(vec![3, 1, 0, 2]);
+        for order in [RowOrder::Binary, RowOrder::Gray, RowOrder::OutputsFirst, custom] {
+            for filter in [None, Some(true)] {
+                let rows = TableRows::new(&func, Some(&dont_care), order.clone(), filter);
+                let outputs = (0..rows.num_rows()).map(|pos| rows.output(rows.minterm(pos))).collect::<Vec<_>>();
+                for pos in 0..outputs.len() {
+                    let start = (0..pos).rev().take_while(|&i| outputs[i] == outputs[pos]).count();
+                    let end = (pos..outputs.len()).take_while(|&i| outputs[i] == outputs[pos]).count();
+                    assert_eq!(((pos - start) as u64, (pos + end) as u64), rows.run(pos as u64));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rows_of_many_variables() {
+        let names = (0..TABLE_LIMIT).map(|i| format!("x{i:02}")).collect::<Vec<_>>();
+        let func = Function::parse(&format!("{} | x05", names.join(" & "))).unwrap();
+        let rows = TableRows::new(&func, None, RowOrder::Binary, None);
+        assert_eq!(1 << 63, rows.num_rows());
+        let block = 1 << (TABLE_LIMIT - 6);
+        assert_eq!(
+            vec![Line::Run { start: 0, end: block }, Line::Run { start: block, end: 2 * block }],
+            rows.lines(0, 2, true)
+        );
+        let last = (1 << 63) - 1;
+        assert_eq!(vec![Line::Run { start: last, end: 1 << 63 }], rows.lines(last, 2, true));
+        assert_eq!(last + 1 - block, rows.line_start(last, true));
+        assert_eq!(Some(true), rows.output(last));
+    }
+
+    #[test]
+    fn find_rows() {
+        let func = Function::parse("a & b | cin").unwrap();
+        assert_eq!(Some(5), find_minterm(func.vars(), " 5 "));
+        assert_eq!(None, find_minterm(func.vars(), "8"));
+        assert_eq!(Some(6), find_minterm(func.vars(), "a = 1, b = 1, cin = 0"));
+        assert_eq!(Some(1), find_minterm(func.vars(), "cin=1,a=0,b=0"));
+        assert_eq!(None, find_minterm(func.vars(), "a = 1, b = 1"));
+        assert_eq!(None, find_minterm(func.vars(), "a = 1, a = 1, cin = 0"));
+        assert_eq!(None, find_minterm(func.vars(), "a = 2, b = 1, cin = 0"));
+        assert_eq!(Some(vec![true, true, false]), find_assignment(func.vars(), "6"));
+
+        let names = (0..70).map(|i| format!("x{i:02}")).collect::<Vec<_>>();
+        let func = Function::parse(&names.join(" & ")).unwrap();
+        let values = find_assignment(func.vars(), "5").unwrap();
+        assert_eq!((70, vec![true, false, true]), (values.len(), values[67..].to_vec()));
+        assert!(values[..67].iter().all(|val| !val));
+        assert_eq!(Some(5), find_minterm(func.vars(), "5"));
+        let query = names.iter().map(|name| format!("{name} = 1")).collect::<Vec<_>>().join(", ");
+        assert_eq!(Some(vec![true; 70]), find_assignment(func.vars(), &query));
+        assert_eq!(None, find_minterm(func.vars(), &query));
+    }
+}